@@ -0,0 +1,707 @@
+mod output;
+mod rpc;
+mod tx;
+
+use std::path::PathBuf;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    token, token_2022, token_interface::TokenAccount,
+};
+use anyhow::{anyhow, bail};
+use clap::{Parser, Subcommand, ValueEnum};
+use neptune_client::{
+    accounts::{
+        decode, fetch, fetch_config, fetch_next_nonce, fetch_raw, fetch_unstaking,
+        fetch_unstakings, fetch_vault, fetch_vault_list,
+    },
+    instructions::{self, CreateVaultParams},
+    neptune::{
+        lock_voter::accounts::{Locker, PartialUnstaking},
+        state::{ConfigParams, FeePayout, LockPolicy, LstMetadata, Unstaking},
+    },
+    pda, quote, AccountSource, VaultAccounts,
+};
+use output::OutputFormat;
+use rpc::RpcClient;
+use serde_json::{json, Value};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use tx::Submit;
+
+const DEFAULT_SLIPPAGE_BPS: u16 = 50;
+
+#[derive(Parser)]
+#[command(
+    name = "neptune-cli",
+    version,
+    about = "Operate neptune vaults, amounts are in base units"
+)]
+struct Cli {
+    /// Json rpc endpoint
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        env = "NEPTUNE_RPC_URL",
+        default_value = "http://localhost:8899"
+    )]
+    url: String,
+    /// Fee payer and signer, defaults to ~/.config/solana/id.json
+    #[arg(long, short = 'k', global = true, env = "NEPTUNE_KEYPAIR")]
+    keypair: Option<PathBuf>,
+    /// Json for scripts
+    #[arg(long, global = true, value_enum, default_value = "display")]
+    output: OutputFormat,
+    /// Sign with the keypair only and print the transaction, never send
+    #[arg(long, global = true, requires = "blockhash")]
+    sign_only: bool,
+    /// Recent blockhash for --sign-only
+    #[arg(long, global = true)]
+    blockhash: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a vault on a WAGMI locker, owned by the keypair unless --owner
+    CreateVault {
+        locker: Pubkey,
+        #[arg(long)]
+        fees_bps: u16,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long, default_value = "")]
+        uri: String,
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        /// Token-2022 lst mint instead of spl token
+        #[arg(long)]
+        token_2022: bool,
+        #[arg(long, requires = "token_2022")]
+        transfer_hook_program: Option<Pubkey>,
+    },
+    /// Protocol admin, vault creators and fee limits
+    ShowConfig,
+    /// Protocol admin operations
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Totals, exchange rate and fees of a vault
+    ShowVault { vault: Pubkey },
+    /// Vaults over a WAGMI locker, in creation order
+    Vaults { locker: Pubkey },
+    /// Unstaking accounts of the keypair unless --owner
+    Unstakings {
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+    /// Stake utoken for lst
+    Stake {
+        vault: Pubkey,
+        amount: u64,
+        /// Max drop from the current quote, in bps
+        #[arg(long, default_value_t = DEFAULT_SLIPPAGE_BPS)]
+        slippage_bps: u16,
+    },
+    /// Escrow lst and start the WAGMI unstaking period
+    BeginUnstaking {
+        vault: Pubkey,
+        amount: u64,
+        /// Max drop from the current quote, in bps
+        #[arg(long, default_value_t = DEFAULT_SLIPPAGE_BPS)]
+        slippage_bps: u16,
+    },
+    /// Cancel an unstaking, lst is returned
+    MergeUnstaking {
+        unstaking: Pubkey,
+        /// Lst to cancel, the rest restarts its cooldown. All of it if omitted
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Withdraw utoken of an expired unstaking
+    WithdrawUnstake {
+        unstaking: Pubkey,
+        /// Lst to withdraw, the rest is staked back. All of it if omitted
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Vault owner operations
+    Admin {
+        vault: Pubkey,
+        #[command(subcommand)]
+        command: AdminCommand,
+    },
+    /// Add the keypair's signature to a --sign-only transaction
+    Sign { transaction: String },
+    /// Send a fully signed --sign-only transaction
+    Send { transaction: String },
+}
+
+#[derive(Subcommand)]
+enum AdminCommand {
+    TransferOwnership {
+        new_owner: Pubkey,
+    },
+    /// Signed by the pending owner
+    AcceptOwnership,
+    UpdateFees {
+        fees_bps: u16,
+    },
+    SetPaused {
+        #[arg(action = clap::ArgAction::Set)]
+        paused: bool,
+    },
+    ConfigureBuffer {
+        buffer_bps: u16,
+        instant_unstake_fee_bps: u16,
+    },
+    SetLockPolicy {
+        #[arg(value_enum)]
+        lock_policy: LockPolicyArg,
+        /// Seconds, duration policy only
+        #[arg(long)]
+        duration: Option<i64>,
+    },
+    /// Seconds
+    ExtendLockDuration {
+        duration: i64,
+    },
+    SetVoter {
+        voter: Pubkey,
+    },
+    SetVoteDelegate {
+        delegate: Pubkey,
+    },
+    UpdateLstMetadata {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long, default_value = "")]
+        uri: String,
+    },
+    ClaimFees {
+        /// Pay out of the buffer in utoken instead of minting lst
+        #[arg(long)]
+        utoken: bool,
+    },
+    ResolveDeficit,
+    /// List a vault created before vault lists, under its lst name
+    RegisterVault {
+        name: String,
+    },
+    /// Return stray tokens held by a vault token account, utoken is compounded
+    SweepEscrowDust {
+        source: Pubkey,
+        /// Ticket of an lst escrow source, dust goes back to its owner,
+        /// a closed ticket's escrow is burnt
+        #[arg(long)]
+        unstaking: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Signed by the program upgrade authority, once
+    Init {
+        admin: Pubkey,
+        /// Anyone may create vaults
+        #[arg(long)]
+        permissionless: bool,
+        #[arg(long)]
+        max_fees_bps: u16,
+        #[arg(long, default_value_t = 0)]
+        protocol_fee_bps: u16,
+        /// Owner of the protocol fee token accounts
+        #[arg(long)]
+        treasury: Pubkey,
+    },
+    /// Omitted limits are kept
+    Update {
+        #[arg(long)]
+        permissionless: Option<bool>,
+        #[arg(long)]
+        max_fees_bps: Option<u16>,
+        #[arg(long)]
+        protocol_fee_bps: Option<u16>,
+        #[arg(long)]
+        treasury: Option<Pubkey>,
+    },
+    /// Allow or disallow a vault creator
+    SetCreator {
+        creator: Pubkey,
+        #[arg(action = clap::ArgAction::Set)]
+        allowed: bool,
+    },
+    TransferAdmin {
+        new_admin: Pubkey,
+    },
+    /// Signed by the pending admin
+    AcceptAdmin,
+    /// Pay a vault's protocol fees to the treasury
+    ClaimProtocolFees {
+        vault: Pubkey,
+        /// Pay out of the buffer in utoken instead of minting lst
+        #[arg(long)]
+        utoken: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LockPolicyArg {
+    MaxLock,
+    Duration,
+}
+
+impl LockPolicyArg {
+    fn with_duration(self, duration: Option<i64>) -> anyhow::Result<LockPolicy> {
+        match (self, duration) {
+            (LockPolicyArg::MaxLock, None) => Ok(LockPolicy::MaxLock),
+            (LockPolicyArg::Duration, Some(duration)) => Ok(LockPolicy::Duration(duration)),
+            (LockPolicyArg::MaxLock, Some(_)) => bail!("--duration is for the duration policy"),
+            (LockPolicyArg::Duration, None) => bail!("duration policy needs --duration"),
+        }
+    }
+}
+
+fn read_keypair(path: Option<PathBuf>) -> anyhow::Result<Keypair> {
+    let path = match path {
+        Some(path) => path,
+        None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+    };
+    read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+}
+
+// dust of a live ticket goes back to its owner, a closed ticket's escrow is burnt,
+// utoken goes to the vault's reward ata, anything else to the vault owner,
+// the destination ata is opened if needed
+fn sweep_escrow_dust_ixs(
+    rpc: &RpcClient,
+    vault_accounts: &VaultAccounts,
+    payer: &Pubkey,
+    source: &Pubkey,
+    unstaking: Option<&Pubkey>,
+) -> anyhow::Result<Vec<Instruction>> {
+    let source_account = fetch_raw(rpc, source)?;
+    let token_program = source_account.owner;
+    let mint = decode::<TokenAccount>(source, &source_account.data)?.mint;
+
+    let destination_owner = match unstaking {
+        Some(unstaking) => match rpc.get_account(unstaking)? {
+            Some(account) => decode::<Unstaking>(unstaking, &account.data)?.owner,
+            None => {
+                return Ok(vec![vault_accounts.sweep_escrow_dust(
+                    &mint,
+                    &token_program,
+                    source,
+                    None,
+                    Some(unstaking),
+                )])
+            }
+        },
+        None if mint == vault_accounts.utoken_mint => vault_accounts.vault,
+        None => vault_accounts.owner,
+    };
+    let destination = pda::get_ata(&destination_owner, &mint, &token_program);
+
+    Ok(vec![
+        create_associated_token_account_idempotent(
+            payer,
+            &destination_owner,
+            &mint,
+            &token_program,
+        ),
+        vault_accounts.sweep_escrow_dust(
+            &mint,
+            &token_program,
+            source,
+            Some(&destination),
+            unstaking,
+        ),
+    ])
+}
+
+// owner instructions only sync the locker's list when the vault is in it
+fn is_listed(rpc: &RpcClient, vault_accounts: &VaultAccounts) -> anyhow::Result<bool> {
+    Ok(fetch_vault_list(rpc, &vault_accounts.locker)?
+        .vaults
+        .iter()
+        .any(|entry| entry.vault == vault_accounts.vault))
+}
+
+fn fee_payout(utoken: bool) -> FeePayout {
+    if utoken {
+        FeePayout::Utoken
+    } else {
+        FeePayout::Lst
+    }
+}
+
+// fees are paid to the `owner` ata of the payout mint, opened if needed
+fn fee_destination(
+    vault_accounts: &VaultAccounts,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    payout: FeePayout,
+) -> (Pubkey, Instruction) {
+    let (mint, token_program) = match payout {
+        FeePayout::Lst => (vault_accounts.lst_mint, vault_accounts.lst_token_program),
+        FeePayout::Utoken => (
+            vault_accounts.utoken_mint,
+            vault_accounts.utoken_token_program,
+        ),
+    };
+    (
+        pda::get_ata(owner, &mint, &token_program),
+        create_associated_token_account_idempotent(payer, owner, &mint, &token_program),
+    )
+}
+
+struct Context {
+    rpc: RpcClient,
+    submit: Submit,
+    keypair: Keypair,
+}
+
+impl Context {
+    fn process(&self, signers: &[&Keypair], ixs: &[Instruction]) -> anyhow::Result<Value> {
+        tx::process(&self.rpc, &self.submit, &self.keypair, signers, ixs)
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<Value> {
+    let submit = match (cli.sign_only, &cli.blockhash) {
+        (true, Some(blockhash)) => Submit::SignOnly(tx::parse_blockhash(blockhash)?),
+        (false, Some(_)) => bail!("--blockhash is only used with --sign-only"),
+        _ => Submit::Send,
+    };
+    let ctx = Context {
+        rpc: RpcClient::new(cli.url),
+        submit,
+        keypair: read_keypair(cli.keypair)?,
+    };
+    let user = ctx.keypair.pubkey();
+
+    match cli.command {
+        Command::CreateVault {
+            locker,
+            fees_bps,
+            name,
+            symbol,
+            uri,
+            owner,
+            token_2022,
+            transfer_hook_program,
+        } => {
+            let locker_state: Locker = fetch(&ctx.rpc, &locker)?;
+            let base = Keypair::new();
+            let vault = pda::find_vault(&base.pubkey()).0;
+            let ix = instructions::create_vault(CreateVaultParams {
+                payer: user,
+                base: base.pubkey(),
+                locker,
+                utoken_mint: locker_state.token_mint,
+                vault_owner: owner.unwrap_or(user),
+                lst_token_program: if token_2022 {
+                    token_2022::ID
+                } else {
+                    token::ID
+                },
+                fees_bps,
+                transfer_hook_program,
+                metadata: LstMetadata { name, symbol, uri },
+            });
+            let result = ctx.process(&[&base], &[ix])?;
+            Ok(output::merge(
+                json!({
+                    "vault": vault.to_string(),
+                    "lst_mint": pda::find_lst_mint(&vault).0.to_string(),
+                }),
+                result,
+            ))
+        }
+        Command::ShowConfig => Ok(output::config(
+            &pda::find_config().0,
+            &fetch_config(&ctx.rpc)?,
+        )),
+        Command::Config { command } => {
+            let ix = match command {
+                ConfigCommand::Init {
+                    admin,
+                    permissionless,
+                    max_fees_bps,
+                    protocol_fee_bps,
+                    treasury,
+                } => instructions::init_config(
+                    &user,
+                    &admin,
+                    ConfigParams {
+                        permissionless,
+                        max_fees_bps,
+                        protocol_fee_bps,
+                        treasury,
+                    },
+                ),
+                ConfigCommand::Update {
+                    permissionless,
+                    max_fees_bps,
+                    protocol_fee_bps,
+                    treasury,
+                } => {
+                    let config = fetch_config(&ctx.rpc)?;
+                    instructions::update_config(
+                        &user,
+                        ConfigParams {
+                            permissionless: permissionless.unwrap_or(config.permissionless),
+                            max_fees_bps: max_fees_bps.unwrap_or(config.max_fees_bps),
+                            protocol_fee_bps: protocol_fee_bps.unwrap_or(config.protocol_fee_bps),
+                            treasury: treasury.unwrap_or(config.treasury),
+                        },
+                    )
+                }
+                ConfigCommand::SetCreator { creator, allowed } => {
+                    instructions::set_creator(&user, &creator, allowed)
+                }
+                ConfigCommand::TransferAdmin { new_admin } => {
+                    instructions::transfer_config_admin(&user, &new_admin)
+                }
+                ConfigCommand::AcceptAdmin => instructions::accept_config_admin(&user),
+                ConfigCommand::ClaimProtocolFees { vault, utoken } => {
+                    let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &vault)?;
+                    let treasury = fetch_config(&ctx.rpc)?.treasury;
+                    let payout = fee_payout(utoken);
+                    let (destination, create_destination_ix) =
+                        fee_destination(&vault_accounts, &user, &treasury, payout);
+                    return ctx.process(
+                        &[],
+                        &[
+                            create_destination_ix,
+                            vault_accounts.claim_protocol_fees(&user, payout, &destination),
+                        ],
+                    );
+                }
+            };
+            ctx.process(&[], &[ix])
+        }
+        Command::ShowVault { vault } => output::vault(&vault, &fetch_vault(&ctx.rpc, &vault)?),
+        Command::Vaults { locker } => Ok(Value::Array(
+            fetch_vault_list(&ctx.rpc, &locker)?
+                .vaults
+                .iter()
+                .map(output::vault_entry)
+                .collect(),
+        )),
+        Command::Unstakings { owner } => {
+            let owner = owner.unwrap_or(user);
+            let unstakings = fetch_unstakings(&ctx.rpc, &owner)?
+                .iter()
+                .map(|(address, unstaking)| {
+                    let partial_unstaking: Option<PartialUnstaking> =
+                        match ctx.rpc.get_account(&unstaking.partial_unstaking)? {
+                            Some(account) => Some(neptune_client::accounts::decode(
+                                &unstaking.partial_unstaking,
+                                &account.data,
+                            )?),
+                            None => None,
+                        };
+                    Ok(output::unstaking(
+                        address,
+                        unstaking,
+                        partial_unstaking.as_ref(),
+                    ))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(Value::Array(unstakings))
+        }
+        Command::Stake {
+            vault,
+            amount,
+            slippage_bps,
+        } => {
+            let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &vault)?;
+            let vault_state = fetch_vault(&ctx.rpc, &vault)?;
+            let min_lst_out =
+                quote::min_out(quote::quote_stake(&vault_state, amount)?, slippage_bps);
+            let with_buffer = vault_state.buffer_bps > 0;
+            ctx.process(
+                &[],
+                &[vault_accounts.stake(&user, amount, min_lst_out, with_buffer)],
+            )
+        }
+        Command::BeginUnstaking {
+            vault,
+            amount,
+            slippage_bps,
+        } => {
+            let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &vault)?;
+            let min_utoken_out = quote::min_out(
+                quote::quote_unstake(&fetch_vault(&ctx.rpc, &vault)?, amount)?,
+                slippage_bps,
+            );
+            let nonce = fetch_next_nonce(&ctx.rpc, &vault, &user)?;
+            let ix = vault_accounts.begin_unstaking(&user, nonce, amount, min_utoken_out);
+            let result = ctx.process(&[], &[ix])?;
+            Ok(output::merge(
+                json!({
+                    "unstaking": pda::find_unstaking(&vault, &user, nonce).0.to_string(),
+                    "nonce": nonce,
+                }),
+                result,
+            ))
+        }
+        Command::MergeUnstaking { unstaking, amount } => {
+            let unstaking_state = fetch_unstaking(&ctx.rpc, &unstaking)?;
+            let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &unstaking_state.vault)?;
+            let ix = vault_accounts.merge_unstaking(
+                &user,
+                &unstaking,
+                &unstaking_state.partial_unstaking,
+                amount,
+            );
+            ctx.process(&[], &[ix])
+        }
+        Command::WithdrawUnstake { unstaking, amount } => {
+            let unstaking_state = fetch_unstaking(&ctx.rpc, &unstaking)?;
+            let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &unstaking_state.vault)?;
+            let ix = vault_accounts.withdraw_unstake(
+                &user,
+                &unstaking,
+                &unstaking_state.partial_unstaking,
+                amount,
+            );
+            ctx.process(&[], &[ix])
+        }
+        Command::Admin { vault, command } => {
+            let vault_accounts = VaultAccounts::fetch(&ctx.rpc, &vault)?;
+            let ix = match command {
+                AdminCommand::TransferOwnership { new_owner } => {
+                    vault_accounts.transfer_ownership(&new_owner)
+                }
+                AdminCommand::AcceptOwnership => vault_accounts.accept_ownership(&user),
+                AdminCommand::UpdateFees { fees_bps } => {
+                    vault_accounts.update_fees_bps(fees_bps, is_listed(&ctx.rpc, &vault_accounts)?)
+                }
+                AdminCommand::SetPaused { paused } => {
+                    vault_accounts.set_paused(paused, is_listed(&ctx.rpc, &vault_accounts)?)
+                }
+                AdminCommand::ConfigureBuffer {
+                    buffer_bps,
+                    instant_unstake_fee_bps,
+                } => vault_accounts.configure_buffer(buffer_bps, instant_unstake_fee_bps),
+                AdminCommand::SetLockPolicy {
+                    lock_policy,
+                    duration,
+                } => vault_accounts.set_lock_policy(lock_policy.with_duration(duration)?),
+                AdminCommand::ExtendLockDuration { duration } => {
+                    vault_accounts.extend_lock_duration(duration)
+                }
+                AdminCommand::SetVoter { voter } => vault_accounts.set_voter(&voter),
+                AdminCommand::SetVoteDelegate { delegate } => {
+                    vault_accounts.set_vote_delegate(&delegate)
+                }
+                AdminCommand::UpdateLstMetadata { name, symbol, uri } => vault_accounts
+                    .update_lst_metadata(
+                        LstMetadata { name, symbol, uri },
+                        is_listed(&ctx.rpc, &vault_accounts)?,
+                    ),
+                AdminCommand::ClaimFees { utoken } => {
+                    let payout = fee_payout(utoken);
+                    let (destination, create_destination_ix) =
+                        fee_destination(&vault_accounts, &user, &vault_accounts.owner, payout);
+                    return ctx.process(
+                        &[],
+                        &[
+                            create_destination_ix,
+                            vault_accounts.claim_owner_fees(payout, &destination),
+                        ],
+                    );
+                }
+                AdminCommand::ResolveDeficit => vault_accounts.resolve_deficit(),
+                AdminCommand::RegisterVault { name } => vault_accounts.register_vault(name),
+                AdminCommand::SweepEscrowDust { source, unstaking } => {
+                    let ixs = sweep_escrow_dust_ixs(
+                        &ctx.rpc,
+                        &vault_accounts,
+                        &user,
+                        &source,
+                        unstaking.as_ref(),
+                    )?;
+                    return ctx.process(&[], &ixs);
+                }
+            };
+            ctx.process(&[], &[ix])
+        }
+        Command::Sign { transaction } => {
+            let mut tx = tx::decode(&transaction)?;
+            tx::partial_sign(&mut tx, &[&ctx.keypair])?;
+            tx::sign_only_output(&tx)
+        }
+        Command::Send { transaction } => {
+            let tx = tx::decode(&transaction)?;
+            Ok(json!({ "signature": tx::send(&ctx.rpc, &tx)? }))
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.output;
+    match run(cli) {
+        Ok(value) => output::print(&value, format),
+        Err(err) => {
+            match format {
+                OutputFormat::Json => eprintln!("{}", json!({ "error": format!("{err:#}") })),
+                OutputFormat::Display => eprintln!("error: {err:#}"),
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let vault = Pubkey::new_unique();
+        let cli = Cli::try_parse_from([
+            "neptune-cli",
+            "admin",
+            &vault.to_string(),
+            "set-paused",
+            "true",
+            "--sign-only",
+            "--blockhash",
+            "11111111111111111111111111111111",
+            "--output",
+            "json",
+        ])
+        .unwrap();
+        assert!(cli.sign_only);
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(
+            cli.command,
+            Command::Admin { vault: v, command: AdminCommand::SetPaused { paused: true } } if v == vault
+        ));
+
+        // sign only needs a blockhash
+        assert!(Cli::try_parse_from([
+            "neptune-cli",
+            "show-vault",
+            &vault.to_string(),
+            "--sign-only"
+        ])
+        .is_err());
+    }
+}
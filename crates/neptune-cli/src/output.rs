@@ -0,0 +1,144 @@
+use anchor_lang::prelude::Pubkey;
+use clap::ValueEnum;
+use neptune_client::{
+    neptune::{
+        lock_voter::accounts::PartialUnstaking,
+        state::{Config, Unstaking, Vault, VaultEntry},
+    },
+    quote,
+};
+use serde_json::{json, Map, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Display,
+    Json,
+}
+
+pub fn print(value: &Value, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!("{value}"),
+        OutputFormat::Display => println!("{}", display(value)),
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => display_object(fields),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join("\n\n"),
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn display_object(fields: &Map<String, Value>) -> String {
+    let width = fields.keys().map(String::len).max().unwrap_or_default();
+    fields
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(string) => string.clone(),
+                Value::Array(items) if items.is_empty() => "-".to_string(),
+                Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+                value => value.to_string(),
+            };
+            format!("{key:width$}  {value}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// merges the fields of `extra` into `value`, both objects
+pub fn merge(mut value: Value, extra: Value) -> Value {
+    if let (Value::Object(fields), Value::Object(extra)) = (&mut value, extra) {
+        fields.extend(extra);
+    }
+    value
+}
+
+pub fn config(address: &Pubkey, config: &Config) -> Value {
+    json!({
+        "address": address.to_string(),
+        "admin": config.admin.to_string(),
+        "pending_admin": config.pending_admin.to_string(),
+        "permissionless": config.permissionless,
+        "max_fees_bps": config.max_fees_bps,
+        "protocol_fee_bps": config.protocol_fee_bps,
+        "treasury": config.treasury.to_string(),
+        "creators": config.creators.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+    })
+}
+
+pub fn vault(address: &Pubkey, vault: &Vault) -> anyhow::Result<Value> {
+    Ok(json!({
+        "address": address.to_string(),
+        "owner": vault.owner.to_string(),
+        "pending_owner": vault.pending_owner.to_string(),
+        "lst_mint": vault.lst_mint.to_string(),
+        "escrow": vault.escrow.to_string(),
+        "total_lst_minted": vault.total_lst_minted,
+        "total_utoken_staked": vault.total_utoken_staked,
+        "exchange_rate": quote::exchange_rate(vault)?,
+        "fees_bps": vault.fees_bps,
+        "accrued_fees_lst": vault.accrued_fees_lst,
+        "accrued_protocol_fees_lst": vault.accrued_protocol_fees_lst,
+        "buffer_bps": vault.buffer_bps,
+        "instant_unstake_fee_bps": vault.instant_unstake_fee_bps,
+        "buffer_utoken_amt": vault.buffer_utoken_amt,
+        "buffer_refill_amt": vault.buffer_refill_amt,
+        "pending_unstake_lst_amt": vault.pending_unstake_lst_amt,
+        "pending_unstake_utoken_amt": vault.pending_unstake_utoken_amt,
+        "lock_policy": format!("{:?}", vault.lock_policy),
+        "voter": vault.voter.to_string(),
+        "vote_delegate": vault.vote_delegate.to_string(),
+        "paused": vault.paused,
+        "in_deficit": vault.in_deficit,
+    }))
+}
+
+pub fn vault_entry(entry: &VaultEntry) -> Value {
+    json!({
+        "vault": entry.vault.to_string(),
+        "lst_mint": entry.lst_mint.to_string(),
+        "name": entry.name,
+        "fees_bps": entry.fees_bps,
+        "status": format!("{:?}", entry.status),
+    })
+}
+
+pub fn unstaking(
+    address: &Pubkey,
+    unstaking: &Unstaking,
+    partial_unstaking: Option<&PartialUnstaking>,
+) -> Value {
+    json!({
+        "address": address.to_string(),
+        "vault": unstaking.vault.to_string(),
+        "partial_unstaking": unstaking.partial_unstaking.to_string(),
+        "lst_amt": unstaking.lst_amt,
+        "utoken_amt": unstaking.utoken_amt,
+        // gone once merged or withdrawn
+        "expiration": partial_unstaking.map(|partial_unstaking| partial_unstaking.expiration),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let value = json!({ "absent": [], "address": "abc", "lst_amt": 42, "signers": ["a", "b"] });
+        assert_eq!(
+            display(&value),
+            "absent   -\naddress  abc\nlst_amt  42\nsigners  a, b"
+        );
+        assert_eq!(display(&json!([{ "a": 1 }, { "a": 2 }])), "a  1\n\na  2");
+    }
+
+    #[test]
+    fn test_merge() {
+        let value = merge(json!({ "vault": "abc" }), json!({ "signature": "xyz" }));
+        assert_eq!(value, json!({ "vault": "abc", "signature": "xyz" }));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{thread, time::Duration};
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use neptune_client::{AccountSource, ClientError, MemcmpFilter, RawAccount};
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+
+const CONFIRM_RETRIES: usize = 60;
+const CONFIRM_INTERVAL: Duration = Duration::from_millis(500);
+
+// minimal json rpc client, only what the cli needs
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    fn call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = ureq::post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} to {}", self.url))?
+            .into_json()?;
+        if let Some(error) = response.get("error") {
+            bail!("{method}: {error}");
+        }
+        Ok(response["result"].take())
+    }
+
+    pub fn latest_blockhash(&self) -> anyhow::Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getLatestBlockhash: no blockhash"))?
+            .parse()
+            .map_err(|_| anyhow!("getLatestBlockhash: invalid blockhash"))
+    }
+
+    pub fn send_and_confirm(&self, tx: &Transaction) -> anyhow::Result<String> {
+        let encoded = STANDARD.encode(bincode::serialize(tx)?);
+        let signature = self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| anyhow!("sendTransaction: no signature"))?
+            .to_string();
+
+        for _ in 0..CONFIRM_RETRIES {
+            let statuses = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &statuses["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {signature} failed: {}", status["err"]);
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(CONFIRM_INTERVAL);
+        }
+        bail!("transaction {signature} not confirmed")
+    }
+}
+
+fn parse_account(value: &Value) -> anyhow::Result<RawAccount> {
+    let data = value["data"][0]
+        .as_str()
+        .ok_or_else(|| anyhow!("account data is not base64"))?;
+    Ok(RawAccount {
+        lamports: value["lamports"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("account has no lamports"))?,
+        owner: value["owner"]
+            .as_str()
+            .ok_or_else(|| anyhow!("account has no owner"))?
+            .parse()?,
+        data: STANDARD.decode(data)?,
+    })
+}
+
+fn source_err(err: anyhow::Error) -> ClientError {
+    ClientError::Source(format!("{err:#}"))
+}
+
+impl AccountSource for RpcClient {
+    fn get_account(&self, key: &Pubkey) -> neptune_client::Result<Option<RawAccount>> {
+        let result = self
+            .call(
+                "getAccountInfo",
+                json!([key.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+            )
+            .map_err(source_err)?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => parse_account(value).map(Some).map_err(source_err),
+        }
+    }
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[MemcmpFilter],
+    ) -> neptune_client::Result<Vec<(Pubkey, RawAccount)>> {
+        let filters: Vec<Value> = filters
+            .iter()
+            .map(|filter| {
+                json!({ "memcmp": {
+                    "offset": filter.offset,
+                    "bytes": STANDARD.encode(&filter.bytes),
+                    "encoding": "base64",
+                } })
+            })
+            .collect();
+        let result = self
+            .call(
+                "getProgramAccounts",
+                json!([program_id.to_string(), {
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                    "filters": filters,
+                }]),
+            )
+            .map_err(source_err)?;
+
+        result
+            .as_array()
+            .ok_or_else(|| ClientError::Source("getProgramAccounts: not an array".to_string()))?
+            .iter()
+            .map(|keyed| {
+                let key = keyed["pubkey"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|_| ClientError::Source("invalid pubkey".to_string()))?;
+                Ok((key, parse_account(&keyed["account"]).map_err(source_err)?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_account() {
+        let owner = Pubkey::new_unique();
+        let value = json!({
+            "lamports": 42,
+            "owner": owner.to_string(),
+            "data": [STANDARD.encode([1, 2, 3]), "base64"],
+            "executable": false,
+        });
+        assert_eq!(
+            parse_account(&value).unwrap(),
+            RawAccount {
+                lamports: 42,
+                owner,
+                data: vec![1, 2, 3],
+            }
+        );
+
+        // jsonParsed data is not supported
+        let value = json!({ "lamports": 42, "owner": owner.to_string(), "data": {} });
+        assert!(parse_account(&value).is_err());
+    }
+}
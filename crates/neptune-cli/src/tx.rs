@@ -0,0 +1,124 @@
+use anchor_lang::solana_program::{hash::Hash, instruction::Instruction};
+use anyhow::{anyhow, bail};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::rpc::RpcClient;
+
+// how a built transaction leaves the cli
+pub enum Submit {
+    Send,
+    // sign with what we have, print the transaction for the other signers
+    SignOnly(Hash),
+}
+
+pub fn encode(tx: &Transaction) -> anyhow::Result<String> {
+    Ok(STANDARD.encode(bincode::serialize(tx)?))
+}
+
+pub fn decode(encoded: &str) -> anyhow::Result<Transaction> {
+    Ok(bincode::deserialize(&STANDARD.decode(encoded.trim())?)?)
+}
+
+// required signers without a signature yet
+fn absent_signers(tx: &Transaction) -> Vec<String> {
+    let num_signers = tx.message.header.num_required_signatures as usize;
+    tx.message.account_keys[..num_signers]
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Default::default())
+        .map(|(key, _)| key.to_string())
+        .collect()
+}
+
+pub fn sign_only_output(tx: &Transaction) -> anyhow::Result<Value> {
+    Ok(json!({
+        "blockhash": tx.message.recent_blockhash.to_string(),
+        "absent_signers": absent_signers(tx),
+        "transaction": encode(tx)?,
+    }))
+}
+
+// signs with every keypair that is a required signer, skips the others
+pub fn partial_sign(tx: &mut Transaction, signers: &[&Keypair]) -> anyhow::Result<()> {
+    let blockhash = tx.message.recent_blockhash;
+    let num_signers = tx.message.header.num_required_signatures as usize;
+    for signer in signers {
+        if let Some(index) = tx.message.account_keys[..num_signers]
+            .iter()
+            .position(|key| key == &signer.pubkey())
+        {
+            tx.try_partial_sign_unchecked(&[*signer], vec![index], blockhash)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn send(rpc: &RpcClient, tx: &Transaction) -> anyhow::Result<String> {
+    let absent = absent_signers(tx);
+    if !absent.is_empty() {
+        bail!("missing signatures from {}", absent.join(", "));
+    }
+    rpc.send_and_confirm(tx)
+}
+
+pub fn process(
+    rpc: &RpcClient,
+    submit: &Submit,
+    payer: &Keypair,
+    signers: &[&Keypair],
+    ixs: &[Instruction],
+) -> anyhow::Result<Value> {
+    let blockhash = match submit {
+        Submit::Send => rpc.latest_blockhash()?,
+        Submit::SignOnly(blockhash) => *blockhash,
+    };
+    let mut tx = Transaction::new_with_payer(ixs, Some(&payer.pubkey()));
+    tx.message.recent_blockhash = blockhash;
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    partial_sign(&mut tx, &all_signers)?;
+
+    match submit {
+        Submit::Send => Ok(json!({ "signature": send(rpc, &tx)? })),
+        Submit::SignOnly(_) => sign_only_output(&tx),
+    }
+}
+
+pub fn parse_blockhash(blockhash: &str) -> anyhow::Result<Hash> {
+    blockhash
+        .parse()
+        .map_err(|_| anyhow!("invalid blockhash {blockhash}"))
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::{prelude::Pubkey, solana_program::instruction::AccountMeta};
+
+    use super::*;
+
+    #[test]
+    fn test_partial_sign() {
+        let (payer, owner) = (Keypair::new(), Keypair::new());
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new_readonly(owner.pubkey(), true)],
+            data: vec![],
+        };
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+        tx.message.recent_blockhash = Hash::new_unique();
+
+        // a keypair that is not a signer is ignored
+        partial_sign(&mut tx, &[&payer, &Keypair::new()]).unwrap();
+        assert_eq!(absent_signers(&tx), vec![owner.pubkey().to_string()]);
+
+        // the owner signs the round tripped transaction offline
+        let mut tx = decode(&encode(&tx).unwrap()).unwrap();
+        partial_sign(&mut tx, &[&owner]).unwrap();
+        assert!(absent_signers(&tx).is_empty());
+        assert!(tx.is_signed());
+    }
+}
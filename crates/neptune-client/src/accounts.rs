@@ -0,0 +1,353 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use neptune::{
+    lock_voter::accounts::{Escrow, Locker},
+    state::{Config, Unstaking, UserState, Vault, VaultList},
+};
+
+use crate::{
+    error::{ClientError, Result},
+    pda,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawAccount {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+// `data` at `offset` must match `bytes`, as in getProgramAccounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemcmpFilter {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl MemcmpFilter {
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.get(self.offset..self.offset + self.bytes.len()) == Some(self.bytes.as_slice())
+    }
+}
+
+// rpc, bank or test backend the client reads accounts from
+pub trait AccountSource {
+    fn get_account(&self, key: &Pubkey) -> Result<Option<RawAccount>>;
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[MemcmpFilter],
+    ) -> Result<Vec<(Pubkey, RawAccount)>>;
+}
+
+pub fn decode<T: AccountDeserialize>(key: &Pubkey, data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|_| ClientError::InvalidAccountData(*key))
+}
+
+pub fn fetch_raw(source: &impl AccountSource, key: &Pubkey) -> Result<RawAccount> {
+    source
+        .get_account(key)?
+        .ok_or(ClientError::AccountNotFound(*key))
+}
+
+pub fn fetch<T: AccountDeserialize>(source: &impl AccountSource, key: &Pubkey) -> Result<T> {
+    decode(key, &fetch_raw(source, key)?.data)
+}
+
+pub fn fetch_vault(source: &impl AccountSource, vault: &Pubkey) -> Result<Vault> {
+    fetch(source, vault)
+}
+
+pub fn fetch_config(source: &impl AccountSource) -> Result<Config> {
+    fetch(source, &pda::find_config().0)
+}
+
+// every vault over `locker`, empty before its first vault
+pub fn fetch_vault_list(source: &impl AccountSource, locker: &Pubkey) -> Result<VaultList> {
+    let vault_list = pda::find_vault_list(locker).0;
+    match source.get_account(&vault_list)? {
+        Some(account) => decode(&vault_list, &account.data),
+        None => Ok(VaultList::default()),
+    }
+}
+
+pub fn fetch_unstaking(source: &impl AccountSource, unstaking: &Pubkey) -> Result<Unstaking> {
+    fetch(source, unstaking)
+}
+
+// Unstaking accounts of `owner`, any vault
+pub fn unstaking_filters(owner: &Pubkey) -> Vec<MemcmpFilter> {
+    vec![
+        MemcmpFilter {
+            offset: 0,
+            bytes: Unstaking::DISCRIMINATOR.to_vec(),
+        },
+        MemcmpFilter {
+            offset: Unstaking::DISCRIMINATOR.len(),
+            bytes: owner.to_bytes().to_vec(),
+        },
+    ]
+}
+
+pub fn fetch_unstakings(
+    source: &impl AccountSource,
+    owner: &Pubkey,
+) -> Result<Vec<(Pubkey, Unstaking)>> {
+    source
+        .get_program_accounts(&neptune::ID, &unstaking_filters(owner))?
+        .into_iter()
+        .map(|(key, account)| Ok((key, decode(&key, &account.data)?)))
+        .collect()
+}
+
+// nonce of the user's next ticket
+pub fn fetch_next_nonce(
+    source: &impl AccountSource,
+    vault: &Pubkey,
+    owner: &Pubkey,
+) -> Result<u64> {
+    let user_state = pda::find_user_state(vault, owner).0;
+    match source.get_account(&user_state)? {
+        Some(account) => Ok(decode::<UserState>(&user_state, &account.data)?.nonce),
+        None => Ok(0),
+    }
+}
+
+// pending tickets of `owner` in `vault`, derived from the UserState nonce
+pub fn fetch_vault_unstakings(
+    source: &impl AccountSource,
+    vault: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<(u64, Pubkey, Unstaking)>> {
+    let mut unstakings = vec![];
+    for nonce in 0..fetch_next_nonce(source, vault, owner)? {
+        let unstaking = pda::find_unstaking(vault, owner, nonce).0;
+        // closed once merged or withdrawn
+        if let Some(account) = source.get_account(&unstaking)? {
+            unstakings.push((nonce, unstaking, decode(&unstaking, &account.data)?));
+        }
+    }
+    Ok(unstakings)
+}
+
+// every account a vault instruction needs besides the user's
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaultAccounts {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub locker: Pubkey,
+    // WAGMI governor of the locker
+    pub governor: Pubkey,
+    pub escrow: Pubkey,
+    // WAGMI escrow token account
+    pub utoken_escrow_ata: Pubkey,
+    pub utoken_mint: Pubkey,
+    pub utoken_token_program: Pubkey,
+    pub lst_mint: Pubkey,
+    pub lst_token_program: Pubkey,
+}
+
+impl VaultAccounts {
+    pub fn fetch(source: &impl AccountSource, vault: &Pubkey) -> Result<Self> {
+        let vault_state = fetch_vault(source, vault)?;
+        let escrow: Escrow = fetch(source, &vault_state.escrow)?;
+        let locker: Locker = fetch(source, &escrow.locker)?;
+        let utoken_mint = fetch_raw(source, &locker.token_mint)?;
+        let lst_mint = fetch_raw(source, &vault_state.lst_mint)?;
+
+        Ok(Self {
+            vault: *vault,
+            owner: vault_state.owner,
+            locker: escrow.locker,
+            governor: locker.governor,
+            escrow: vault_state.escrow,
+            utoken_escrow_ata: escrow.tokens,
+            utoken_mint: locker.token_mint,
+            utoken_token_program: utoken_mint.owner,
+            lst_mint: vault_state.lst_mint,
+            lst_token_program: lst_mint.owner,
+        })
+    }
+
+    pub fn lst_ata(&self, owner: &Pubkey) -> Pubkey {
+        pda::get_ata(owner, &self.lst_mint, &self.lst_token_program)
+    }
+
+    pub fn utoken_ata(&self, owner: &Pubkey) -> Pubkey {
+        pda::get_ata(owner, &self.utoken_mint, &self.utoken_token_program)
+    }
+
+    pub fn utoken_buffer_ata(&self) -> Pubkey {
+        pda::find_utoken_buffer(&self.vault).0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use anchor_lang::AccountSerialize;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MapSource(HashMap<Pubkey, RawAccount>);
+
+    impl MapSource {
+        fn insert<T: AccountSerialize>(&mut self, key: Pubkey, owner: Pubkey, value: &T) {
+            let mut data = vec![];
+            value.try_serialize(&mut data).unwrap();
+            self.0.insert(
+                key,
+                RawAccount {
+                    lamports: 1,
+                    owner,
+                    data,
+                },
+            );
+        }
+    }
+
+    impl AccountSource for MapSource {
+        fn get_account(&self, key: &Pubkey) -> Result<Option<RawAccount>> {
+            Ok(self.0.get(key).cloned())
+        }
+
+        fn get_program_accounts(
+            &self,
+            program_id: &Pubkey,
+            filters: &[MemcmpFilter],
+        ) -> Result<Vec<(Pubkey, RawAccount)>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|(_, account)| {
+                    &account.owner == program_id
+                        && filters.iter().all(|filter| filter.matches(&account.data))
+                })
+                .map(|(key, account)| (*key, account.clone()))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_fetch_unstakings() {
+        let mut source = MapSource::default();
+        let owner = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        for lst_amt in [10, 20] {
+            source.insert(
+                Pubkey::new_unique(),
+                neptune::ID,
+                &Unstaking {
+                    owner,
+                    vault,
+                    lst_amt,
+                    ..Default::default()
+                },
+            );
+        }
+        // someone else's
+        source.insert(
+            Pubkey::new_unique(),
+            neptune::ID,
+            &Unstaking {
+                owner: Pubkey::new_unique(),
+                vault,
+                ..Default::default()
+            },
+        );
+
+        let mut unstakings = fetch_unstakings(&source, &owner).unwrap();
+        unstakings.sort_by_key(|(_, unstaking)| unstaking.lst_amt);
+        assert_eq!(unstakings.len(), 2);
+        assert_eq!(unstakings[0].1.lst_amt, 10);
+        assert_eq!(unstakings[1].1.lst_amt, 20);
+        assert!(unstakings.iter().all(|(_, u)| u.owner == owner));
+    }
+
+    #[test]
+    fn test_fetch_vault_unstakings() {
+        let mut source = MapSource::default();
+        let (vault, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(fetch_next_nonce(&source, &vault, &owner).unwrap(), 0);
+        assert!(fetch_vault_unstakings(&source, &vault, &owner)
+            .unwrap()
+            .is_empty());
+
+        source.insert(
+            pda::find_user_state(&vault, &owner).0,
+            neptune::ID,
+            &UserState {
+                owner,
+                vault,
+                nonce: 3,
+                bump: 0,
+            },
+        );
+        // ticket 1 was withdrawn
+        for nonce in [0, 2] {
+            source.insert(
+                pda::find_unstaking(&vault, &owner, nonce).0,
+                neptune::ID,
+                &Unstaking {
+                    owner,
+                    vault,
+                    lst_amt: nonce,
+                    ..Default::default()
+                },
+            );
+        }
+
+        assert_eq!(fetch_next_nonce(&source, &vault, &owner).unwrap(), 3);
+        let unstakings = fetch_vault_unstakings(&source, &vault, &owner).unwrap();
+        assert_eq!(
+            unstakings
+                .iter()
+                .map(|(nonce, key, unstaking)| (*nonce, *key, unstaking.lst_amt))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, pda::find_unstaking(&vault, &owner, 0).0, 0),
+                (2, pda::find_unstaking(&vault, &owner, 2).0, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_vault_list() {
+        let mut source = MapSource::default();
+        let locker = Pubkey::new_unique();
+        // no vault yet
+        assert!(fetch_vault_list(&source, &locker)
+            .unwrap()
+            .vaults
+            .is_empty());
+
+        let vault_list = VaultList {
+            locker,
+            bump: pda::find_vault_list(&locker).1,
+            vaults: vec![Default::default(); 2],
+        };
+        source.insert(pda::find_vault_list(&locker).0, neptune::ID, &vault_list);
+        assert_eq!(fetch_vault_list(&source, &locker).unwrap().vaults.len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let mut source = MapSource::default();
+        let missing = Pubkey::new_unique();
+        assert!(matches!(
+            fetch_vault(&source, &missing),
+            Err(ClientError::AccountNotFound(key)) if key == missing
+        ));
+
+        // an Unstaking is not a Vault
+        let unstaking = Pubkey::new_unique();
+        source.insert(unstaking, neptune::ID, &Unstaking::default());
+        assert!(matches!(
+            fetch_vault(&source, &unstaking),
+            Err(ClientError::InvalidAccountData(key)) if key == unstaking
+        ));
+        assert!(fetch_unstaking(&source, &unstaking).is_ok());
+    }
+}
@@ -0,0 +1,16 @@
+use anchor_lang::prelude::Pubkey;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} could not be decoded")]
+    InvalidAccountData(Pubkey),
+    #[error("account source: {0}")]
+    Source(String),
+    #[error(transparent)]
+    Program(#[from] anchor_lang::error::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
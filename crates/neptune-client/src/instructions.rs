@@ -0,0 +1,926 @@
+// one builder per neptune entry point,
+// lst transfer hook accounts, if any, are appended by the caller
+use anchor_lang::{
+    prelude::*, solana_program::instruction::Instruction, system_program, InstructionData,
+};
+use anchor_spl::{associated_token, metadata::mpl_token_metadata, token_2022};
+use neptune::{
+    accounts, govern, instruction, lock_voter,
+    state::{ConfigParams, FeePayout, LockPolicy, LstMetadata},
+};
+
+use crate::{accounts::VaultAccounts, pda};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: neptune::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// metaplex accounts are only passed for spl lst mints
+fn metaplex_accounts(
+    lst_mint: &Pubkey,
+    lst_token_program: &Pubkey,
+) -> (Option<Pubkey>, Option<Pubkey>) {
+    if lst_token_program == &token_2022::ID {
+        (None, None)
+    } else {
+        (
+            Some(pda::find_lst_metadata(lst_mint).0),
+            Some(mpl_token_metadata::ID),
+        )
+    }
+}
+
+// signed by the program upgrade authority
+pub fn init_config(
+    upgrade_authority: &Pubkey,
+    admin: &Pubkey,
+    params: ConfigParams,
+) -> Instruction {
+    build(
+        accounts::InitConfig {
+            upgrade_authority: *upgrade_authority,
+            config: pda::find_config().0,
+            program: neptune::ID,
+            program_data: pda::find_program_data().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitConfig {
+            admin: *admin,
+            params,
+        },
+    )
+}
+
+fn update_config_accounts(admin: &Pubkey) -> accounts::UpdateConfig {
+    accounts::UpdateConfig {
+        admin: *admin,
+        config: pda::find_config().0,
+    }
+}
+
+pub fn update_config(admin: &Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        update_config_accounts(admin),
+        instruction::UpdateConfig { params },
+    )
+}
+
+pub fn set_creator(admin: &Pubkey, creator: &Pubkey, allowed: bool) -> Instruction {
+    build(
+        update_config_accounts(admin),
+        instruction::SetCreator {
+            creator: *creator,
+            allowed,
+        },
+    )
+}
+
+pub fn transfer_config_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        update_config_accounts(admin),
+        instruction::TransferConfigAdmin {
+            new_admin: *new_admin,
+        },
+    )
+}
+
+pub fn accept_config_admin(pending_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptConfigAdmin {
+            pending_admin: *pending_admin,
+            config: pda::find_config().0,
+        },
+        instruction::AcceptConfigAdmin {},
+    )
+}
+
+pub struct CreateVaultParams {
+    pub payer: Pubkey,
+    // fresh keypair, signs and derives the vault
+    pub base: Pubkey,
+    pub locker: Pubkey,
+    pub utoken_mint: Pubkey,
+    pub vault_owner: Pubkey,
+    pub lst_token_program: Pubkey,
+    pub fees_bps: u16,
+    pub transfer_hook_program: Option<Pubkey>,
+    pub metadata: LstMetadata,
+}
+
+pub fn create_vault(params: CreateVaultParams) -> Instruction {
+    let vault = pda::find_vault(&params.base).0;
+    let lst_mint = pda::find_lst_mint(&vault).0;
+    let (lst_metadata, metadata_program) = metaplex_accounts(&lst_mint, &params.lst_token_program);
+
+    build(
+        accounts::CreateVault {
+            signer: params.payer,
+            config: pda::find_config().0,
+            base: params.base,
+            vault,
+            lst_mint,
+            utoken_mint: params.utoken_mint,
+            lst_metadata,
+            locker: params.locker,
+            escrow: pda::find_escrow(&params.locker, &vault).0,
+            vault_list: pda::find_vault_list(&params.locker).0,
+            vault_owner: params.vault_owner,
+            locked_voter: lock_voter::ID,
+            lst_token_program: params.lst_token_program,
+            metadata_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateVault {
+            fees_bps: params.fees_bps,
+            transfer_hook_program: params.transfer_hook_program,
+            metadata: params.metadata,
+        },
+    )
+}
+
+// view, simulate and decode the VaultPage return data
+pub fn get_vaults(locker: &Pubkey, offset: u32, limit: u8) -> Instruction {
+    build(
+        accounts::GetVaults {
+            vault_list: pda::find_vault_list(locker).0,
+        },
+        instruction::GetVaults { offset, limit },
+    )
+}
+
+// vault may predate the current layout, so it is not decoded,
+// `base` is the key it was derived from
+pub fn migrate_vault(owner: &Pubkey, vault: &Pubkey, base: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateVault {
+            owner: *owner,
+            vault: *vault,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateVault { base: *base },
+    )
+}
+
+impl VaultAccounts {
+    // `with_buffer` once the owner configured a buffer
+    pub fn stake(
+        &self,
+        user: &Pubkey,
+        amount: u64,
+        min_lst_out: u64,
+        with_buffer: bool,
+    ) -> Instruction {
+        build(
+            accounts::Stake {
+                signer: *user,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                lst_mint: self.lst_mint,
+                utoken_mint: self.utoken_mint,
+                utoken_source_ata: self.utoken_ata(user),
+                lst_ata: self.lst_ata(user),
+                utoken_buffer_ata: with_buffer.then(|| self.utoken_buffer_ata()),
+                locked_voter: lock_voter::ID,
+                associated_token_program: associated_token::ID,
+                token_program: self.utoken_token_program,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::Stake {
+                amount,
+                min_lst_out,
+            },
+        )
+    }
+
+    // `nonce` is UserState::nonce, 0 for the user's first ticket
+    pub fn begin_unstaking(
+        &self,
+        user: &Pubkey,
+        nonce: u64,
+        amount: u64,
+        min_utoken_out: u64,
+    ) -> Instruction {
+        let unstaking = pda::find_unstaking(&self.vault, user, nonce).0;
+        build(
+            accounts::BeginUnstaking {
+                signer: *user,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                lst_mint: self.lst_mint,
+                user_state: pda::find_user_state(&self.vault, user).0,
+                unstaking,
+                partial_unstaking: pda::find_partial_unstaking(&unstaking).0,
+                lst_source_ata: self.lst_ata(user),
+                lst_escrow_ata: pda::find_unstaking_escrow_ata(&unstaking).0,
+                locked_voter: lock_voter::ID,
+                associated_token_program: associated_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::BeginUnstaking {
+                amount,
+                min_utoken_out,
+            },
+        )
+    }
+
+    // `amount` of lst out of the ticket, all of it if None
+    pub fn merge_unstaking(
+        &self,
+        user: &Pubkey,
+        unstaking: &Pubkey,
+        partial_unstaking: &Pubkey,
+        amount: Option<u64>,
+    ) -> Instruction {
+        let accounts = accounts::MergeUnstake {
+            signer: *user,
+            locker: self.locker,
+            escrow: self.escrow,
+            vault: self.vault,
+            partial_unstaking: *partial_unstaking,
+            lst_mint: self.lst_mint,
+            lst_ata: self.lst_ata(user),
+            unstaking: *unstaking,
+            lst_escrow_ata: pda::find_unstaking_escrow_ata(unstaking).0,
+            locked_voter: lock_voter::ID,
+            associated_token_program: associated_token::ID,
+            lst_token_program: self.lst_token_program,
+            system_program: system_program::ID,
+        };
+        match amount {
+            Some(amount) => build(accounts, instruction::PartialMergeUnstaking { amount }),
+            None => build(accounts, instruction::MergeUnstaking {}),
+        }
+    }
+
+    // `amount` of lst out of the ticket, all of it if None,
+    // the rest is staked back
+    pub fn withdraw_unstake(
+        &self,
+        user: &Pubkey,
+        unstaking: &Pubkey,
+        partial_unstaking: &Pubkey,
+        amount: Option<u64>,
+    ) -> Instruction {
+        let accounts = accounts::WithdrawUnstake {
+            signer: *user,
+            locker: self.locker,
+            escrow: self.escrow,
+            vault: self.vault,
+            partial_unstaking: *partial_unstaking,
+            lst_mint: self.lst_mint,
+            unstaking: *unstaking,
+            lst_escrow_ata: pda::find_unstaking_escrow_ata(unstaking).0,
+            lst_ata: self.lst_ata(user),
+            utoken_mint: self.utoken_mint,
+            utoken_target_ata: self.utoken_ata(user),
+            utoken_escrow_ata: self.utoken_escrow_ata,
+            locked_voter: lock_voter::ID,
+            associated_token_program: associated_token::ID,
+            token_program: self.utoken_token_program,
+            lst_token_program: self.lst_token_program,
+            system_program: system_program::ID,
+        };
+        match amount {
+            Some(amount) => build(accounts, instruction::PartialWithdrawUnstake { amount }),
+            None => build(accounts, instruction::WithdrawUnstake {}),
+        }
+    }
+
+    pub fn configure_buffer(&self, buffer_bps: u16, instant_unstake_fee_bps: u16) -> Instruction {
+        build(
+            accounts::ConfigureBuffer {
+                owner: self.owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                token_program: self.utoken_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::ConfigureBuffer {
+                buffer_bps,
+                instant_unstake_fee_bps,
+            },
+        )
+    }
+
+    pub fn instant_unstake(&self, user: &Pubkey, amount: u64) -> Instruction {
+        build(
+            accounts::InstantUnstake {
+                signer: *user,
+                vault: self.vault,
+                config: pda::find_config().0,
+                lst_mint: self.lst_mint,
+                lst_source_ata: self.lst_ata(user),
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                utoken_target_ata: self.utoken_ata(user),
+                associated_token_program: associated_token::ID,
+                token_program: self.utoken_token_program,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::InstantUnstake { amount },
+        )
+    }
+
+    pub fn refill_buffer(&self, signer: &Pubkey) -> Instruction {
+        build(
+            accounts::RefillBuffer {
+                signer: *signer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                buffer_refill: pda::find_buffer_refill(&self.vault).0,
+                locked_voter: lock_voter::ID,
+                system_program: system_program::ID,
+            },
+            instruction::RefillBuffer {},
+        )
+    }
+
+    pub fn withdraw_buffer_refill(&self, signer: &Pubkey) -> Instruction {
+        build(
+            accounts::WithdrawBufferRefill {
+                signer: *signer,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                buffer_refill: pda::find_buffer_refill(&self.vault).0,
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                locked_voter: lock_voter::ID,
+                token_program: self.utoken_token_program,
+            },
+            instruction::WithdrawBufferRefill {},
+        )
+    }
+
+    pub fn compound(&self, signer: &Pubkey) -> Instruction {
+        build(
+            accounts::Compound {
+                signer: *signer,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                lst_mint: self.lst_mint,
+                config: pda::find_config().0,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                utoken_reward_ata: self.utoken_ata(&self.vault),
+                locked_voter: lock_voter::ID,
+                associated_token_program: associated_token::ID,
+                token_program: self.utoken_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::Compound {},
+        )
+    }
+
+    pub fn sync_vault(&self, signer: &Pubkey) -> Instruction {
+        build(
+            accounts::SyncVault {
+                signer: *signer,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                lst_mint: self.lst_mint,
+                config: pda::find_config().0,
+            },
+            instruction::SyncVault {},
+        )
+    }
+
+    pub fn resolve_deficit(&self) -> Instruction {
+        build(
+            accounts::ResolveDeficit {
+                owner: self.owner,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+            },
+            instruction::ResolveDeficit {},
+        )
+    }
+
+    pub fn transfer_ownership(&self, new_owner: &Pubkey) -> Instruction {
+        build(
+            accounts::TransferOwnership {
+                owner: self.owner,
+                vault: self.vault,
+            },
+            instruction::TransferOwnership {
+                new_owner: *new_owner,
+            },
+        )
+    }
+
+    pub fn accept_ownership(&self, pending_owner: &Pubkey) -> Instruction {
+        build(
+            accounts::AcceptOwnership {
+                pending_owner: *pending_owner,
+                vault: self.vault,
+            },
+            instruction::AcceptOwnership {},
+        )
+    }
+
+    fn vault_list(&self) -> Pubkey {
+        pda::find_vault_list(&self.locker).0
+    }
+
+    // vaults from before vault lists, listed under `name`
+    pub fn register_vault(&self, name: String) -> Instruction {
+        build(
+            accounts::RegisterVault {
+                owner: self.owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault_list: self.vault_list(),
+                system_program: system_program::ID,
+            },
+            instruction::RegisterVault { name },
+        )
+    }
+
+    // `listed` syncs the vault's entry in the locker's list
+    pub fn update_fees_bps(&self, fees_bps: u16, listed: bool) -> Instruction {
+        build(
+            accounts::UpdateFeesBps {
+                owner: self.owner,
+                vault: self.vault,
+                vault_list: listed.then(|| self.vault_list()),
+                config: pda::find_config().0,
+            },
+            instruction::UpdateFeesBps { fees_bps },
+        )
+    }
+
+    pub fn update_lst_metadata(&self, metadata: LstMetadata, listed: bool) -> Instruction {
+        let (lst_metadata, metadata_program) =
+            metaplex_accounts(&self.lst_mint, &self.lst_token_program);
+        build(
+            accounts::UpdateLstMetadata {
+                owner: self.owner,
+                vault: self.vault,
+                vault_list: listed.then(|| self.vault_list()),
+                lst_mint: self.lst_mint,
+                lst_metadata,
+                lst_token_program: self.lst_token_program,
+                metadata_program,
+                system_program: system_program::ID,
+            },
+            instruction::UpdateLstMetadata { metadata },
+        )
+    }
+
+    pub fn set_paused(&self, paused: bool, listed: bool) -> Instruction {
+        build(
+            accounts::SetPaused {
+                owner: self.owner,
+                vault: self.vault,
+                vault_list: listed.then(|| self.vault_list()),
+            },
+            instruction::SetPaused { paused },
+        )
+    }
+
+    pub fn set_lock_policy(&self, lock_policy: LockPolicy) -> Instruction {
+        build(
+            accounts::SetLockPolicy {
+                owner: self.owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: lock_voter::ID,
+            },
+            instruction::SetLockPolicy { lock_policy },
+        )
+    }
+
+    pub fn extend_lock_duration(&self, duration: i64) -> Instruction {
+        build(
+            accounts::ExtendLockDuration {
+                owner: self.owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: lock_voter::ID,
+            },
+            instruction::ExtendLockDuration { duration },
+        )
+    }
+
+    pub fn enforce_lock_policy(&self, signer: &Pubkey) -> Instruction {
+        build(
+            accounts::EnforceLockPolicy {
+                signer: *signer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: lock_voter::ID,
+            },
+            instruction::EnforceLockPolicy {},
+        )
+    }
+
+    pub fn set_voter(&self, new_voter: &Pubkey) -> Instruction {
+        build(
+            accounts::SetVoter {
+                owner: self.owner,
+                vault: self.vault,
+            },
+            instruction::SetVoter {
+                new_voter: *new_voter,
+            },
+        )
+    }
+
+    pub fn cast_vote(&self, voter: &Pubkey, proposal: &Pubkey, side: u8) -> Instruction {
+        build(
+            accounts::CastVote {
+                voter: *voter,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                governor: self.governor,
+                proposal: *proposal,
+                vote: pda::find_governance_vote(proposal, &self.vault).0,
+                vault_proposal: pda::find_vault_proposal(&self.vault, proposal).0,
+                locked_voter: lock_voter::ID,
+                govern_program: govern::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CastVote { side },
+        )
+    }
+
+    pub fn set_vote_delegate(&self, new_delegate: &Pubkey) -> Instruction {
+        build(
+            accounts::SetVoteDelegate {
+                owner: self.owner,
+                vault: self.vault,
+                escrow: self.escrow,
+                locked_voter: lock_voter::ID,
+            },
+            instruction::SetVoteDelegate {
+                new_delegate: *new_delegate,
+            },
+        )
+    }
+
+    pub fn create_vault_proposal(&self, signer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CreateVaultProposal {
+                signer: *signer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                proposal: *proposal,
+                vault_proposal: pda::find_vault_proposal(&self.vault, proposal).0,
+                system_program: system_program::ID,
+            },
+            instruction::CreateVaultProposal {},
+        )
+    }
+
+    pub fn cast_lst_vote(
+        &self,
+        user: &Pubkey,
+        proposal: &Pubkey,
+        side: u8,
+        amount: u64,
+    ) -> Instruction {
+        let vault_proposal = pda::find_vault_proposal(&self.vault, proposal).0;
+        let lst_vote = pda::find_lst_vote(&vault_proposal, user).0;
+        build(
+            accounts::CastLstVote {
+                signer: *user,
+                vault: self.vault,
+                vault_proposal,
+                lst_mint: self.lst_mint,
+                lst_source_ata: self.lst_ata(user),
+                lst_vote,
+                lst_vote_escrow_ata: pda::find_lst_vote_escrow_ata(&lst_vote).0,
+                associated_token_program: associated_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::CastLstVote { side, amount },
+        )
+    }
+
+    pub fn withdraw_lst_vote(&self, user: &Pubkey, proposal: &Pubkey) -> Instruction {
+        let vault_proposal = pda::find_vault_proposal(&self.vault, proposal).0;
+        let lst_vote = pda::find_lst_vote(&vault_proposal, user).0;
+        build(
+            accounts::WithdrawLstVote {
+                signer: *user,
+                vault: self.vault,
+                vault_proposal,
+                lst_mint: self.lst_mint,
+                lst_vote,
+                lst_vote_escrow_ata: pda::find_lst_vote_escrow_ata(&lst_vote).0,
+                lst_ata: self.lst_ata(user),
+                associated_token_program: associated_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            },
+            instruction::WithdrawLstVote {},
+        )
+    }
+
+    pub fn cast_vault_vote(&self, signer: &Pubkey, proposal: &Pubkey) -> Instruction {
+        build(
+            accounts::CastVaultVote {
+                signer: *signer,
+                vault: self.vault,
+                vault_proposal: pda::find_vault_proposal(&self.vault, proposal).0,
+                locker: self.locker,
+                escrow: self.escrow,
+                governor: self.governor,
+                proposal: *proposal,
+                vote: pda::find_governance_vote(proposal, &self.vault).0,
+                locked_voter: lock_voter::ID,
+                govern_program: govern::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CastVaultVote {},
+        )
+    }
+
+    // `destination` holds lst or utoken following the payout
+    fn claim_fees_accounts(
+        &self,
+        authority: &Pubkey,
+        payout: FeePayout,
+        destination: &Pubkey,
+    ) -> accounts::ClaimFees {
+        accounts::ClaimFees {
+            authority: *authority,
+            config: pda::find_config().0,
+            vault: self.vault,
+            lst_mint: self.lst_mint,
+            utoken_mint: self.utoken_mint,
+            utoken_buffer_ata: (payout == FeePayout::Utoken).then(|| self.utoken_buffer_ata()),
+            destination: *destination,
+            token_program: self.utoken_token_program,
+            lst_token_program: self.lst_token_program,
+        }
+    }
+
+    pub fn claim_owner_fees(&self, payout: FeePayout, destination: &Pubkey) -> Instruction {
+        build(
+            self.claim_fees_accounts(&self.owner, payout, destination),
+            instruction::ClaimOwnerFees { payout },
+        )
+    }
+
+    // signed by the config admin, `destination` must be owned by the treasury
+    pub fn claim_protocol_fees(
+        &self,
+        admin: &Pubkey,
+        payout: FeePayout,
+        destination: &Pubkey,
+    ) -> Instruction {
+        build(
+            self.claim_fees_accounts(admin, payout, destination),
+            instruction::ClaimProtocolFees { payout },
+        )
+    }
+
+    // stray tokens of a vault owned `source`, lst only from a ticket escrow
+    // with its `unstaking`, no destination burns a closed ticket's escrow
+    pub fn sweep_escrow_dust(
+        &self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        source: &Pubkey,
+        destination: Option<&Pubkey>,
+        unstaking: Option<&Pubkey>,
+    ) -> Instruction {
+        build(
+            accounts::SweepEscrowDust {
+                owner: self.owner,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                mint: *mint,
+                source: *source,
+                destination: destination.copied(),
+                unstaking: unstaking.copied(),
+                token_program: *token_program,
+            },
+            instruction::SweepEscrowDust {},
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_spl::token;
+
+    use super::*;
+
+    fn vault_accounts(lst_token_program: Pubkey) -> VaultAccounts {
+        let vault = pda::find_vault(&Pubkey::new_unique()).0;
+        let locker = Pubkey::new_unique();
+        let escrow = pda::find_escrow(&locker, &vault).0;
+        let utoken_mint = Pubkey::new_unique();
+        VaultAccounts {
+            vault,
+            owner: Pubkey::new_unique(),
+            locker,
+            governor: Pubkey::new_unique(),
+            escrow,
+            utoken_escrow_ata: pda::get_ata(&escrow, &utoken_mint, &token::ID),
+            utoken_mint,
+            utoken_token_program: token::ID,
+            lst_mint: pda::find_lst_mint(&vault).0,
+            lst_token_program,
+        }
+    }
+
+    #[test]
+    fn test_stake() {
+        let vault_accounts = vault_accounts(token_2022::ID);
+        let user = Pubkey::new_unique();
+
+        let ix = vault_accounts.stake(&user, 42, 40, false);
+        assert_eq!(ix.program_id, neptune::ID);
+        assert_eq!(
+            ix.data,
+            instruction::Stake {
+                amount: 42,
+                min_lst_out: 40
+            }
+            .data()
+        );
+        // user signs, lst ata under the lst program
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == user);
+        assert_eq!(
+            ix.accounts[8].pubkey,
+            pda::get_ata(&user, &vault_accounts.lst_mint, &token_2022::ID)
+        );
+        // missing buffer is the program id
+        assert_eq!(ix.accounts[9].pubkey, neptune::ID);
+
+        let ix = vault_accounts.stake(&user, 42, 40, true);
+        assert_eq!(ix.accounts[9].pubkey, vault_accounts.utoken_buffer_ata());
+        assert!(ix.accounts[9].is_writable);
+    }
+
+    #[test]
+    fn test_begin_unstaking() {
+        let vault_accounts = vault_accounts(token::ID);
+        let user = Pubkey::new_unique();
+        let unstaking = pda::find_unstaking(&vault_accounts.vault, &user, 3).0;
+
+        let ix = vault_accounts.begin_unstaking(&user, 3, 7, 0);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert!(keys.contains(&pda::find_user_state(&vault_accounts.vault, &user).0));
+        assert!(keys.contains(&unstaking));
+        assert!(keys.contains(&pda::find_partial_unstaking(&unstaking).0));
+        assert!(keys.contains(&pda::find_unstaking_escrow_ata(&unstaking).0));
+        assert!(keys.contains(&vault_accounts.escrow));
+        // only the user signs
+        assert_eq!(ix.accounts.iter().filter(|meta| meta.is_signer).count(), 1);
+    }
+
+    #[test]
+    fn test_set_paused() {
+        let vault_accounts = vault_accounts(token::ID);
+
+        let ix = vault_accounts.set_paused(true, true);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            pda::find_vault_list(&vault_accounts.locker).0
+        );
+        assert!(ix.accounts[2].is_writable);
+
+        // unlisted vault, no list is the program id
+        let ix = vault_accounts.set_paused(true, false);
+        assert_eq!(ix.accounts[2].pubkey, neptune::ID);
+    }
+
+    #[test]
+    fn test_sweep_escrow_dust() {
+        let vault_accounts = vault_accounts(token::ID);
+        let unstaking = Pubkey::new_unique();
+        let lst_escrow_ata = pda::find_unstaking_escrow_ata(&unstaking).0;
+        let destination = Pubkey::new_unique();
+
+        let ix = vault_accounts.sweep_escrow_dust(
+            &vault_accounts.lst_mint,
+            &token::ID,
+            &lst_escrow_ata,
+            Some(&destination),
+            Some(&unstaking),
+        );
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == vault_accounts.owner);
+        assert_eq!(ix.accounts[5].pubkey, lst_escrow_ata);
+        assert_eq!(ix.accounts[6].pubkey, destination);
+        assert_eq!(ix.accounts[7].pubkey, unstaking);
+
+        // closed ticket, its escrow is burnt
+        let ix = vault_accounts.sweep_escrow_dust(
+            &vault_accounts.lst_mint,
+            &token::ID,
+            &lst_escrow_ata,
+            None,
+            Some(&unstaking),
+        );
+        assert_eq!(ix.accounts[6].pubkey, neptune::ID);
+        assert!(ix.accounts[4].is_writable);
+
+        // no ticket for other mints
+        let ix = vault_accounts.sweep_escrow_dust(
+            &Pubkey::new_unique(),
+            &token::ID,
+            &Pubkey::new_unique(),
+            Some(&destination),
+            None,
+        );
+        assert_eq!(ix.accounts[7].pubkey, neptune::ID);
+    }
+
+    #[test]
+    fn test_init_config() {
+        let upgrade_authority = Pubkey::new_unique();
+        let ix = init_config(
+            &upgrade_authority,
+            &Pubkey::new_unique(),
+            Default::default(),
+        );
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == upgrade_authority);
+        assert_eq!(ix.accounts[1].pubkey, pda::find_config().0);
+        assert_eq!(ix.accounts[2].pubkey, neptune::ID);
+        assert_eq!(ix.accounts[3].pubkey, pda::find_program_data().0);
+
+        // create_vault is checked against the config
+        let ix = create_vault(CreateVaultParams {
+            payer: Pubkey::new_unique(),
+            base: Pubkey::new_unique(),
+            locker: Pubkey::new_unique(),
+            utoken_mint: Pubkey::new_unique(),
+            vault_owner: Pubkey::new_unique(),
+            lst_token_program: token::ID,
+            fees_bps: 100,
+            transfer_hook_program: None,
+            metadata: Default::default(),
+        });
+        assert_eq!(ix.accounts[1].pubkey, pda::find_config().0);
+        assert!(!ix.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn test_claim_fees() {
+        let vault_accounts = vault_accounts(token_2022::ID);
+        let destination = Pubkey::new_unique();
+
+        let ix = vault_accounts.claim_owner_fees(FeePayout::Lst, &destination);
+        assert_eq!(
+            ix.data,
+            instruction::ClaimOwnerFees {
+                payout: FeePayout::Lst
+            }
+            .data()
+        );
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == vault_accounts.owner);
+        assert_eq!(ix.accounts[1].pubkey, pda::find_config().0);
+        // no buffer for lst payouts
+        assert_eq!(ix.accounts[5].pubkey, neptune::ID);
+        assert_eq!(ix.accounts[6].pubkey, destination);
+
+        let admin = Pubkey::new_unique();
+        let ix = vault_accounts.claim_protocol_fees(&admin, FeePayout::Utoken, &destination);
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == admin);
+        assert_eq!(ix.accounts[5].pubkey, vault_accounts.utoken_buffer_ata());
+        assert!(ix.accounts[5].is_writable);
+    }
+
+    #[test]
+    fn test_metaplex_accounts() {
+        let lst_mint = Pubkey::new_unique();
+        assert_eq!(metaplex_accounts(&lst_mint, &token_2022::ID), (None, None));
+        assert_eq!(
+            metaplex_accounts(&lst_mint, &token::ID),
+            (
+                Some(pda::find_lst_metadata(&lst_mint).0),
+                Some(mpl_token_metadata::ID)
+            )
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! Rust client for the neptune program: pdas, instruction builders,
+//! account decoding and exchange rate quotes.
+
+pub mod accounts;
+pub mod error;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use accounts::{AccountSource, MemcmpFilter, RawAccount, VaultAccounts};
+pub use error::{ClientError, Result};
+pub use neptune;
@@ -0,0 +1,168 @@
+use anchor_lang::{prelude::Pubkey, solana_program::bpf_loader_upgradeable};
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, metadata};
+use neptune::{
+    govern, lock_voter,
+    state::{Config, LstVote, Unstaking, UserState, Vault, VaultList, VaultProposal},
+};
+
+// protocol wide, see init_config
+pub fn find_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::CONFIG_SEED], &neptune::ID)
+}
+
+// holds the neptune upgrade authority
+pub fn find_program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[neptune::ID.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+// vault is derived from its base keypair, see create_vault
+pub fn find_vault(base: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Vault::VAULT_SEED, base.as_ref()], &neptune::ID)
+}
+
+// every vault over `locker`
+pub fn find_vault_list(locker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VaultList::VAULT_LIST_SEED, locker.as_ref()], &neptune::ID)
+}
+
+pub fn find_lst_mint(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Vault::VAULT_LST_MINT, vault.as_ref()], &neptune::ID)
+}
+
+pub fn find_utoken_buffer(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Vault::VAULT_BUFFER_SEED, vault.as_ref()], &neptune::ID)
+}
+
+// WAGMI partial unstaking refilling the buffer
+pub fn find_buffer_refill(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Vault::VAULT_BUFFER_REFILL_SEED, vault.as_ref()],
+        &neptune::ID,
+    )
+}
+
+pub fn find_user_state(vault: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[UserState::USER_STATE_SEED, vault.as_ref(), owner.as_ref()],
+        &neptune::ID,
+    )
+}
+
+// `nonce`-th ticket of `owner`, see UserState
+pub fn find_unstaking(vault: &Pubkey, owner: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Unstaking::UNSTAKING_SEED,
+            vault.as_ref(),
+            owner.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &neptune::ID,
+    )
+}
+
+// WAGMI partial unstaking of a ticket
+pub fn find_partial_unstaking(unstaking: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Unstaking::PARTIAL_UNSTAKING_SEED, unstaking.as_ref()],
+        &neptune::ID,
+    )
+}
+
+pub fn find_unstaking_escrow_ata(unstaking: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Unstaking::UNSTAKING_ESCROW_ATA_SEED, unstaking.as_ref()],
+        &neptune::ID,
+    )
+}
+
+pub fn find_vault_proposal(vault: &Pubkey, proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VaultProposal::VAULT_PROPOSAL_SEED,
+            vault.as_ref(),
+            proposal.as_ref(),
+        ],
+        &neptune::ID,
+    )
+}
+
+pub fn find_lst_vote(vault_proposal: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LstVote::LST_VOTE_SEED,
+            vault_proposal.as_ref(),
+            owner.as_ref(),
+        ],
+        &neptune::ID,
+    )
+}
+
+pub fn find_lst_vote_escrow_ata(lst_vote: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LstVote::LST_VOTE_ESCROW_ATA_SEED, lst_vote.as_ref()],
+        &neptune::ID,
+    )
+}
+
+// as in WAGMI locked_voter
+pub fn find_escrow(locker: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"Escrow", locker.as_ref(), owner.as_ref()],
+        &lock_voter::ID,
+    )
+}
+
+// as in WAGMI govern
+pub fn find_governance_vote(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"Vote", proposal.as_ref(), voter.as_ref()], &govern::ID)
+}
+
+// metaplex metadata of spl lst mints
+pub fn find_lst_metadata(lst_mint: &Pubkey) -> (Pubkey, u8) {
+    metadata::mpl_token_metadata::accounts::Metadata::find_pda(lst_mint)
+}
+
+pub fn get_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_vault() {
+        let base = Pubkey::new_unique();
+        let (vault, vault_bump) = find_vault(&base);
+        let (lst_mint, lst_mint_bump) = find_lst_mint(&vault);
+
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[Vault::VAULT_SEED, base.as_ref(), &[vault_bump]],
+                &neptune::ID
+            )
+            .unwrap(),
+            vault
+        );
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[Vault::VAULT_LST_MINT, vault.as_ref(), &[lst_mint_bump]],
+                &neptune::ID
+            )
+            .unwrap(),
+            lst_mint
+        );
+        // each base its own vault
+        assert_ne!(find_vault(&Pubkey::new_unique()).0, vault);
+    }
+
+    #[test]
+    fn test_find_unstaking() {
+        let (vault, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let first = find_unstaking(&vault, &owner, 0).0;
+        assert_ne!(first, find_unstaking(&vault, &owner, 1).0);
+        assert_ne!(first, find_unstaking(&vault, &Pubkey::new_unique(), 0).0);
+        assert_ne!(find_partial_unstaking(&first).0, first);
+    }
+}
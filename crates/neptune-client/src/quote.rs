@@ -0,0 +1,100 @@
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
+use neptune::state::Vault;
+
+use crate::error::Result;
+
+// same math as the program, quotes hold until the vault totals move
+
+pub fn quote_stake(vault: &Vault, utoken_amt: u64) -> Result<u64> {
+    Ok(vault.get_stake_lst_amt(utoken_amt)?)
+}
+
+// utoken withdrawn once the unstaking expires, fixed when it begins
+pub fn quote_unstake(vault: &Vault, lst_amt: u64) -> Result<u64> {
+    Ok(vault.get_utoken_amt(lst_amt)?)
+}
+
+// lst returned for merging `lst_amt` of a ticket worth `utoken_amt`,
+// the rest is burnt if rewards accrued during the cooldown
+pub fn quote_merge_unstaking(vault: &Vault, lst_amt: u64, utoken_amt: u64) -> Result<u64> {
+    let mut vault = vault.clone();
+    vault.pending_unstake_lst_amt = lst_amt;
+    vault.pending_unstake_utoken_amt = utoken_amt;
+    Ok(vault.cancel_unstake(lst_amt, utoken_amt)?)
+}
+
+// utoken paid out of the buffer, net of the instant unstake fee,
+// the protocol share only splits that fee
+pub fn quote_instant_unstake(vault: &Vault, lst_amt: u64) -> Result<u64> {
+    Ok(vault.clone().instant_unstake(lst_amt, 0)?)
+}
+
+// least a quote can move by `slippage_bps` before the instruction fails
+pub fn min_out(quote: u64, slippage_bps: u16) -> u64 {
+    let slippage_bps = slippage_bps.min(MAX_FEE_BASIS_POINTS) as u128;
+    (quote as u128 * (MAX_FEE_BASIS_POINTS as u128 - slippage_bps) / MAX_FEE_BASIS_POINTS as u128)
+        as u64
+}
+
+// utoken per lst, as a float for display
+pub fn exchange_rate(vault: &Vault) -> Result<f64> {
+    Ok(vault.get_exchange_rate()? as f64 / Vault::EXCHANGE_RATE_PRECISION as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vault() -> Vault {
+        Vault {
+            // 1.1 counting the virtual shares
+            total_lst_minted: 1_000_000 - Vault::VIRTUAL_SHARES,
+            total_utoken_staked: 1_100_000 - Vault::VIRTUAL_SHARES,
+            buffer_utoken_amt: 500,
+            instant_unstake_fee_bps: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_quote() {
+        let vault = vault();
+        assert_eq!(quote_stake(&vault, 110).unwrap(), 100);
+        assert_eq!(quote_unstake(&vault, 100).unwrap(), 110);
+        assert!((exchange_rate(&vault).unwrap() - 1.1).abs() < 1e-9);
+
+        // 1% fee rounded up stays in the vault
+        assert_eq!(quote_instant_unstake(&vault, 100).unwrap(), 108);
+        // quoting leaves the vault untouched
+        assert_eq!(vault.buffer_utoken_amt, 500);
+        // more than the buffer holds
+        assert!(quote_instant_unstake(&vault, 600).is_err());
+    }
+
+    #[test]
+    fn test_quote_merge_unstaking() {
+        let vault = vault();
+        // ticket quoted at 1:1, the rate went up since
+        assert_eq!(quote_merge_unstaking(&vault, 100, 100).unwrap(), 90);
+        assert_eq!(quote_merge_unstaking(&vault, 100, 110).unwrap(), 100);
+        // never more lst than the ticket holds
+        assert_eq!(quote_merge_unstaking(&vault, 100, 220).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_min_out() {
+        assert_eq!(min_out(10_000, 50), 9_950);
+        assert_eq!(min_out(10_000, 0), 10_000);
+        assert_eq!(min_out(10_000, u16::MAX), 0);
+    }
+
+    #[test]
+    fn test_quote_empty_vault() {
+        let vault = Vault::default();
+        // the first stake seeds the rate
+        assert!(quote_stake(&vault, 42).is_err());
+        assert_eq!(quote_stake(&vault, 4_200).unwrap(), 4_200);
+        assert_eq!(quote_unstake(&vault, 42).unwrap(), 42);
+        assert_eq!(exchange_rate(&vault).unwrap(), 1.0);
+    }
+}
@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{FeePayout, LockPolicy};
+
+// exchange_rate is utoken amt for Vault::EXCHANGE_RATE_PRECISION lst
+
+#[event]
+pub struct VaultCreated {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub escrow: Pubkey,
+    pub lst_mint: Pubkey,
+    pub fees_bps: u16,
+}
+
+#[event]
+pub struct VaultListed {
+    pub vault: Pubkey,
+    pub locker: Pubkey,
+    pub vault_list: Pubkey,
+}
+
+#[event]
+pub struct Staked {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub utoken_amt: u64,
+    pub lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct UnstakingBegun {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub unstaking: Pubkey,
+    // seeds the unstaking, see UserState
+    pub nonce: u64,
+    pub utoken_amt: u64,
+    pub lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct UnstakingMerged {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub unstaking: Pubkey,
+    pub utoken_amt: u64,
+    pub lst_amt: u64,
+    // at the current rate, the rest of lst_amt is burnt
+    pub returned_lst_amt: u64,
+    // left unstaking in the ticket
+    pub remaining_lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct UnstakeWithdrawn {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub unstaking: Pubkey,
+    pub utoken_amt: u64,
+    pub lst_amt: u64,
+    // rest of the ticket returned to the user at the current rate,
+    // its utoken locked again
+    pub restaked_lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct InstantUnstaked {
+    pub vault: Pubkey,
+    pub user: Pubkey,
+    pub utoken_amt: u64,
+    pub lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct RewardCompounded {
+    pub vault: Pubkey,
+    pub utoken_amt: u64,
+    pub fee_lst_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct VaultSynced {
+    pub vault: Pubkey,
+    pub escrow_utoken_amt: u64,
+    pub in_deficit: bool,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct FeesClaimed {
+    pub vault: Pubkey,
+    // vault owner, or config admin for protocol fees
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub protocol: bool,
+    pub payout: FeePayout,
+    pub lst_amt: u64,
+    // utoken payout only
+    pub utoken_amt: u64,
+}
+
+#[event]
+pub struct EscrowDustSwept {
+    pub vault: Pubkey,
+    pub source: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowDustBurnt {
+    pub vault: Pubkey,
+    pub source: Pubkey,
+    pub amount: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
+
+#[event]
+pub struct FeesBpsUpdated {
+    pub vault: Pubkey,
+    pub fees_bps: u16,
+}
+
+#[event]
+pub struct VaultPauseSet {
+    pub vault: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct OwnershipTransferStarted {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnershipAccepted {
+    pub vault: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct LockPolicySet {
+    pub vault: Pubkey,
+    pub lock_policy: LockPolicy,
+}
+
+#[event]
+pub struct VoteDelegateSet {
+    pub vault: Pubkey,
+    pub vote_delegate: Pubkey,
+}
+
+#[event]
+pub struct BufferConfigured {
+    pub vault: Pubkey,
+    pub buffer_bps: u16,
+    pub instant_unstake_fee_bps: u16,
+}
+
+#[event]
+pub struct BufferRefillBegun {
+    pub vault: Pubkey,
+    pub buffer_refill: Pubkey,
+    pub utoken_amt: u64,
+    pub buffer_utoken_amt: u64,
+}
+
+#[event]
+pub struct DeficitResolved {
+    pub vault: Pubkey,
+    pub escrow_utoken_amt: u64,
+    pub total_lst_minted: u64,
+    pub total_utoken_staked: u64,
+    pub exchange_rate: u64,
+}
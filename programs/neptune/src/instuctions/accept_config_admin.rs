@@ -0,0 +1,25 @@
+use crate::{state::Config, NeptuneError};
+use anchor_lang::prelude::*;
+
+impl<'info> AcceptConfigAdmin<'info> {
+    pub fn accept_config_admin(&mut self) -> Result<()> {
+        self.config.admin = self.pending_admin.key();
+        self.config.pending_admin = Pubkey::default();
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct AcceptConfigAdmin<'info>{
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+        has_one = pending_admin @ NeptuneError::Unauthorized
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
@@ -0,0 +1,30 @@
+use crate::{events::OwnershipAccepted, state::Vault, NeptuneError};
+use anchor_lang::prelude::*;
+
+impl<'info> AcceptOwnership<'info> {
+    pub fn accept_ownership(&mut self) -> Result<()> {
+        let previous_owner = self.vault.owner;
+        self.vault.owner = self.pending_owner.key();
+        self.vault.pending_owner = Pubkey::default();
+
+        emit!(OwnershipAccepted {
+            vault: self.vault.key(),
+            previous_owner,
+            owner: self.vault.owner,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info>{
+    pub pending_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = pending_owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
@@ -1,24 +1,43 @@
 use crate::{
+    events::UnstakingBegun,
     lock_voter::{
         self,
         accounts::Escrow,
         cpi::{self as locked_voter, accounts::OpenPartialUnstaking},
     },
-    state::{Unstaking, Vault},
+    state::{Unstaking, UserState, Vault},
+    utils::transfer_checked_with_hook,
     NeptuneError,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, TransferChecked},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 impl<'info> BeginUnstaking<'info> {
-    pub fn begin_unstaking(&mut self, lst_amt: u64) -> Result<()> {
+    pub fn begin_unstaking(
+        &mut self,
+        lst_amt: u64,
+        min_utoken_out: u64,
+        user_state_bump: u8,
+        partial_unstaking_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         require!(lst_amt > 0, NeptuneError::AmtMustGreaterThanZero);
+        require!(!self.vault.paused, NeptuneError::VaultPaused);
 
-        let utoken_amt = self.vault.get_utoken_amt(lst_amt)?;
+        // first ticket of the user in this vault
+        if self.user_state.owner == Pubkey::default() {
+            self.user_state.owner = self.signer.key();
+            self.user_state.vault = self.vault.key();
+            self.user_state.bump = user_state_bump;
+        }
+        let nonce = self.user_state.next_nonce()?;
+
+        // ticket leaves the totals now, cooldown rewards stay with stakers
+        let utoken_amt = self.vault.begin_unstake(lst_amt)?;
+        require!(utoken_amt >= min_utoken_out, NeptuneError::SlippageExceeded);
         // update unstaking state
         self.unstaking.utoken_amt = utoken_amt;
         self.unstaking.lst_amt = lst_amt;
@@ -28,18 +47,28 @@ impl<'info> BeginUnstaking<'info> {
 
         // xfer lst to our escrow
         let xfer_lst_to_escrow_cpi = CpiContext::new(
-            self.token_program.to_account_info(),
+            self.lst_token_program.to_account_info(),
             TransferChecked {
                 from: self.lst_source_ata.to_account_info(),
                 to: self.lst_escrow_ata.to_account_info(),
                 mint: self.lst_mint.to_account_info(),
                 authority: self.signer.to_account_info(),
             },
-        );
-        token::transfer_checked(xfer_lst_to_escrow_cpi, lst_amt, self.lst_mint.decimals)?;
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked_with_hook(xfer_lst_to_escrow_cpi, lst_amt, self.lst_mint.decimals)?;
 
-        // open partial unstaking
-        let open_partial_unstaking_cpi = CpiContext::new(
+        // open partial unstaking, vault owns the escrow
+        let unstaking_key = self.unstaking.key();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &self.vault.signer_seeds(),
+            &[
+                Unstaking::PARTIAL_UNSTAKING_SEED,
+                unstaking_key.as_ref(),
+                &[partial_unstaking_bump],
+            ],
+        ];
+        let open_partial_unstaking_cpi = CpiContext::new_with_signer(
             self.locked_voter.to_account_info(),
             OpenPartialUnstaking {
                 locker: self.locker.to_account_info(),
@@ -48,6 +77,7 @@ impl<'info> BeginUnstaking<'info> {
                 partial_unstake: self.partial_unstaking.to_account_info(),
                 system_program: self.system_program.to_account_info(),
             },
+            signer_seeds,
         );
         locked_voter::open_partial_unstaking(
             open_partial_unstaking_cpi,
@@ -55,6 +85,22 @@ impl<'info> BeginUnstaking<'info> {
             Unstaking::PARTIAL_UNSTAKING_MEMO.to_string(),
         )?;
 
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(UnstakingBegun {
+            vault: self.vault.key(),
+            user: self.signer.key(),
+            unstaking: self.unstaking.key(),
+            nonce,
+            utoken_amt,
+            lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
         Ok(())
     }
 }
@@ -66,6 +112,7 @@ pub struct BeginUnstaking<'info>{
     pub signer: Signer<'info>,
 
     #[account(
+        mut,
         has_one = escrow
     )]
     pub vault: Box<Account<'info, Vault>>,
@@ -82,27 +129,54 @@ pub struct BeginUnstaking<'info>{
     pub escrow: Box<Account<'info, Escrow>>,
 
     #[account(
-        address = vault.lst_mint
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program,
     )]
     pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
     
-    // unstaking must sign
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = UserState::DISCRIMINATOR.len() + UserState::INIT_SPACE,
+        seeds = [
+            &UserState::USER_STATE_SEED,
+            vault.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_state: Box<Account<'info, UserState>>,
+
     #[account(
         init,
         payer = signer,
-        space = Unstaking::DISCRIMINATOR.len() + Unstaking::INIT_SPACE
+        space = Unstaking::DISCRIMINATOR.len() + Unstaking::INIT_SPACE,
+        seeds = [
+            &Unstaking::UNSTAKING_SEED,
+            vault.key().as_ref(),
+            signer.key().as_ref(),
+            user_state.nonce.to_le_bytes().as_ref()
+        ],
+        bump,
     )]
     pub unstaking: Box<Account<'info, Unstaking>>,
 
-    // partial_unstaking must sign
-    /// CHECK: checked in cpi
-    #[account(mut)]
+    /// CHECK: WAGMI partial unstaking, init in cpi
+    #[account(
+        mut,
+        seeds = [
+            &Unstaking::PARTIAL_UNSTAKING_SEED,
+            unstaking.key().as_ref()
+        ],
+        bump,
+    )]
     pub partial_unstaking: UncheckedAccount<'info>,
 
     #[account(
         mut,
         associated_token::mint = lst_mint,
         associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
     )]
     pub lst_source_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -115,7 +189,8 @@ pub struct BeginUnstaking<'info>{
         ],
         bump,
         token::mint = lst_mint,
-        token::authority = vault
+        token::authority = vault,
+        token::token_program = lst_token_program
     )]
     pub lst_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -124,6 +199,6 @@ pub struct BeginUnstaking<'info>{
     #[account(address = lock_voter::ID)]
     pub locked_voter: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,110 @@
+use crate::{
+    state::{LstVote, Vault, VaultProposal},
+    utils::transfer_checked_with_hook,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+impl<'info> CastLstVote<'info> {
+    pub fn cast_lst_vote(
+        &mut self,
+        lst_vote_bump: u8,
+        side: u8,
+        lst_amt: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(lst_amt > 0, NeptuneError::AmtMustGreaterThanZero);
+        require!(
+            self.vault_proposal.is_voting(Clock::get()?.unix_timestamp),
+            NeptuneError::VotingEnded
+        );
+
+        // update vote state
+        self.vault_proposal.add_vote(side, lst_amt)?;
+        self.lst_vote.owner = self.signer.key();
+        self.lst_vote.vault_proposal = self.vault_proposal.key();
+        self.lst_vote.side = side;
+        self.lst_vote.weight = lst_amt;
+        self.lst_vote.bump = lst_vote_bump;
+
+        // lock lst for the vote so it cannot vote twice
+        let xfer_lst_to_escrow_cpi = CpiContext::new(
+            self.lst_token_program.to_account_info(),
+            TransferChecked {
+                from: self.lst_source_ata.to_account_info(),
+                to: self.lst_vote_escrow_ata.to_account_info(),
+                mint: self.lst_mint.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked_with_hook(xfer_lst_to_escrow_cpi, lst_amt, self.lst_mint.decimals)?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct CastLstVote<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        has_one = vault
+    )]
+    pub vault_proposal: Box<Account<'info, VaultProposal>>,
+
+    #[account(
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program,
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lst_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
+    )]
+    pub lst_source_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = LstVote::DISCRIMINATOR.len() + LstVote::INIT_SPACE,
+        seeds = [
+            &LstVote::LST_VOTE_SEED,
+            vault_proposal.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump,
+    )]
+    pub lst_vote: Box<Account<'info, LstVote>>,
+
+    #[account(
+        init,
+        payer = signer,
+        seeds = [
+            &LstVote::LST_VOTE_ESCROW_ATA_SEED,
+            lst_vote.key().as_ref()
+        ],
+        bump,
+        token::mint = lst_mint,
+        token::authority = vault,
+        token::token_program = lst_token_program
+    )]
+    pub lst_vote_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,107 @@
+use crate::{
+    govern::{
+        self,
+        accounts::{Governor, Proposal},
+        cpi::{self as govern_cpi, accounts::NewVote},
+    },
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+        cpi::{self as locked_voter, accounts::CastVote as LockedVoterCastVote},
+    },
+    state::{Vault, VaultProposal},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> CastVaultVote<'info> {
+    pub fn cast_vault_vote(&mut self) -> Result<()> {
+        // can be cranked again while voting, WAGMI overrides the side
+        let side = self.vault_proposal.winning_side()?;
+
+        // vault's vote on this proposal doesn't exist yet
+        if self.vote.data_is_empty() {
+            let new_vote_cpi = CpiContext::new(
+                self.govern_program.to_account_info(),
+                NewVote {
+                    proposal: self.proposal.to_account_info(),
+                    vote: self.vote.to_account_info(),
+                    payer: self.signer.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+            );
+            govern_cpi::new_vote(new_vote_cpi, self.vault.key())?;
+        }
+
+        // vault is the escrow's vote delegate
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let cast_vote_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            LockedVoterCastVote {
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                vote_delegate: self.vault.to_account_info(),
+                proposal: self.proposal.to_account_info(),
+                vote: self.vote.to_account_info(),
+                governor: self.governor.to_account_info(),
+                govern_program: self.govern_program.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::cast_vote(cast_vote_cpi, side)?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct CastVaultVote<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        has_one = vault,
+        has_one = proposal
+    )]
+    pub vault_proposal: Box<Account<'info, VaultProposal>>,
+
+    #[account(
+        has_one = governor
+    )]
+    pub locker: Box<Account<'info, Locker>>,
+
+    // the vault only votes while it is the escrow's delegate
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key(),
+        constraint = escrow.vote_delegate == vault.key() @ NeptuneError::VoteDelegated
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    pub governor: Box<Account<'info, Governor>>,
+
+    #[account(
+        mut,
+        has_one = governor
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    /// CHECK: checked in cpi
+    #[account(mut)]
+    pub vote: UncheckedAccount<'info>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+    /// CHECK: check in attr
+    #[account(address = govern::ID)]
+    pub govern_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,116 @@
+use crate::{
+    govern::{
+        self,
+        accounts::{Governor, Proposal},
+        cpi::{self as govern_cpi, accounts::NewVote},
+    },
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+        cpi::{self as locked_voter, accounts::CastVote as LockedVoterCastVote},
+    },
+    state::{Vault, VaultProposal},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> CastVote<'info> {
+    pub fn cast_vote(&mut self, side: u8) -> Result<()> {
+        // lst holders vote on mirrored proposals, the voter cannot override them
+        require!(
+            self.vault_proposal.data_is_empty(),
+            NeptuneError::VaultProposalExists
+        );
+
+        // vault's vote on this proposal doesn't exist yet
+        if self.vote.data_is_empty() {
+            let new_vote_cpi = CpiContext::new(
+                self.govern_program.to_account_info(),
+                NewVote {
+                    proposal: self.proposal.to_account_info(),
+                    vote: self.vote.to_account_info(),
+                    payer: self.voter.to_account_info(),
+                    system_program: self.system_program.to_account_info(),
+                },
+            );
+            govern_cpi::new_vote(new_vote_cpi, self.vault.key())?;
+        }
+
+        // vault is the escrow's vote delegate
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let cast_vote_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            LockedVoterCastVote {
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                vote_delegate: self.vault.to_account_info(),
+                proposal: self.proposal.to_account_info(),
+                vote: self.vote.to_account_info(),
+                governor: self.governor.to_account_info(),
+                govern_program: self.govern_program.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::cast_vote(cast_vote_cpi, side)?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct CastVote<'info>{
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        has_one = escrow,
+        has_one = voter @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        has_one = governor
+    )]
+    pub locker: Box<Account<'info, Locker>>,
+
+    // the vault only votes while it is the escrow's delegate
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key(),
+        constraint = escrow.vote_delegate == vault.key() @ NeptuneError::VoteDelegated
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    pub governor: Box<Account<'info, Governor>>,
+
+    #[account(
+        mut,
+        has_one = governor
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    /// CHECK: checked in cpi
+    #[account(mut)]
+    pub vote: UncheckedAccount<'info>,
+
+    /// CHECK: must not exist, checked in ix
+    #[account(
+        seeds = [
+            &VaultProposal::VAULT_PROPOSAL_SEED,
+            vault.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault_proposal: UncheckedAccount<'info>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+    /// CHECK: check in attr
+    #[account(address = govern::ID)]
+    pub govern_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
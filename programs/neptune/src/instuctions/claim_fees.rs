@@ -0,0 +1,173 @@
+use crate::{
+    events::FeesClaimed,
+    state::{Config, FeePayout, Vault},
+    unwrap_ops, NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+impl<'info> ClaimFees<'info> {
+    pub fn claim_owner_fees(&mut self, payout: FeePayout) -> Result<()> {
+        require_keys_eq!(
+            self.authority.key(),
+            self.vault.owner,
+            NeptuneError::Unauthorized
+        );
+
+        let fee_lst_amt = self.vault.claim_owner_fees()?;
+        self.pay_fees(fee_lst_amt, payout, false)
+    }
+
+    // protocol fees only go to the treasury
+    pub fn claim_protocol_fees(&mut self, payout: FeePayout) -> Result<()> {
+        require_keys_eq!(
+            self.authority.key(),
+            self.config.admin,
+            NeptuneError::Unauthorized
+        );
+        require_keys_eq!(
+            self.destination.owner,
+            self.config.treasury,
+            NeptuneError::InvalidFeeDestination
+        );
+
+        let fee_lst_amt = self.vault.claim_protocol_fees()?;
+        self.pay_fees(fee_lst_amt, payout, true)
+    }
+
+    fn pay_fees(&mut self, fee_lst_amt: u64, payout: FeePayout, protocol: bool) -> Result<()> {
+        let utoken_amt = match payout {
+            FeePayout::Lst => {
+                require_keys_eq!(
+                    self.destination.mint,
+                    self.lst_mint.key(),
+                    NeptuneError::InvalidFeeDestination
+                );
+                require_keys_eq!(
+                    *self.destination.to_account_info().owner,
+                    self.lst_token_program.key(),
+                    NeptuneError::InvalidFeeDestination
+                );
+                self.mint_fee_lst(fee_lst_amt)?;
+                0
+            }
+            FeePayout::Utoken => {
+                require_keys_eq!(
+                    self.destination.mint,
+                    self.utoken_mint.key(),
+                    NeptuneError::InvalidFeeDestination
+                );
+                require_keys_eq!(
+                    *self.destination.to_account_info().owner,
+                    self.token_program.key(),
+                    NeptuneError::InvalidFeeDestination
+                );
+                require!(
+                    self.utoken_buffer_ata.is_some(),
+                    NeptuneError::InsufficientBuffer
+                );
+                let utoken_amt = self.vault.redeem_fees(fee_lst_amt)?;
+                self.pay_utoken(utoken_amt)?;
+                utoken_amt
+            }
+        };
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(FeesClaimed {
+            vault: self.vault.key(),
+            authority: self.authority.key(),
+            destination: self.destination.key(),
+            protocol,
+            payout,
+            lst_amt: fee_lst_amt,
+            utoken_amt,
+        });
+
+        Ok(())
+    }
+
+    fn mint_fee_lst(&self, fee_lst_amt: u64) -> Result<()> {
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let mint_fee_lst_cpi = CpiContext::new_with_signer(
+            self.lst_token_program.to_account_info(),
+            MintTo {
+                mint: self.lst_mint.to_account_info(),
+                to: self.destination.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::mint_to(mint_fee_lst_cpi, fee_lst_amt)
+    }
+
+    fn pay_utoken(&self, utoken_amt: u64) -> Result<()> {
+        let utoken_buffer_ata = unwrap_ops!(
+            self.utoken_buffer_ata.as_ref(),
+            NeptuneError::InsufficientBuffer
+        );
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let xfer_utoken_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: utoken_buffer_ata.to_account_info(),
+                to: self.destination.to_account_info(),
+                mint: self.utoken_mint.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::transfer_checked(xfer_utoken_cpi, utoken_amt, self.utoken_mint.decimals)
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct ClaimFees<'info>{
+    // vault owner, or config admin for protocol fees
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // utoken payout only
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+        token::mint = utoken_mint,
+        token::authority = vault
+    )]
+    pub utoken_buffer_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // lst or utoken account following the payout, checked in ix
+    #[account(mut)]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
+}
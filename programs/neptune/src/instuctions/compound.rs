@@ -0,0 +1,118 @@
+use crate::{
+    events::RewardCompounded,
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+        cpi::{self as locked_voter, accounts::IncreaseLockedAmount},
+    },
+    state::{Config, Vault},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+impl<'info> Compound<'info> {
+    pub fn compound(&mut self) -> Result<()> {
+        // anyone can donate rewards by sending utoken to the vault's reward ata
+        let reward_amt = self.utoken_reward_ata.amount;
+        require!(reward_amt > 0, NeptuneError::AmtMustGreaterThanZero);
+        // rewards would be shared on top of the loss, resolve it first
+        require!(!self.vault.in_deficit, NeptuneError::VaultInDeficit);
+
+        // lock rewards to locked_voter, vault is the source authority
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let incease_lock_amt_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            IncreaseLockedAmount {
+                payer: self.vault.to_account_info(),
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                source_tokens: self.utoken_reward_ata.to_account_info(),
+                escrow_tokens: self.utoken_escrow_ata.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::increase_locked_amount(incease_lock_amt_cpi, reward_amt)?;
+
+        // update vault state
+        let fee_lst_amt = self
+            .vault
+            .add_reward(reward_amt, self.config.protocol_fee_bps)?;
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(RewardCompounded {
+            vault: self.vault.key(),
+            utoken_amt: reward_amt,
+            fee_lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct Compound<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        address = vault.lst_mint
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        address = escrow.tokens
+    )]
+    pub utoken_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // airdrops & donations land here
+    #[account(
+        mut,
+        associated_token::mint = locker.token_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub utoken_reward_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,70 @@
+use crate::{
+    events::BufferConfigured,
+    lock_voter::accounts::{Escrow, Locker},
+    state::Vault,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+impl<'info> ConfigureBuffer<'info> {
+    pub fn configure_buffer(
+        &mut self,
+        buffer_bps: u16,
+        instant_unstake_fee_bps: u16,
+    ) -> Result<()> {
+        self.vault.set_buffer(buffer_bps, instant_unstake_fee_bps)?;
+
+        emit!(BufferConfigured {
+            vault: self.vault.key(),
+            buffer_bps,
+            instant_unstake_fee_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct ConfigureBuffer<'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        address = locker.token_mint
+    )]
+    pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            &Vault::VAULT_BUFFER_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+        token::mint = utoken_mint,
+        token::authority = vault
+    )]
+    pub utoken_buffer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
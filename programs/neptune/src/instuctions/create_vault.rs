@@ -1,28 +1,58 @@
 use crate::{
+    events::{VaultCreated, VaultListed},
     lock_voter::{
         self,
         accounts::Locker,
-        cpi::{self as locked_voter, accounts::NewEscrow},
+        cpi::{
+            self as locked_voter,
+            accounts::{NewEscrow, ToggleMaxLock},
+        },
     },
-    state::Vault,
+    state::{Config, LockPolicy, LstMetadata, Vault, VaultEntry, VaultList},
+    utils::{grow_account, top_up_rent},
     NeptuneError,
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, CreateAccount},
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_2022::spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS,
-    token_interface::{Mint, TokenInterface},
+    metadata::{self, CreateMetadataAccountsV3, Metadata},
+    token_2022,
+    token_interface::{
+        self, find_mint_account_size, spl_token_2022::extension::ExtensionType, InitializeMint2,
+        MetadataPointerInitialize, Mint, TokenInterface, TokenMetadataInitialize,
+        TransferHookInitialize,
+    },
 };
 
 impl<'info> CreateVault<'info> {
-    pub fn create_vault(&mut self, vault_bump: u8, fees_bps: u16) -> Result<()> {
-        require!(fees_bps < MAX_FEE_BASIS_POINTS, NeptuneError::InvalidBPS);
+    pub fn create_vault(
+        &mut self,
+        vault_bump: u8,
+        lst_mint_bump: u8,
+        vault_list_bump: u8,
+        fees_bps: u16,
+        transfer_hook_program: Option<Pubkey>,
+        metadata: LstMetadata,
+    ) -> Result<()> {
+        metadata.validate()?;
+        self.config.check_fees_bps(fees_bps)?;
 
         self.vault.bump = vault_bump;
+        self.vault.base = self.base.key();
         self.vault.escrow = self.escrow.key();
-        self.vault.lst_mint = self.utoken_mint.key();
+        self.vault.lst_mint = self.lst_mint.key();
+        self.vault.lst_mint_bump = lst_mint_bump;
         self.vault.owner = self.vault_owner.key();
-        self.vault.fees_bps = fees_bps;
+        self.vault.voter = self.vault_owner.key();
+        self.vault.vote_delegate = self.vault.key();
+        self.vault.set_fees_bps(fees_bps)?;
+        self.vault.lock_policy = LockPolicy::MaxLock;
+
+        self.init_lst_mint(lst_mint_bump, transfer_hook_program)?;
+        self.init_lst_metadata(&metadata)?;
 
         let new_escrow_cpi = CpiContext::new(
             self.locked_voter.to_account_info(),
@@ -36,8 +66,206 @@ impl<'info> CreateVault<'info> {
         );
         locked_voter::new_escrow(new_escrow_cpi)?;
 
+        // max lock by default
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let toggle_max_lock_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            ToggleMaxLock {
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                escrow_owner: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::toggle_max_lock(toggle_max_lock_cpi, true)?;
+
+        self.list_vault(vault_list_bump, metadata.name.clone())?;
+
+        // nothing minted yet
+        self.vault.assert_invariants(&self.vault.key(), 0)?;
+
+        emit!(VaultCreated {
+            vault: self.vault.key(),
+            owner: self.vault.owner,
+            escrow: self.vault.escrow,
+            lst_mint: self.vault.lst_mint,
+            fees_bps: self.vault.fees_bps,
+        });
+
         Ok(())
     }
+
+    // append to the locker's vault list, opened by its first vault,
+    // a full list leaves the vault unlisted
+    fn list_vault(&mut self, vault_list_bump: u8, name: String) -> Result<()> {
+        self.vault_list.locker = self.locker.key();
+        self.vault_list.bump = vault_list_bump;
+        if self.vault_list.is_full() {
+            return Ok(());
+        }
+
+        grow_account(
+            self.signer.to_account_info(),
+            self.vault_list.to_account_info(),
+            self.system_program.to_account_info(),
+            VaultList::space(self.vault_list.vaults.len() + 1),
+        )?;
+        self.vault_list
+            .add(VaultEntry::new(self.vault.key(), &self.vault, name))?;
+
+        emit!(VaultListed {
+            vault: self.vault.key(),
+            locker: self.locker.key(),
+            vault_list: self.vault_list.key(),
+        });
+
+        Ok(())
+    }
+
+    // lst mint is spl or token-2022 following lst_token_program,
+    // token-2022 mints get metadata pointer & transfer hook extensions
+    fn init_lst_mint(
+        &self,
+        lst_mint_bump: u8,
+        transfer_hook_program: Option<Pubkey>,
+    ) -> Result<()> {
+        let is_token_2022 = self.lst_token_program.key() == token_2022::ID;
+        require!(
+            is_token_2022 || transfer_hook_program.is_none(),
+            NeptuneError::InvalidTokenProgram
+        );
+
+        let extensions = if is_token_2022 {
+            vec![ExtensionType::MetadataPointer, ExtensionType::TransferHook]
+        } else {
+            vec![]
+        };
+        let space = find_mint_account_size(Some(&extensions))?;
+
+        let vault_key = self.vault.key();
+        let lst_mint_seeds: &[&[&[u8]]] =
+            &[&[Vault::VAULT_LST_MINT, vault_key.as_ref(), &[lst_mint_bump]]];
+        let create_lst_mint_cpi = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            CreateAccount {
+                from: self.signer.to_account_info(),
+                to: self.lst_mint.to_account_info(),
+            },
+            lst_mint_seeds,
+        );
+        system_program::create_account(
+            create_lst_mint_cpi,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &self.lst_token_program.key(),
+        )?;
+
+        if is_token_2022 {
+            // metadata lives in the mint itself
+            let metadata_pointer_cpi = CpiContext::new(
+                self.lst_token_program.to_account_info(),
+                MetadataPointerInitialize {
+                    token_program_id: self.lst_token_program.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                },
+            );
+            token_interface::metadata_pointer_initialize(
+                metadata_pointer_cpi,
+                Some(vault_key),
+                Some(self.lst_mint.key()),
+            )?;
+
+            let transfer_hook_cpi = CpiContext::new(
+                self.lst_token_program.to_account_info(),
+                TransferHookInitialize {
+                    token_program_id: self.lst_token_program.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                },
+            );
+            token_interface::transfer_hook_initialize(
+                transfer_hook_cpi,
+                Some(vault_key),
+                transfer_hook_program,
+            )?;
+        }
+
+        let init_lst_mint_cpi = CpiContext::new(
+            self.lst_token_program.to_account_info(),
+            InitializeMint2 {
+                mint: self.lst_mint.to_account_info(),
+            },
+        );
+        token_interface::initialize_mint2(
+            init_lst_mint_cpi,
+            self.utoken_mint.decimals,
+            &vault_key,
+            Some(&vault_key),
+        )
+    }
+
+    // vault is mint & update authority, so only the vault can change metadata
+    fn init_lst_metadata(&self, metadata: &LstMetadata) -> Result<()> {
+        let vault_key = self.vault.key();
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+
+        if self.lst_token_program.key() == token_2022::ID {
+            // metadata extension reallocs the mint without paying rent
+            let token_metadata = metadata.to_token_metadata(vault_key, self.lst_mint.key());
+            let lst_mint_len = self.lst_mint.data_len() + token_metadata.tlv_size_of()?;
+            top_up_rent(
+                self.signer.to_account_info(),
+                self.lst_mint.to_account_info(),
+                self.system_program.to_account_info(),
+                lst_mint_len,
+            )?;
+
+            let token_metadata_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: self.lst_token_program.to_account_info(),
+                    metadata: self.lst_mint.to_account_info(),
+                    update_authority: self.vault.to_account_info(),
+                    mint_authority: self.vault.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                },
+                vault_seeds,
+            );
+            return token_interface::token_metadata_initialize(
+                token_metadata_cpi,
+                metadata.name.clone(),
+                metadata.symbol.clone(),
+                metadata.uri.clone(),
+            );
+        }
+
+        let (Some(lst_metadata), Some(metadata_program)) =
+            (&self.lst_metadata, &self.metadata_program)
+        else {
+            return err!(NeptuneError::MissingMetadataAccounts);
+        };
+
+        let create_metadata_cpi = CpiContext::new_with_signer(
+            metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: lst_metadata.to_account_info(),
+                mint: self.lst_mint.to_account_info(),
+                mint_authority: self.vault.to_account_info(),
+                payer: self.signer.to_account_info(),
+                update_authority: self.vault.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                // not read by v3
+                rent: self.system_program.to_account_info(),
+            },
+            vault_seeds,
+        );
+        metadata::create_metadata_accounts_v3(
+            create_metadata_cpi,
+            metadata.to_data_v2(),
+            true,
+            true,
+            None,
+        )
+    }
 }
 
 #[rustfmt::skip]
@@ -46,35 +274,47 @@ pub struct CreateVault<'info>{
     #[account(mut)]
     pub signer: Signer<'info>,
 
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.can_create_vault(&signer.key()) @ NeptuneError::Unauthorized
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // fresh keypair, one vault per base
+    pub base: Signer<'info>,
+
     #[account(
         init,
         payer = signer,
         space = Vault::DISCRIMINATOR.len() + Vault::INIT_SPACE,
         seeds = [
             &Vault::VAULT_SEED,
-            escrow.key().as_ref()
+            base.key().as_ref()
         ],
         bump,
     )]
     pub vault: Box<Account<'info, Vault>>,
 
+    /// CHECK: initialized in handler, extensions depend on lst_token_program
     #[account(
-        init,
-        payer = signer,
-        mint::freeze_authority = vault,
-        mint::decimals = utoken_mint.decimals,
-        mint::authority = vault,
-        seeds = [Vault::VAULT_LST_MINT.as_ref(),
+        mut,
+        seeds = [Vault::VAULT_LST_MINT,
                     vault.key().as_ref()],
         bump
     )]
-    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub lst_mint: UncheckedAccount<'info>,
 
     #[account(
         address = locker.token_mint
     )]
     pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    // spl lst mint only, token-2022 mints hold their own metadata
+    /// CHECK: metaplex metadata pda, checked in cpi
+    #[account(mut)]
+    pub lst_metadata: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub locker: Box<Account<'info, Locker>>,
 
@@ -88,9 +328,23 @@ pub struct CreateVault<'info>{
             vault.key().as_ref()
         ],
         bump,
+        seeds::program = lock_voter::ID,
     )]
     pub escrow: UncheckedAccount<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = signer,
+        // grows by an entry per vault, init_if_needed checks the current size
+        space = VaultList::space(0).max(vault_list.data_len()),
+        seeds = [
+            &VaultList::VAULT_LIST_SEED,
+            locker.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault_list: Box<Account<'info, VaultList>>,
+
     /// CHECK: new vault owner
     pub vault_owner: UncheckedAccount<'info>,
 
@@ -98,7 +352,8 @@ pub struct CreateVault<'info>{
     /// CHECK: check in attr
     #[account(address = lock_voter::ID)]
     pub locked_voter: UncheckedAccount<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
+    pub metadata_program: Option<Program<'info, Metadata>>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,68 @@
+use crate::{
+    govern::accounts::Proposal,
+    lock_voter::accounts::{Escrow, Locker},
+    state::{Vault, VaultProposal},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> CreateVaultProposal<'info> {
+    pub fn create_vault_proposal(&mut self, vault_proposal_bump: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.proposal.activated_at > 0
+                && self.proposal.canceled_at == 0
+                && now < self.proposal.voting_ends_at,
+            NeptuneError::ProposalNotActive
+        );
+
+        self.vault_proposal.vault = self.vault.key();
+        self.vault_proposal.proposal = self.proposal.key();
+        self.vault_proposal.max_option = self.proposal.max_option;
+        self.vault_proposal.voting_ends_at = self.proposal.voting_ends_at;
+        self.vault_proposal.bump = vault_proposal_bump;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct CreateVaultProposal<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        constraint = proposal.governor == locker.governor
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = VaultProposal::DISCRIMINATOR.len() + VaultProposal::INIT_SPACE,
+        seeds = [
+            &VaultProposal::VAULT_PROPOSAL_SEED,
+            vault.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault_proposal: Box<Account<'info, VaultProposal>>,
+
+    // programs
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,50 @@
+use crate::{
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+    },
+    state::Vault,
+    utils::apply_lock_policy,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> EnforceLockPolicy<'info> {
+    pub fn enforce_lock_policy(&mut self) -> Result<()> {
+        // bring escrow back to vault's lock policy
+        let enforced = apply_lock_policy(
+            &self.vault,
+            &mut self.escrow,
+            self.locker.to_account_info(),
+            self.locked_voter.to_account_info(),
+        )?;
+        require!(enforced, NeptuneError::LockPolicyInEffect);
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct EnforceLockPolicy<'info>{
+    pub signer: Signer<'info>,
+
+    #[account(
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+}
@@ -0,0 +1,71 @@
+use crate::{
+    events::LockPolicySet,
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+    },
+    state::{LockPolicy, Vault},
+    unwrap_ops,
+    utils::apply_lock_policy,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> ExtendLockDuration<'info> {
+    // lengthens the duration policy, escrow is brought up to it
+    pub fn extend_lock_duration(&mut self, duration: i64) -> Result<()> {
+        // max lock has no end to extend
+        let LockPolicy::Duration(lock_duration) = self.vault.lock_policy else {
+            return err!(NeptuneError::InvalidLockPolicy);
+        };
+        require!(duration > 0, NeptuneError::InvalidLockPolicy);
+
+        let lock_policy = LockPolicy::Duration(unwrap_ops!(lock_duration.checked_add(duration)));
+        lock_policy.validate(
+            self.locker.params.min_stake_duration,
+            self.locker.params.max_stake_duration,
+        )?;
+        self.vault.lock_policy = lock_policy;
+
+        apply_lock_policy(
+            &self.vault,
+            &mut self.escrow,
+            self.locker.to_account_info(),
+            self.locked_voter.to_account_info(),
+        )?;
+
+        emit!(LockPolicySet {
+            vault: self.vault.key(),
+            lock_policy: self.vault.lock_policy,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct ExtendLockDuration<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+}
@@ -0,0 +1,14 @@
+use crate::state::{VaultList, VaultPage};
+use anchor_lang::prelude::*;
+
+impl<'info> GetVaults<'info> {
+    pub fn get_vaults(&self, offset: u32, limit: u8) -> VaultPage {
+        self.vault_list.page(offset, limit)
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct GetVaults<'info>{
+    pub vault_list: Box<Account<'info, VaultList>>,
+}
@@ -0,0 +1,50 @@
+use crate::{
+    program::Neptune,
+    state::{Config, ConfigParams},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> InitConfig<'info> {
+    pub fn init_config(
+        &mut self,
+        config_bump: u8,
+        admin: Pubkey,
+        params: ConfigParams,
+    ) -> Result<()> {
+        self.config.admin = admin;
+        self.config.bump = config_bump;
+        self.config.set_params(params)
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct InitConfig<'info>{
+    // only the upgrade authority, so the config cannot be front-run
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = Config::DISCRIMINATOR.len() + Config::INIT_SPACE,
+        seeds = [&Config::CONFIG_SEED],
+        bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program: Program<'info, Neptune>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ NeptuneError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    // programs
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,127 @@
+use crate::{
+    events::InstantUnstaked,
+    state::{Config, Vault},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+impl<'info> InstantUnstake<'info> {
+    pub fn instant_unstake(&mut self, lst_amt: u64) -> Result<()> {
+        require!(lst_amt > 0, NeptuneError::AmtMustGreaterThanZero);
+        require!(!self.vault.paused, NeptuneError::VaultPaused);
+
+        // update vault state
+        let utoken_amt = self
+            .vault
+            .instant_unstake(lst_amt, self.config.protocol_fee_bps)?;
+
+        // burn user lst
+        let burn_lst_cpi = CpiContext::new(
+            self.lst_token_program.to_account_info(),
+            Burn {
+                mint: self.lst_mint.to_account_info(),
+                from: self.lst_source_ata.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        );
+        token_interface::burn(burn_lst_cpi, lst_amt)?;
+
+        // pay out of buffer
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let xfer_utoken_to_user_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.utoken_buffer_ata.to_account_info(),
+                to: self.utoken_target_ata.to_account_info(),
+                mint: self.utoken_mint.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::transfer_checked(
+            xfer_utoken_to_user_cpi,
+            utoken_amt,
+            self.utoken_mint.decimals,
+        )?;
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(InstantUnstaked {
+            vault: self.vault.key(),
+            user: self.signer.key(),
+            utoken_amt,
+            lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct InstantUnstake<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = lst_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
+    )]
+    pub lst_source_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+        token::mint = utoken_mint,
+        token::authority = vault
+    )]
+    pub utoken_buffer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = utoken_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub utoken_target_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,42 +1,79 @@
 use crate::{
+    events::UnstakingMerged,
     lock_voter::{
         self,
         accounts::Escrow,
-        cpi::{self as locked_voter, accounts::MergePartialUnstaking},
+        cpi::{
+            self as locked_voter,
+            accounts::{MergePartialUnstaking, OpenPartialUnstaking},
+        },
     },
     state::{Unstaking, Vault},
-    vault_seeds,
+    unwrap_ops,
+    utils::transfer_checked_with_hook,
+    NeptuneError,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, TransferChecked},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{
+        self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 impl<'info> MergeUnstake<'info> {
-    pub fn merge_unstaking(&mut self) -> Result<()> {
-        let vault_key = self.vault.key();
-        let vault_seeds: &[&[&[u8]]] = vault_seeds!(self.vault, vault_key);
+    pub fn merge_unstaking(
+        &mut self,
+        lst_amt: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let unstaking_key = self.unstaking.key();
+        let partial_unstaking_bump = self.unstaking.find_partial_unstaking_bump(&unstaking_key);
+        let utoken_amt = self.unstaking.split(lst_amt)?;
+        let returned_lst_amt = match partial_unstaking_bump {
+            Some(_) => self.vault.cancel_unstake(lst_amt, utoken_amt)?,
+            // legacy tickets never left the totals
+            None => lst_amt,
+        };
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
 
         // return lst to user
-        let xfer_lst_to_user_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            TransferChecked {
-                authority: self.vault.to_account_info(),
-                from: self.lst_escrow_ata.to_account_info(),
-                to: self.lst_ata.to_account_info(),
-                mint: self.lst_mint.to_account_info(),
-            },
-            vault_seeds,
-        );
-        token::transfer_checked(
-            xfer_lst_to_user_cpi,
-            self.unstaking.lst_amt,
-            self.lst_mint.decimals,
-        )?;
+        if returned_lst_amt > 0 {
+            let xfer_lst_to_user_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
+                TransferChecked {
+                    authority: self.vault.to_account_info(),
+                    from: self.lst_escrow_ata.to_account_info(),
+                    to: self.lst_ata.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                },
+                vault_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(
+                xfer_lst_to_user_cpi,
+                returned_lst_amt,
+                self.lst_mint.decimals,
+            )?;
+        }
 
-        // merge parital_unstaking
+        // ticket lst the utoken no longer covers at the current rate
+        let burn_lst_amt = unwrap_ops!(lst_amt.checked_sub(returned_lst_amt));
+        if burn_lst_amt > 0 {
+            let burn_lst_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
+                Burn {
+                    mint: self.lst_mint.to_account_info(),
+                    from: self.lst_escrow_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                vault_seeds,
+            );
+            token_interface::burn(burn_lst_cpi, burn_lst_amt)?;
+        }
+
+        // merge parital_unstaking, WAGMI only merges it whole
         let merge_partial_unstaking_cpi = CpiContext::new_with_signer(
             self.locked_voter.to_account_info(),
             MergePartialUnstaking {
@@ -49,8 +86,101 @@ impl<'info> MergeUnstake<'info> {
         );
         locked_voter::merge_partial_unstaking(merge_partial_unstaking_cpi)?;
 
+        if self.unstaking.lst_amt == 0 {
+            self.close_lst_escrow_ata(remaining_accounts)?;
+            self.unstaking.close(self.signer.to_account_info())?;
+        } else {
+            // keypair tickets from before UserState cannot be signed for
+            let partial_unstaking_bump = unwrap_ops!(
+                partial_unstaking_bump,
+                NeptuneError::InvalidPartialUnstaking
+            );
+            self.reopen_partial_unstaking(partial_unstaking_bump)?;
+        }
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(UnstakingMerged {
+            vault: self.vault.key(),
+            user: self.signer.key(),
+            unstaking: self.unstaking.key(),
+            utoken_amt,
+            lst_amt,
+            returned_lst_amt,
+            remaining_lst_amt: self.unstaking.lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
         Ok(())
     }
+
+    // the ticket is done with its escrow, anything sent to it goes back
+    // to the ticket owner with the rent
+    fn close_lst_escrow_ata(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        self.lst_escrow_ata.reload()?;
+        let exceeding_amt = self.lst_escrow_ata.amount;
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+
+        if exceeding_amt > 0 {
+            let xfer_exceeding_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.lst_escrow_ata.to_account_info(),
+                    to: self.lst_ata.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                vault_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(xfer_exceeding_cpi, exceeding_amt, self.lst_mint.decimals)?;
+        }
+
+        let close_lst_escrow_ata_cpi = CpiContext::new_with_signer(
+            self.lst_token_program.to_account_info(),
+            CloseAccount {
+                account: self.lst_escrow_ata.to_account_info(),
+                destination: self.signer.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::close_account(close_lst_escrow_ata_cpi)
+    }
+
+    // the rest of the ticket gets a new partial unstaking at the same pda,
+    // its cooldown starts over
+    fn reopen_partial_unstaking(&self, partial_unstaking_bump: u8) -> Result<()> {
+        let unstaking_key = self.unstaking.key();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &self.vault.signer_seeds(),
+            &[
+                Unstaking::PARTIAL_UNSTAKING_SEED,
+                unstaking_key.as_ref(),
+                &[partial_unstaking_bump],
+            ],
+        ];
+        let open_partial_unstaking_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            OpenPartialUnstaking {
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                owner: self.vault.to_account_info(),
+                partial_unstake: self.partial_unstaking.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            signer_seeds,
+        );
+        locked_voter::open_partial_unstaking(
+            open_partial_unstaking_cpi,
+            self.unstaking.utoken_amt,
+            Unstaking::PARTIAL_UNSTAKING_MEMO.to_string(),
+        )
+    }
 }
 
 #[rustfmt::skip]
@@ -71,6 +201,7 @@ pub struct MergeUnstake<'info>{
     pub escrow: Box<Account<'info, Escrow>>,
 
     #[account(
+        mut,
         has_one = escrow
     )]
     pub vault: Box<Account<'info, Vault>>,
@@ -80,7 +211,9 @@ pub struct MergeUnstake<'info>{
     pub partial_unstaking: UncheckedAccount<'info>,
 
     #[account(
+        mut,
         address = vault.lst_mint,
+        mint::token_program = lst_token_program,
     )]
     pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -89,6 +222,7 @@ pub struct MergeUnstake<'info>{
         payer = signer,
         associated_token::mint = lst_mint,
         associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
     )]
     pub lst_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -97,7 +231,6 @@ pub struct MergeUnstake<'info>{
         has_one = partial_unstaking,
         has_one = vault,
         constraint = unstaking.owner == signer.key(),
-        close = signer
     )]
     pub unstaking: Box<Account<'info, Unstaking>>,
 
@@ -109,7 +242,8 @@ pub struct MergeUnstake<'info>{
         ],
         bump,
         token::mint = lst_mint,
-        token::authority = vault
+        token::authority = vault,
+        token::token_program = lst_token_program
     )]
     pub lst_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -118,6 +252,6 @@ pub struct MergeUnstake<'info>{
     #[account(address = lock_voter::ID)]
     pub locked_voter: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,66 @@
+use crate::{state::Vault, utils::grow_account, NeptuneError};
+use anchor_lang::prelude::*;
+
+impl<'info> MigrateVault<'info> {
+    // `base` is the key the vault pda was derived from at creation,
+    // older layouts did not record it
+    pub fn migrate_vault(&mut self, base: Pubkey) -> Result<()> {
+        let vault_info = self.vault.to_account_info();
+        let vault_len = Vault::DISCRIMINATOR.len() + Vault::INIT_SPACE;
+
+        // grow vault to current layout, new fields are zeroed
+        if vault_info.data_len() < vault_len {
+            grow_account(
+                self.owner.to_account_info(),
+                vault_info.clone(),
+                self.system_program.to_account_info(),
+                vault_len,
+            )?;
+        }
+
+        let mut vault = Vault::try_deserialize(&mut &vault_info.try_borrow_data()?[..])?;
+        require_keys_eq!(vault.owner, self.owner.key(), NeptuneError::Unauthorized);
+
+        // every cpi the vault signs needs its seeds
+        vault.base = base;
+        let vault_key = Pubkey::create_program_address(&vault.signer_seeds(), &crate::ID)
+            .map_err(|_| NeptuneError::InvalidVaultBase)?;
+        require_keys_eq!(vault_key, vault_info.key(), NeptuneError::InvalidVaultBase);
+
+        // defaults as in create_vault
+        if vault.voter == Pubkey::default() {
+            vault.voter = vault.owner;
+        }
+        if vault.vote_delegate == Pubkey::default() {
+            vault.vote_delegate = vault_info.key();
+        }
+        // early vaults recorded the utoken mint as lst mint
+        let (lst_mint, lst_mint_bump) = Pubkey::find_program_address(
+            &[Vault::VAULT_LST_MINT, vault_info.key().as_ref()],
+            &crate::ID,
+        );
+        vault.lst_mint = lst_mint;
+        vault.lst_mint_bump = lst_mint_bump;
+
+        vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct MigrateVault<'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: vault in an older layout, checked in handler
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // programs
+    pub system_program: Program<'info, System>,
+}
@@ -1,11 +1,71 @@
+pub use accept_config_admin::*;
+pub use accept_ownership::*;
 pub use begin_unstake::*;
+pub use cast_lst_vote::*;
+pub use cast_vault_vote::*;
+pub use cast_vote::*;
+pub use claim_fees::*;
+pub use compound::*;
+pub use configure_buffer::*;
 pub use create_vault::*;
+pub use create_vault_proposal::*;
+pub use enforce_lock_policy::*;
+pub use extend_lock_duration::*;
+pub use get_vaults::*;
+pub use init_config::*;
+pub use instant_unstake::*;
 pub use merge_unstake::*;
+pub use migrate_vault::*;
+pub use refill_buffer::*;
+pub use register_vault::*;
+pub use resolve_deficit::*;
+pub use set_lock_policy::*;
+pub use set_paused::*;
+pub use set_vote_delegate::*;
+pub use set_voter::*;
 pub use stake::*;
+pub use sweep_escrow_dust::*;
+pub use sync_vault::*;
+pub use transfer_ownership::*;
+pub use update_config::*;
+pub use update_fees_bps::*;
+pub use update_lst_metadata::*;
+pub use withdraw_buffer_refill::*;
+pub use withdraw_lst_vote::*;
 pub use withdraw_unstake::*;
 
+pub mod accept_config_admin;
+pub mod accept_ownership;
 pub mod begin_unstake;
+pub mod cast_lst_vote;
+pub mod cast_vault_vote;
+pub mod cast_vote;
+pub mod claim_fees;
+pub mod compound;
+pub mod configure_buffer;
 pub mod create_vault;
+pub mod create_vault_proposal;
+pub mod enforce_lock_policy;
+pub mod extend_lock_duration;
+pub mod get_vaults;
+pub mod init_config;
+pub mod instant_unstake;
 pub mod merge_unstake;
+pub mod migrate_vault;
+pub mod refill_buffer;
+pub mod register_vault;
+pub mod resolve_deficit;
+pub mod set_lock_policy;
+pub mod set_paused;
+pub mod set_vote_delegate;
+pub mod set_voter;
 pub mod stake;
+pub mod sweep_escrow_dust;
+pub mod sync_vault;
+pub mod transfer_ownership;
+pub mod update_config;
+pub mod update_fees_bps;
+pub mod update_lst_metadata;
+pub mod withdraw_buffer_refill;
+pub mod withdraw_lst_vote;
 pub mod withdraw_unstake;
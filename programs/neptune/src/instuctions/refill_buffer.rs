@@ -0,0 +1,94 @@
+use crate::{
+    events::BufferRefillBegun,
+    lock_voter::{
+        self,
+        accounts::Escrow,
+        cpi::{self as locked_voter, accounts::OpenPartialUnstaking},
+    },
+    state::Vault,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> RefillBuffer<'info> {
+    pub fn refill_buffer(&mut self, buffer_refill_bump: u8) -> Result<()> {
+        // update vault state
+        let refill_amt = self.vault.begin_buffer_refill()?;
+
+        // vault owns the escrow, buffer_refill is the partial unstaking
+        let vault_key = self.vault.key();
+        let signer_seeds: &[&[&[u8]]] = &[
+            &self.vault.signer_seeds(),
+            &[
+                Vault::VAULT_BUFFER_REFILL_SEED,
+                vault_key.as_ref(),
+                &[buffer_refill_bump],
+            ],
+        ];
+        let open_partial_unstaking_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            OpenPartialUnstaking {
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                owner: self.vault.to_account_info(),
+                partial_unstake: self.buffer_refill.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            },
+            signer_seeds,
+        );
+        locked_voter::open_partial_unstaking(
+            open_partial_unstaking_cpi,
+            refill_amt,
+            Vault::BUFFER_REFILL_MEMO.to_string(),
+        )?;
+
+        emit!(BufferRefillBegun {
+            vault: vault_key,
+            buffer_refill: self.buffer_refill.key(),
+            utoken_amt: refill_amt,
+            buffer_utoken_amt: self.vault.buffer_utoken_amt,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct RefillBuffer<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: checked in cpi
+    #[account(mut)]
+    pub locker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    /// CHECK: WAGMI partial unstaking, init in cpi
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_REFILL_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+    )]
+    pub buffer_refill: UncheckedAccount<'info>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,71 @@
+use crate::{
+    events::VaultListed,
+    lock_voter::accounts::{Escrow, Locker},
+    state::{LstMetadata, Vault, VaultEntry, VaultList},
+    utils::grow_account,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> RegisterVault<'info> {
+    pub fn register_vault(&mut self, vault_list_bump: u8, name: String) -> Result<()> {
+        LstMetadata::validate_name(&name)?;
+
+        self.vault_list.locker = self.locker.key();
+        self.vault_list.bump = vault_list_bump;
+
+        grow_account(
+            self.owner.to_account_info(),
+            self.vault_list.to_account_info(),
+            self.system_program.to_account_info(),
+            VaultList::space(self.vault_list.vaults.len() + 1),
+        )?;
+        self.vault_list
+            .add(VaultEntry::new(self.vault.key(), &self.vault, name))?;
+
+        emit!(VaultListed {
+            vault: self.vault.key(),
+            locker: self.locker.key(),
+            vault_list: self.vault_list.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct RegisterVault<'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        // grows by an entry per vault, init_if_needed checks the current size
+        space = VaultList::space(0).max(vault_list.data_len()),
+        seeds = [
+            &VaultList::VAULT_LIST_SEED,
+            locker.key().as_ref()
+        ],
+        bump,
+    )]
+    pub vault_list: Box<Account<'info, VaultList>>,
+
+    // programs
+    pub system_program: Program<'info, System>,
+}
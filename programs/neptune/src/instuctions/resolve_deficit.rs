@@ -0,0 +1,60 @@
+use crate::{
+    events::DeficitResolved, lock_voter::accounts::Escrow, state::Vault, unwrap_ops, NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+impl<'info> ResolveDeficit<'info> {
+    pub fn resolve_deficit(&mut self) -> Result<()> {
+        require!(self.vault.in_deficit, NeptuneError::VaultNotInDeficit);
+
+        // as in sync, only what the escrow's token account holds is paid out,
+        // it must cover tickets and the vault totals again
+        let escrow_utoken_amt = self.escrow.amount.min(self.utoken_escrow_ata.amount);
+        let vault_utoken_amt =
+            unwrap_ops!(escrow_utoken_amt.checked_add(self.vault.buffer_utoken_amt));
+        let owed_utoken_amt = unwrap_ops!(self
+            .vault
+            .total_utoken_staked
+            .checked_add(self.vault.pending_unstake_utoken_amt));
+        require!(
+            vault_utoken_amt >= owed_utoken_amt,
+            NeptuneError::DeficitNotCovered
+        );
+
+        self.vault.in_deficit = false;
+
+        emit!(DeficitResolved {
+            vault: self.vault.key(),
+            escrow_utoken_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct ResolveDeficit<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        address = escrow.tokens
+    )]
+    pub utoken_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+}
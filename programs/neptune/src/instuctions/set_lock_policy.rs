@@ -0,0 +1,62 @@
+use crate::{
+    events::LockPolicySet,
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+    },
+    state::{LockPolicy, Vault},
+    utils::apply_lock_policy,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetLockPolicy<'info> {
+    pub fn set_lock_policy(&mut self, lock_policy: LockPolicy) -> Result<()> {
+        lock_policy.validate(
+            self.locker.params.min_stake_duration,
+            self.locker.params.max_stake_duration,
+        )?;
+        self.vault.lock_policy = lock_policy;
+
+        apply_lock_policy(
+            &self.vault,
+            &mut self.escrow,
+            self.locker.to_account_info(),
+            self.locked_voter.to_account_info(),
+        )?;
+
+        emit!(LockPolicySet {
+            vault: self.vault.key(),
+            lock_policy: self.vault.lock_policy,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SetLockPolicy<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+}
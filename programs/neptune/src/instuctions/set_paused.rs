@@ -0,0 +1,51 @@
+use crate::{
+    events::VaultPauseSet,
+    state::{Vault, VaultList, VaultStatus},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetPaused<'info> {
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.vault.paused = paused;
+        // vaults past a full list are not listed
+        let vault_key = self.vault.key();
+        let entry = self
+            .vault_list
+            .as_mut()
+            .and_then(|vault_list| vault_list.get_mut(&vault_key));
+        if let Some(entry) = entry {
+            entry.status = VaultStatus::of(&self.vault);
+        }
+
+        emit!(VaultPauseSet {
+            vault: vault_key,
+            paused,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SetPaused<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &VaultList::VAULT_LIST_SEED,
+            vault_list.locker.as_ref()
+        ],
+        bump = vault_list.bump,
+    )]
+    // optional, the list is only kept in sync when passed
+    pub vault_list: Option<Box<Account<'info, VaultList>>>,
+}
@@ -0,0 +1,60 @@
+use crate::{
+    events::VoteDelegateSet,
+    lock_voter::{
+        self,
+        accounts::Escrow,
+        cpi::{self as locked_voter, accounts::SetVoteDelegate as LockedVoterSetVoteDelegate},
+    },
+    state::Vault,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> SetVoteDelegate<'info> {
+    pub fn set_vote_delegate(&mut self, new_delegate: Pubkey) -> Result<()> {
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let set_vote_delegate_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            LockedVoterSetVoteDelegate {
+                escrow: self.escrow.to_account_info(),
+                escrow_owner: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::set_vote_delegate(set_vote_delegate_cpi, new_delegate)?;
+
+        // update vault state
+        self.vault.vote_delegate = new_delegate;
+
+        emit!(VoteDelegateSet {
+            vault: self.vault.key(),
+            vote_delegate: new_delegate,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SetVoteDelegate<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+}
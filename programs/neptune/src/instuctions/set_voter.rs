@@ -0,0 +1,22 @@
+use crate::{state::Vault, NeptuneError};
+use anchor_lang::prelude::*;
+
+impl<'info> SetVoter<'info> {
+    pub fn set_voter(&mut self, new_voter: Pubkey) -> Result<()> {
+        self.vault.voter = new_voter;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SetVoter<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
@@ -1,22 +1,52 @@
 use crate::{
+    events::Staked,
     lock_voter::{
         self,
         accounts::{Escrow, Locker},
         cpi::{self as locked_voter, accounts::IncreaseLockedAmount},
     },
     state::Vault,
-    vault_seeds, NeptuneError,
+    unwrap_ops, NeptuneError,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, MintTo},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
 };
 
 impl<'info> Stake<'info> {
-    pub fn stake(&mut self, utoken_amt: u64) -> Result<()> {
+    pub fn stake(&mut self, utoken_amt: u64, min_lst_out: u64) -> Result<()> {
         require!(utoken_amt > 0, NeptuneError::AmtMustGreaterThanZero);
+        require!(!self.vault.paused, NeptuneError::VaultPaused);
+        require!(!self.vault.in_deficit, NeptuneError::VaultInDeficit);
+        let lst_amt = self.vault.get_stake_lst_amt(utoken_amt)?;
+        require!(lst_amt >= min_lst_out, NeptuneError::SlippageExceeded);
+
+        // keep a share liquid for instant unstake
+        let buffer_amt = match &self.utoken_buffer_ata {
+            Some(utoken_buffer_ata) => {
+                let buffer_amt = self.vault.get_buffer_skim_amt(utoken_amt)?;
+                if buffer_amt > 0 {
+                    let xfer_utoken_to_buffer_cpi = CpiContext::new(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: self.utoken_source_ata.to_account_info(),
+                            to: utoken_buffer_ata.to_account_info(),
+                            mint: self.utoken_mint.to_account_info(),
+                            authority: self.signer.to_account_info(),
+                        },
+                    );
+                    token_interface::transfer_checked(
+                        xfer_utoken_to_buffer_cpi,
+                        buffer_amt,
+                        self.utoken_mint.decimals,
+                    )?;
+                }
+                buffer_amt
+            }
+            None => 0,
+        };
+        let lock_amt = unwrap_ops!(utoken_amt.checked_sub(buffer_amt));
 
         // increase stake to locked_voter
         let incease_lock_amt_cpi = CpiContext::new(
@@ -30,14 +60,12 @@ impl<'info> Stake<'info> {
                 token_program: self.token_program.to_account_info(),
             },
         );
-        locked_voter::increase_locked_amount(incease_lock_amt_cpi, utoken_amt)?;
+        locked_voter::increase_locked_amount(incease_lock_amt_cpi, lock_amt)?;
 
         // mint lst to user
-        let lst_amt = self.vault.get_lst_amt(utoken_amt)?;
-        let wagmi_escrow_key = self.escrow.key();
-        let vault_seeds: &[&[&[u8]]] = vault_seeds!(self.vault, wagmi_escrow_key);
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
         let mint_lst_to_user_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.lst_token_program.to_account_info(),
             MintTo {
                 mint: self.lst_mint.to_account_info(),
                 to: self.lst_ata.to_account_info(),
@@ -45,10 +73,25 @@ impl<'info> Stake<'info> {
             },
             vault_seeds,
         );
-        token::mint_to(mint_lst_to_user_cpi, lst_amt)?;
+        token_interface::mint_to(mint_lst_to_user_cpi, lst_amt)?;
 
         // update vault state
         self.vault.stake(utoken_amt, lst_amt)?;
+        self.vault.add_to_buffer(buffer_amt)?;
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(Staked {
+            vault: self.vault.key(),
+            user: self.signer.key(),
+            utoken_amt,
+            lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
 
         Ok(())
     }
@@ -84,14 +127,21 @@ pub struct Stake<'info>{
 
     #[account(
         mut,
-        address = vault.lst_mint
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program
     )]
     pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    #[account(
+        address = locker.token_mint
+    )]
+    pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(
         mut,
         associated_token::mint = locker.token_mint,
         associated_token::authority = signer,
+        associated_token::token_program = token_program,
     )]
     pub utoken_source_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -100,9 +150,23 @@ pub struct Stake<'info>{
         payer = signer,
         associated_token::mint = lst_mint,
         associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
     )]
     pub lst_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    // only if vault has a buffer
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+        token::mint = utoken_mint,
+        token::authority = vault
+    )]
+    pub utoken_buffer_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
 
     // programs
     /// CHECK: check in attr
@@ -110,5 +174,6 @@ pub struct Stake<'info>{
     pub locked_voter: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,217 @@
+use crate::{
+    events::{EscrowDustBurnt, EscrowDustSwept},
+    lock_voter::accounts::{Escrow, Locker},
+    state::{Unstaking, Vault},
+    unwrap_ops,
+    utils::transfer_checked_with_hook,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{
+        self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+impl<'info> SweepEscrowDust<'info> {
+    pub fn sweep_escrow_dust(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let sweep_amt = if self.mint.key() == self.vault.lst_mint {
+            match self.get_lst_dust_amt()? {
+                Some(sweep_amt) => sweep_amt,
+                // a closed ticket has nobody left to return its escrow to
+                None => return self.burn_lst_escrow(),
+            }
+        } else if self.mint.key() == self.locker.token_mint {
+            self.get_utoken_dust_amt()?
+        } else {
+            self.source.amount
+        };
+        require!(sweep_amt > 0, NeptuneError::NothingToSweep);
+        let destination = unwrap_ops!(self.destination.as_ref(), NeptuneError::InvalidSweepSource);
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        let xfer_dust_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.source.to_account_info(),
+                to: destination.to_account_info(),
+                mint: self.mint.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked_with_hook(xfer_dust_cpi, sweep_amt, self.mint.decimals)?;
+
+        emit!(EscrowDustSwept {
+            vault: self.vault.key(),
+            source: self.source.key(),
+            mint: self.mint.key(),
+            destination: destination.key(),
+            amount: sweep_amt,
+        });
+
+        Ok(())
+    }
+
+    // lst held by the vault only sits in unstaking escrows, anything above
+    // the ticket is dust and goes back to the ticket owner,
+    // none once the ticket is closed
+    fn get_lst_dust_amt(&self) -> Result<Option<u64>> {
+        let unstaking = unwrap_ops!(self.unstaking.as_ref(), NeptuneError::InvalidSweepSource);
+        let (lst_escrow_ata, _) = Pubkey::find_program_address(
+            &[
+                Unstaking::UNSTAKING_ESCROW_ATA_SEED,
+                unstaking.key().as_ref(),
+            ],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            lst_escrow_ata,
+            self.source.key(),
+            NeptuneError::InvalidSweepSource
+        );
+
+        if unstaking.owner != &crate::ID || unstaking.data_is_empty() {
+            return Ok(None);
+        }
+
+        let ticket = Unstaking::try_deserialize(&mut &unstaking.try_borrow_data()?[..])?;
+        let destination = unwrap_ops!(self.destination.as_ref(), NeptuneError::InvalidSweepSource);
+        require_keys_eq!(
+            destination.owner,
+            ticket.owner,
+            NeptuneError::InvalidSweepSource
+        );
+
+        Ok(Some(self.source.amount.saturating_sub(ticket.lst_amt)))
+    }
+
+    // utoken only goes to the reward ata for compound to share,
+    // the buffer keeps what the vault counts in it
+    fn get_utoken_dust_amt(&self) -> Result<u64> {
+        let destination = unwrap_ops!(self.destination.as_ref(), NeptuneError::InvalidSweepSource);
+        let utoken_reward_ata = get_associated_token_address_with_program_id(
+            &self.vault.key(),
+            &self.locker.token_mint,
+            &self.token_program.key(),
+        );
+        require_keys_eq!(
+            destination.key(),
+            utoken_reward_ata,
+            NeptuneError::InvalidSweepSource
+        );
+
+        let (utoken_buffer_ata, _) = Pubkey::find_program_address(
+            &[Vault::VAULT_BUFFER_SEED, self.vault.key().as_ref()],
+            &crate::ID,
+        );
+        if self.source.key() == utoken_buffer_ata {
+            return Ok(self
+                .source
+                .amount
+                .saturating_sub(self.vault.buffer_utoken_amt));
+        }
+
+        Ok(self.source.amount)
+    }
+
+    // lst left in a closed ticket's escrow is burnt for the remaining
+    // stakers, the escrow is closed
+    fn burn_lst_escrow(&mut self) -> Result<()> {
+        let burn_lst_amt = self.source.amount;
+        self.vault.unstake(burn_lst_amt, 0)?;
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+        if burn_lst_amt > 0 {
+            let burn_lst_cpi = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.mint.to_account_info(),
+                    from: self.source.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                vault_seeds,
+            );
+            token_interface::burn(burn_lst_cpi, burn_lst_amt)?;
+        }
+
+        let close_lst_escrow_ata_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.source.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::close_account(close_lst_escrow_ata_cpi)?;
+
+        self.mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.mint.supply)?;
+
+        emit!(EscrowDustBurnt {
+            vault: self.vault.key(),
+            source: self.source.key(),
+            amount: burn_lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SweepEscrowDust<'info>{
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = vault,
+        token::token_program = token_program
+    )]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // none when burning a closed ticket's escrow
+    #[account(
+        mut,
+        constraint = destination.key() != source.key() @ NeptuneError::InvalidSweepSource,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: ticket of an lst escrow source, may be closed, checked in ix
+    pub unstaking: Option<UncheckedAccount<'info>>,
+
+    // programs
+    pub token_program: Interface<'info, TokenInterface>,
+}
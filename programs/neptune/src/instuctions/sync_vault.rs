@@ -0,0 +1,76 @@
+use crate::{
+    events::VaultSynced,
+    lock_voter::accounts::Escrow,
+    state::{Config, Vault},
+    unwrap_ops, NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+impl<'info> SyncVault<'info> {
+    pub fn sync_vault(&mut self) -> Result<()> {
+        // escrow amount still holds partial unstakings until withdrawn,
+        // tickets are owed theirs and out of the totals, buffer refill is not.
+        // only what the escrow's token account holds can be paid out,
+        // tickets are paid first
+        let escrow_utoken_amt = self
+            .escrow
+            .amount
+            .min(self.utoken_escrow_ata.amount)
+            .saturating_sub(self.vault.pending_unstake_utoken_amt);
+
+        // buffer is counted in vault too
+        let vault_utoken_amt =
+            unwrap_ops!(escrow_utoken_amt.checked_add(self.vault.buffer_utoken_amt));
+        self.vault
+            .sync(vault_utoken_amt, self.config.protocol_fee_bps)?;
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(VaultSynced {
+            vault: self.vault.key(),
+            escrow_utoken_amt,
+            in_deficit: self.vault.in_deficit,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct SyncVault<'info>{
+    pub signer: Signer<'info>,
+
+    #[account(
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        address = escrow.tokens
+    )]
+    pub utoken_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = vault.lst_mint
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
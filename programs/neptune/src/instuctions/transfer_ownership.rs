@@ -0,0 +1,29 @@
+use crate::{events::OwnershipTransferStarted, state::Vault, NeptuneError};
+use anchor_lang::prelude::*;
+
+impl<'info> TransferOwnership<'info> {
+    pub fn transfer_ownership(&mut self, new_owner: Pubkey) -> Result<()> {
+        // new owner must accept
+        self.vault.pending_owner = new_owner;
+
+        emit!(OwnershipTransferStarted {
+            vault: self.vault.key(),
+            owner: self.vault.owner,
+            pending_owner: new_owner,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct TransferOwnership<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+}
@@ -0,0 +1,36 @@
+use crate::{
+    state::{Config, ConfigParams},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(&mut self, params: ConfigParams) -> Result<()> {
+        self.config.set_params(params)
+    }
+
+    pub fn set_creator(&mut self, creator: Pubkey, allowed: bool) -> Result<()> {
+        self.config.set_creator(creator, allowed)
+    }
+
+    pub fn transfer_config_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        // new admin must accept
+        self.config.pending_admin = new_admin;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info>{
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ NeptuneError::Unauthorized
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
@@ -0,0 +1,59 @@
+use crate::{
+    events::FeesBpsUpdated,
+    state::{Config, Vault, VaultList},
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+
+impl<'info> UpdateFeesBps<'info> {
+    pub fn update_fees_bps(&mut self, fees_bps: u16) -> Result<()> {
+        self.config.check_fees_bps(fees_bps)?;
+        // only applies to rewards added from now on
+        self.vault.set_fees_bps(fees_bps)?;
+        // vaults past a full list are not listed
+        let vault_key = self.vault.key();
+        let entry = self
+            .vault_list
+            .as_mut()
+            .and_then(|vault_list| vault_list.get_mut(&vault_key));
+        if let Some(entry) = entry {
+            entry.fees_bps = fees_bps;
+        }
+
+        emit!(FeesBpsUpdated {
+            vault: vault_key,
+            fees_bps,
+        });
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct UpdateFeesBps<'info>{
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &VaultList::VAULT_LIST_SEED,
+            vault_list.locker.as_ref()
+        ],
+        bump = vault_list.bump,
+    )]
+    // optional, the list is only kept in sync when passed
+    pub vault_list: Option<Box<Account<'info, VaultList>>>,
+
+    #[account(
+        seeds = [&Config::CONFIG_SEED],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
@@ -0,0 +1,140 @@
+use crate::{
+    state::{LstMetadata, Vault, VaultList},
+    utils::top_up_rent,
+    NeptuneError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{self, Metadata, UpdateMetadataAccountsV2},
+    token_2022,
+    token_interface::{
+        self,
+        spl_token_2022::{
+            extension::{BaseStateWithExtensions, StateWithExtensions},
+            state::Mint as MintState,
+        },
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        Mint, TokenInterface, TokenMetadataUpdateField,
+    },
+};
+
+impl<'info> UpdateLstMetadata<'info> {
+    pub fn update_lst_metadata(&mut self, metadata: LstMetadata) -> Result<()> {
+        metadata.validate()?;
+        // vaults past a full list are not listed
+        let vault_key = self.vault.key();
+        let entry = self
+            .vault_list
+            .as_mut()
+            .and_then(|vault_list| vault_list.get_mut(&vault_key));
+        if let Some(entry) = entry {
+            entry.name = metadata.name.clone();
+        }
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+
+        if self.lst_token_program.key() == token_2022::ID {
+            // metadata extension reallocs the mint without paying rent
+            let lst_mint_info = self.lst_mint.to_account_info();
+            let cur_metadata_len = {
+                let lst_mint_data = lst_mint_info.try_borrow_data()?;
+                StateWithExtensions::<MintState>::unpack(&lst_mint_data)?
+                    .get_variable_len_extension::<TokenMetadata>()?
+                    .tlv_size_of()?
+            };
+            let new_metadata_len = metadata
+                .to_token_metadata(self.vault.key(), self.lst_mint.key())
+                .tlv_size_of()?;
+            let lst_mint_len =
+                (lst_mint_info.data_len() + new_metadata_len).saturating_sub(cur_metadata_len);
+            top_up_rent(
+                self.owner.to_account_info(),
+                lst_mint_info,
+                self.system_program.to_account_info(),
+                lst_mint_len,
+            )?;
+
+            for (field, value) in [
+                (Field::Name, metadata.name),
+                (Field::Symbol, metadata.symbol),
+                (Field::Uri, metadata.uri),
+            ] {
+                let update_field_cpi = CpiContext::new_with_signer(
+                    self.lst_token_program.to_account_info(),
+                    TokenMetadataUpdateField {
+                        program_id: self.lst_token_program.to_account_info(),
+                        metadata: self.lst_mint.to_account_info(),
+                        update_authority: self.vault.to_account_info(),
+                    },
+                    vault_seeds,
+                );
+                token_interface::token_metadata_update_field(update_field_cpi, field, value)?;
+            }
+
+            return Ok(());
+        }
+
+        let (Some(lst_metadata), Some(metadata_program)) =
+            (&self.lst_metadata, &self.metadata_program)
+        else {
+            return err!(NeptuneError::MissingMetadataAccounts);
+        };
+
+        let update_metadata_cpi = CpiContext::new_with_signer(
+            metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: lst_metadata.to_account_info(),
+                update_authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        metadata::update_metadata_accounts_v2(
+            update_metadata_cpi,
+            None,
+            Some(metadata.to_data_v2()),
+            None,
+            None,
+        )
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct UpdateLstMetadata<'info>{
+    // pays rent if token-2022 metadata grows
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner @ NeptuneError::Unauthorized
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &VaultList::VAULT_LIST_SEED,
+            vault_list.locker.as_ref()
+        ],
+        bump = vault_list.bump,
+    )]
+    // optional, the list is only kept in sync when passed
+    pub vault_list: Option<Box<Account<'info, VaultList>>>,
+
+    #[account(
+        mut,
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // spl lst mint only, token-2022 mints hold their own metadata
+    /// CHECK: metaplex metadata pda, checked in cpi
+    #[account(mut)]
+    pub lst_metadata: Option<UncheckedAccount<'info>>,
+
+    // programs
+    pub lst_token_program: Interface<'info, TokenInterface>,
+    pub metadata_program: Option<Program<'info, Metadata>>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,101 @@
+use crate::{
+    lock_voter::{
+        self,
+        accounts::{Escrow, Locker},
+        cpi::{self as locked_voter, accounts::WithdrawPartialUnstaking},
+    },
+    state::Vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+impl<'info> WithdrawBufferRefill<'info> {
+    pub fn withdraw_buffer_refill(&mut self) -> Result<()> {
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+
+        // withdraw partial unstaking into buffer
+        let withdraw_partial_unstaking_cpi = CpiContext::new_with_signer(
+            self.locked_voter.to_account_info(),
+            WithdrawPartialUnstaking {
+                payer: self.signer.to_account_info(),
+                locker: self.locker.to_account_info(),
+                escrow: self.escrow.to_account_info(),
+                owner: self.vault.to_account_info(),
+                partial_unstake: self.buffer_refill.to_account_info(),
+                escrow_tokens: self.utoken_escrow_ata.to_account_info(),
+                destination_tokens: self.utoken_buffer_ata.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::withdraw_partial_unstaking(withdraw_partial_unstaking_cpi)?;
+
+        // update vault state
+        self.vault.end_buffer_refill()?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct WithdrawBufferRefill<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub locker: Box<Account<'info, Locker>>,
+
+    #[account(
+        mut,
+        has_one = locker,
+        constraint = escrow.owner == vault.key()
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    #[account(
+        mut,
+        has_one = escrow
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: check in cpi
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_REFILL_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+    )]
+    pub buffer_refill: UncheckedAccount<'info>,
+
+    #[account(
+        address = locker.token_mint,
+    )]
+    pub utoken_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &Vault::VAULT_BUFFER_SEED,
+            vault.key().as_ref()
+        ],
+        bump,
+        token::mint = utoken_mint,
+        token::authority = vault
+    )]
+    pub utoken_buffer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = escrow.tokens
+    )]
+    pub utoken_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    /// CHECK: check in attr
+    #[account(address = lock_voter::ID)]
+    pub locked_voter: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
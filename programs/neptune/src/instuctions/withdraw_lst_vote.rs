@@ -0,0 +1,109 @@
+use crate::{
+    state::{LstVote, Vault, VaultProposal},
+    utils::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+impl<'info> WithdrawLstVote<'info> {
+    pub fn withdraw_lst_vote(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // withdrawing before voting ends takes the vote back
+        if self.vault_proposal.is_voting(Clock::get()?.unix_timestamp) {
+            self.vault_proposal
+                .remove_vote(self.lst_vote.side, self.lst_vote.weight)?;
+        }
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
+
+        // return lst to user
+        let xfer_lst_to_user_cpi = CpiContext::new_with_signer(
+            self.lst_token_program.to_account_info(),
+            TransferChecked {
+                authority: self.vault.to_account_info(),
+                from: self.lst_vote_escrow_ata.to_account_info(),
+                to: self.lst_ata.to_account_info(),
+                mint: self.lst_mint.to_account_info(),
+            },
+            vault_seeds,
+        )
+        .with_remaining_accounts(remaining_accounts.to_vec());
+        transfer_checked_with_hook(
+            xfer_lst_to_user_cpi,
+            self.lst_vote.weight,
+            self.lst_mint.decimals,
+        )?;
+
+        // close lst_vote_escrow_ata
+        let close_lst_vote_escrow_ata_cpi = CpiContext::new_with_signer(
+            self.lst_token_program.to_account_info(),
+            CloseAccount {
+                account: self.lst_vote_escrow_ata.to_account_info(),
+                destination: self.signer.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        token_interface::close_account(close_lst_vote_escrow_ata_cpi)?;
+
+        Ok(())
+    }
+}
+
+#[rustfmt::skip]
+#[derive(Accounts)]
+pub struct WithdrawLstVote<'info>{
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub vault: Box<Account<'info, Vault>>,
+
+    #[account(
+        mut,
+        has_one = vault
+    )]
+    pub vault_proposal: Box<Account<'info, VaultProposal>>,
+
+    #[account(
+        address = vault.lst_mint,
+        mint::token_program = lst_token_program,
+    )]
+    pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        has_one = vault_proposal,
+        constraint = lst_vote.owner == signer.key(),
+        close = signer
+    )]
+    pub lst_vote: Box<Account<'info, LstVote>>,
+
+    #[account(
+        mut,
+        seeds = [
+            &LstVote::LST_VOTE_ESCROW_ATA_SEED,
+            lst_vote.key().as_ref()
+        ],
+        bump,
+        token::mint = lst_mint,
+        token::authority = vault,
+        token::token_program = lst_token_program
+    )]
+    pub lst_vote_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = lst_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
+    )]
+    pub lst_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
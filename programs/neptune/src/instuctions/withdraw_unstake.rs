@@ -1,23 +1,71 @@
 use crate::{
+    events::UnstakeWithdrawn,
     lock_voter::{
         self,
         accounts::{Escrow, Locker},
-        cpi::{self as locked_voter, accounts::WithdrawPartialUnstaking},
+        cpi::{
+            self as locked_voter,
+            accounts::{IncreaseLockedAmount, WithdrawPartialUnstaking},
+        },
     },
     state::{Unstaking, Vault},
-    unwrap_ops, vault_seeds, NeptuneError,
+    unwrap_ops,
+    utils::transfer_checked_with_hook,
+    NeptuneError,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Burn, CloseAccount, TransferChecked},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{
+        self, Burn, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 impl<'info> WithdrawUnstake<'info> {
-    pub fn withdraw_unstake(&mut self) -> Result<()> {
-        let vault_key = self.vault.key();
-        let vault_seeds: &[&[&[u8]]] = vault_seeds!(self.vault, vault_key);
+    pub fn withdraw_unstake(
+        &mut self,
+        lst_amt: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // handle ATA amt > escrowed lst amt
+        let exceeding_amt = unwrap_ops!(
+            self.lst_escrow_ata
+                .amount
+                .checked_sub(self.unstaking.lst_amt),
+            NeptuneError::EscrowAmtIsNotCorrect
+        );
+        let unstaking_key = self.unstaking.key();
+        let partial_unstaking_bump = self.unstaking.find_partial_unstaking_bump(&unstaking_key);
+        let utoken_amt = self.unstaking.split(lst_amt)?;
+        // what is left of the ticket goes back to the vault
+        let (restake_lst_amt, restake_utoken_amt) =
+            (self.unstaking.lst_amt, self.unstaking.utoken_amt);
+        if restake_lst_amt > 0 {
+            require!(!self.vault.paused, NeptuneError::VaultPaused);
+        }
+
+        // update vault state
+        let returned_lst_amt = match partial_unstaking_bump {
+            Some(_) => {
+                self.vault.end_unstake(lst_amt, utoken_amt)?;
+                if restake_lst_amt > 0 {
+                    self.vault
+                        .cancel_unstake(restake_lst_amt, restake_utoken_amt)?
+                } else {
+                    0
+                }
+            }
+            // legacy tickets never left the totals, the rest stays in
+            None => {
+                self.vault.unstake(lst_amt, utoken_amt)?;
+                restake_lst_amt
+            }
+        };
+        let burn_lst_amt = unwrap_ops!(lst_amt
+            .checked_add(restake_lst_amt)
+            .and_then(|amt| amt.checked_sub(returned_lst_amt)));
+
+        let vault_seeds: &[&[&[u8]]] = &[&self.vault.signer_seeds()];
 
         // withdraw partial unstaking
         let withdraw_partial_unstaking_cpi = CpiContext::new_with_signer(
@@ -36,34 +84,60 @@ impl<'info> WithdrawUnstake<'info> {
         );
         locked_voter::withdraw_partial_unstaking(withdraw_partial_unstaking_cpi)?;
 
-        // update vault state
-        self.vault
-            .unstake(self.unstaking.lst_amt, self.unstaking.utoken_amt)?;
+        if restake_lst_amt > 0 {
+            // WAGMI paid out the whole ticket, lock the rest again
+            let increase_lock_amt_cpi = CpiContext::new(
+                self.locked_voter.to_account_info(),
+                IncreaseLockedAmount {
+                    payer: self.signer.to_account_info(),
+                    locker: self.locker.to_account_info(),
+                    escrow: self.escrow.to_account_info(),
+                    source_tokens: self.utoken_target_ata.to_account_info(),
+                    escrow_tokens: self.utoken_escrow_ata.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            );
+            locked_voter::increase_locked_amount(increase_lock_amt_cpi, restake_utoken_amt)?;
+        }
 
-        // handle ATA amt > escrowed lst amt
-        let exceeding_amt = unwrap_ops!(
-            self.lst_escrow_ata
-                .amount
-                .checked_sub(self.unstaking.lst_amt),
-            NeptuneError::EscrowAmtIsNotCorrect
-        );
+        if returned_lst_amt > 0 {
+            // its lst was never burnt, return it
+            let xfer_lst_to_user_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.lst_escrow_ata.to_account_info(),
+                    to: self.lst_ata.to_account_info(),
+                    mint: self.lst_mint.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                vault_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(
+                xfer_lst_to_user_cpi,
+                returned_lst_amt,
+                self.lst_mint.decimals,
+            )?;
+        }
 
         if exceeding_amt > 0 {
             // xfer exceeding back to user
-            let xfer_exceeding_cpi = CpiContext::new(
-                self.token_program.to_account_info(),
+            let xfer_exceeding_cpi = CpiContext::new_with_signer(
+                self.lst_token_program.to_account_info(),
                 TransferChecked {
                     from: self.lst_escrow_ata.to_account_info(),
                     to: self.lst_ata.to_account_info(),
                     mint: self.lst_mint.to_account_info(),
                     authority: self.vault.to_account_info(),
                 },
-            );
-            token::transfer_checked(xfer_exceeding_cpi, exceeding_amt, self.lst_mint.decimals)?;
+                vault_seeds,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            transfer_checked_with_hook(xfer_exceeding_cpi, exceeding_amt, self.lst_mint.decimals)?;
         }
         // burn lst
         let burn_lst_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.lst_token_program.to_account_info(),
             Burn {
                 mint: self.lst_mint.to_account_info(),
                 from: self.lst_escrow_ata.to_account_info(),
@@ -71,11 +145,11 @@ impl<'info> WithdrawUnstake<'info> {
             },
             vault_seeds,
         );
-        token::burn(burn_lst_cpi, self.unstaking.lst_amt)?;
+        token_interface::burn(burn_lst_cpi, burn_lst_amt)?;
 
         // close lst_escrow_ata
         let close_lst_escrow_ata_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
+            self.lst_token_program.to_account_info(),
             CloseAccount {
                 account: self.lst_escrow_ata.to_account_info(),
                 destination: self.signer.to_account_info(),
@@ -83,7 +157,23 @@ impl<'info> WithdrawUnstake<'info> {
             },
             vault_seeds,
         );
-        token::close_account(close_lst_escrow_ata_cpi)?;
+        token_interface::close_account(close_lst_escrow_ata_cpi)?;
+
+        self.lst_mint.reload()?;
+        self.vault
+            .assert_invariants(&self.vault.key(), self.lst_mint.supply)?;
+
+        emit!(UnstakeWithdrawn {
+            vault: self.vault.key(),
+            user: self.signer.key(),
+            unstaking: self.unstaking.key(),
+            utoken_amt,
+            lst_amt,
+            restaked_lst_amt: returned_lst_amt,
+            total_lst_minted: self.vault.total_lst_minted,
+            total_utoken_staked: self.vault.total_utoken_staked,
+            exchange_rate: self.vault.get_exchange_rate()?,
+        });
 
         Ok(())
     }
@@ -118,6 +208,7 @@ pub struct WithdrawUnstake<'info>{
     #[account(
         mut,
         address = vault.lst_mint,
+        mint::token_program = lst_token_program,
     )]
     pub lst_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -138,7 +229,8 @@ pub struct WithdrawUnstake<'info>{
         ],
         bump,
         token::mint = lst_mint,
-        token::authority = vault
+        token::authority = vault,
+        token::token_program = lst_token_program
     )]
     pub lst_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -147,6 +239,7 @@ pub struct WithdrawUnstake<'info>{
         payer = signer,
         associated_token::mint = lst_mint,
         associated_token::authority = signer,
+        associated_token::token_program = lst_token_program,
     )]
     pub lst_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -161,7 +254,7 @@ pub struct WithdrawUnstake<'info>{
         payer = signer,
         associated_token::mint = utoken_mint,
         associated_token::authority = signer,
-
+        associated_token::token_program = token_program,
     )]
     pub utoken_target_ata: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -177,5 +270,6 @@ pub struct WithdrawUnstake<'info>{
     pub locked_voter: UncheckedAccount<'info>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub lst_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -1,36 +1,243 @@
 use anchor_lang::prelude::*;
 use instuctions::*;
+use state::{ConfigParams, FeePayout, LockPolicy, LstMetadata, VaultPage};
 
+pub mod events;
 pub mod instuctions;
 pub mod macros;
 pub mod state;
+pub mod utils;
 
 declare_id!("DxQiCxj7hPw5oCXt4uMxXrsp1CLBmRUXzZczUwH9C5VU");
 
 declare_program!(lock_voter);
+declare_program!(govern);
 
 #[program]
 pub mod neptune {
     use super::*;
 
-    pub fn create_vault(ctx: Context<CreateVault>, fees_bps: u16) -> Result<()> {
-        ctx.accounts.create_vault(ctx.bumps.vault, fees_bps)
+    // once, by the program upgrade authority
+    pub fn init_config(
+        ctx: Context<InitConfig>,
+        admin: Pubkey,
+        params: ConfigParams,
+    ) -> Result<()> {
+        ctx.accounts.init_config(ctx.bumps.config, admin, params)
     }
 
-    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        ctx.accounts.stake(amount)
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.update_config(params)
     }
 
-    pub fn begin_unstaking(ctx: Context<BeginUnstaking>, amount: u64) -> Result<()> {
-        ctx.accounts.begin_unstaking(amount)
+    // adds `creator` to the vault creation allow-list, removes it if not `allowed`
+    pub fn set_creator(ctx: Context<UpdateConfig>, creator: Pubkey, allowed: bool) -> Result<()> {
+        ctx.accounts.set_creator(creator, allowed)
     }
 
-    pub fn merge_unstaking(ctx: Context<MergeUnstake>) -> Result<()> {
-        ctx.accounts.merge_unstaking()
+    pub fn transfer_config_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_config_admin(new_admin)
     }
 
-    pub fn withdraw_unstake(ctx: Context<WithdrawUnstake>) -> Result<()> {
-        ctx.accounts.withdraw_unstake()
+    pub fn accept_config_admin(ctx: Context<AcceptConfigAdmin>) -> Result<()> {
+        ctx.accounts.accept_config_admin()
+    }
+
+    pub fn create_vault(
+        ctx: Context<CreateVault>,
+        fees_bps: u16,
+        transfer_hook_program: Option<Pubkey>,
+        metadata: LstMetadata,
+    ) -> Result<()> {
+        ctx.accounts.create_vault(
+            ctx.bumps.vault,
+            ctx.bumps.lst_mint,
+            ctx.bumps.vault_list,
+            fees_bps,
+            transfer_hook_program,
+            metadata,
+        )
+    }
+
+    // fails if fewer than `min_lst_out` lst would be minted
+    pub fn stake(ctx: Context<Stake>, amount: u64, min_lst_out: u64) -> Result<()> {
+        ctx.accounts.stake(amount, min_lst_out)
+    }
+
+    // fails if the ticket would be worth less than `min_utoken_out`
+    pub fn begin_unstaking<'info>(
+        ctx: Context<'_, '_, '_, 'info, BeginUnstaking<'info>>,
+        amount: u64,
+        min_utoken_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.begin_unstaking(
+            amount,
+            min_utoken_out,
+            ctx.bumps.user_state,
+            ctx.bumps.partial_unstaking,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn merge_unstaking<'info>(
+        ctx: Context<'_, '_, '_, 'info, MergeUnstake<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.unstaking.lst_amt;
+        ctx.accounts.merge_unstaking(amount, ctx.remaining_accounts)
+    }
+
+    // returns `amount` lst, the rest of the ticket restarts its cooldown
+    pub fn partial_merge_unstaking<'info>(
+        ctx: Context<'_, '_, '_, 'info, MergeUnstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.merge_unstaking(amount, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnstake<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.unstaking.lst_amt;
+        ctx.accounts.withdraw_unstake(amount, ctx.remaining_accounts)
+    }
+
+    // withdraws `amount` lst worth of utoken, the rest is staked back
+    pub fn partial_withdraw_unstake<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnstake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_unstake(amount, ctx.remaining_accounts)
+    }
+
+    pub fn configure_buffer(
+        ctx: Context<ConfigureBuffer>,
+        buffer_bps: u16,
+        instant_unstake_fee_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .configure_buffer(buffer_bps, instant_unstake_fee_bps)
+    }
+
+    pub fn instant_unstake(ctx: Context<InstantUnstake>, amount: u64) -> Result<()> {
+        ctx.accounts.instant_unstake(amount)
+    }
+
+    pub fn refill_buffer(ctx: Context<RefillBuffer>) -> Result<()> {
+        ctx.accounts.refill_buffer(ctx.bumps.buffer_refill)
+    }
+
+    pub fn withdraw_buffer_refill(ctx: Context<WithdrawBufferRefill>) -> Result<()> {
+        ctx.accounts.withdraw_buffer_refill()
+    }
+
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        ctx.accounts.compound()
+    }
+
+    pub fn sync_vault(ctx: Context<SyncVault>) -> Result<()> {
+        ctx.accounts.sync_vault()
+    }
+
+    pub fn resolve_deficit(ctx: Context<ResolveDeficit>) -> Result<()> {
+        ctx.accounts.resolve_deficit()
+    }
+
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_ownership(new_owner)
+    }
+
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        ctx.accounts.accept_ownership()
+    }
+
+    pub fn update_fees_bps(ctx: Context<UpdateFeesBps>, fees_bps: u16) -> Result<()> {
+        ctx.accounts.update_fees_bps(fees_bps)
+    }
+
+    pub fn update_lst_metadata(
+        ctx: Context<UpdateLstMetadata>,
+        metadata: LstMetadata,
+    ) -> Result<()> {
+        ctx.accounts.update_lst_metadata(metadata)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>, base: Pubkey) -> Result<()> {
+        ctx.accounts.migrate_vault(base)
+    }
+
+    pub fn set_lock_policy(ctx: Context<SetLockPolicy>, lock_policy: LockPolicy) -> Result<()> {
+        ctx.accounts.set_lock_policy(lock_policy)
+    }
+
+    pub fn extend_lock_duration(ctx: Context<ExtendLockDuration>, duration: i64) -> Result<()> {
+        ctx.accounts.extend_lock_duration(duration)
+    }
+
+    pub fn enforce_lock_policy(ctx: Context<EnforceLockPolicy>) -> Result<()> {
+        ctx.accounts.enforce_lock_policy()
+    }
+
+    pub fn set_voter(ctx: Context<SetVoter>, new_voter: Pubkey) -> Result<()> {
+        ctx.accounts.set_voter(new_voter)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, side: u8) -> Result<()> {
+        ctx.accounts.cast_vote(side)
+    }
+
+    pub fn set_vote_delegate(ctx: Context<SetVoteDelegate>, new_delegate: Pubkey) -> Result<()> {
+        ctx.accounts.set_vote_delegate(new_delegate)
+    }
+
+    pub fn create_vault_proposal(ctx: Context<CreateVaultProposal>) -> Result<()> {
+        ctx.accounts.create_vault_proposal(ctx.bumps.vault_proposal)
+    }
+
+    pub fn cast_lst_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, CastLstVote<'info>>,
+        side: u8,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .cast_lst_vote(ctx.bumps.lst_vote, side, amount, ctx.remaining_accounts)
+    }
+
+    pub fn withdraw_lst_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawLstVote<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_lst_vote(ctx.remaining_accounts)
+    }
+
+    pub fn cast_vault_vote(ctx: Context<CastVaultVote>) -> Result<()> {
+        ctx.accounts.cast_vault_vote()
+    }
+
+    pub fn claim_owner_fees(ctx: Context<ClaimFees>, payout: FeePayout) -> Result<()> {
+        ctx.accounts.claim_owner_fees(payout)
+    }
+
+    pub fn claim_protocol_fees(ctx: Context<ClaimFees>, payout: FeePayout) -> Result<()> {
+        ctx.accounts.claim_protocol_fees(payout)
+    }
+
+    // lists a vault created before vault lists, under its lst name
+    pub fn register_vault(ctx: Context<RegisterVault>, name: String) -> Result<()> {
+        ctx.accounts.register_vault(ctx.bumps.vault_list, name)
+    }
+
+    // view, up to VaultList::MAX_PAGE_LEN entries from `offset`
+    pub fn get_vaults(ctx: Context<GetVaults>, offset: u32, limit: u8) -> Result<VaultPage> {
+        Ok(ctx.accounts.get_vaults(offset, limit))
+    }
+
+    pub fn sweep_escrow_dust<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepEscrowDust<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.sweep_escrow_dust(ctx.remaining_accounts)
     }
 }
 
@@ -46,4 +253,38 @@ pub enum NeptuneError {
     AmtMustGreaterThanZero,
     InvalidBPS,
     EscrowAmtIsNotCorrect,
+    NoFeesToClaim,
+    VaultInDeficit,
+    VaultNotInDeficit,
+    InvalidVoteSide,
+    NoLstVotes,
+    ProposalNotActive,
+    VotingEnded,
+    VaultPaused,
+    InsufficientBuffer,
+    BufferIsFull,
+    BufferRefillPending,
+    InvalidLockPolicy,
+    LockPolicyInEffect,
+    NoStakers,
+    VaultInvariantViolated,
+    InvalidTokenProgram,
+    InvalidLstMetadata,
+    MissingMetadataAccounts,
+    InvalidPartialUnstaking,
+    FirstStakeTooSmall,
+    SlippageExceeded,
+    NothingToSweep,
+    InvalidSweepSource,
+    VaultAlreadyListed,
+    VaultNotListed,
+    TooManyCreators,
+    FeesAboveProtocolMax,
+    InvalidFeeDestination,
+    VaultProposalExists,
+    VoteDelegated,
+    InvalidVaultBase,
+    VaultListFull,
+    LstVoteTied,
+    DeficitNotCovered,
 }
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
+
+use crate::NeptuneError;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConfigParams {
+    // anyone may create vaults, the creator allow-list is ignored
+    pub permissionless: bool,
+    // cap on every vault's fees_bps
+    pub max_fees_bps: u16,
+    // share of every vault fee owed to the protocol
+    pub protocol_fee_bps: u16,
+    // owns the token accounts protocol fees are paid to
+    pub treasury: Pubkey,
+}
+
+// protocol wide settings, one per deployment
+#[account]
+#[derive(InitSpace, Debug, Default)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub bump: u8,
+    pub permissionless: bool,
+    pub max_fees_bps: u16,
+    pub protocol_fee_bps: u16,
+    pub treasury: Pubkey,
+    // may create vaults unless permissionless
+    #[max_len(16)]
+    pub creators: Vec<Pubkey>,
+}
+
+impl Config {
+    pub const CONFIG_SEED: &'static [u8] = b"config";
+    pub const MAX_CREATORS: usize = 16;
+
+    pub fn set_params(&mut self, params: ConfigParams) -> Result<()> {
+        require!(
+            params.max_fees_bps < MAX_FEE_BASIS_POINTS,
+            NeptuneError::InvalidBPS
+        );
+        require!(
+            params.protocol_fee_bps <= MAX_FEE_BASIS_POINTS,
+            NeptuneError::InvalidBPS
+        );
+
+        self.permissionless = params.permissionless;
+        self.max_fees_bps = params.max_fees_bps;
+        self.protocol_fee_bps = params.protocol_fee_bps;
+        self.treasury = params.treasury;
+
+        Ok(())
+    }
+
+    pub fn set_creator(&mut self, creator: Pubkey, allowed: bool) -> Result<()> {
+        if !allowed {
+            self.creators.retain(|cur| cur != &creator);
+        } else if !self.creators.contains(&creator) {
+            require!(
+                self.creators.len() < Self::MAX_CREATORS,
+                NeptuneError::TooManyCreators
+            );
+            self.creators.push(creator);
+        }
+
+        Ok(())
+    }
+
+    pub fn can_create_vault(&self, creator: &Pubkey) -> bool {
+        self.permissionless || self.creators.contains(creator)
+    }
+
+    // applies to new vaults & fee updates, existing fees are kept
+    pub fn check_fees_bps(&self, fees_bps: u16) -> Result<()> {
+        require!(
+            fees_bps <= self.max_fees_bps,
+            NeptuneError::FeesAboveProtocolMax
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn params() -> ConfigParams {
+        ConfigParams {
+            permissionless: false,
+            max_fees_bps: 2_000,
+            protocol_fee_bps: 1_000,
+            treasury: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn test_set_params() {
+        let mut config = Config::default();
+        config.set_params(params()).unwrap();
+        assert_eq!(config.max_fees_bps, 2_000);
+        assert_eq!(config.protocol_fee_bps, 1_000);
+
+        // the whole fee may go to the protocol, never the whole reward to fees
+        config
+            .set_params(ConfigParams {
+                protocol_fee_bps: MAX_FEE_BASIS_POINTS,
+                ..params()
+            })
+            .unwrap();
+        assert!(config
+            .set_params(ConfigParams {
+                max_fees_bps: MAX_FEE_BASIS_POINTS,
+                ..params()
+            })
+            .is_err());
+        assert!(config
+            .set_params(ConfigParams {
+                protocol_fee_bps: MAX_FEE_BASIS_POINTS + 1,
+                ..params()
+            })
+            .is_err());
+        assert_eq!(config.protocol_fee_bps, MAX_FEE_BASIS_POINTS);
+    }
+
+    #[test]
+    fn test_set_creator() {
+        let mut config = Config::default();
+        config.set_params(params()).unwrap();
+        let creator = Pubkey::new_unique();
+        assert!(!config.can_create_vault(&creator));
+
+        config.set_creator(creator, true).unwrap();
+        config.set_creator(creator, true).unwrap();
+        assert_eq!(config.creators, vec![creator]);
+        assert!(config.can_create_vault(&creator));
+        assert!(!config.can_create_vault(&Pubkey::new_unique()));
+
+        config.set_creator(creator, false).unwrap();
+        assert!(config.creators.is_empty());
+        assert!(!config.can_create_vault(&creator));
+
+        // anyone when permissionless
+        config.permissionless = true;
+        assert!(config.can_create_vault(&creator));
+
+        for _ in 0..Config::MAX_CREATORS {
+            config.set_creator(Pubkey::new_unique(), true).unwrap();
+        }
+        assert!(config.set_creator(creator, true).is_err());
+    }
+
+    #[test]
+    fn test_check_fees_bps() {
+        let mut config = Config::default();
+        config.set_params(params()).unwrap();
+        config.check_fees_bps(2_000).unwrap();
+        assert!(config.check_fees_bps(2_001).is_err());
+    }
+}
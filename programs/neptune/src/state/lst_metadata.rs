@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::mpl_token_metadata::types::DataV2,
+    token_interface::{
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::TokenMetadata,
+    },
+};
+
+use crate::NeptuneError;
+
+// lst name, symbol & uri, written by metaplex for spl mints
+// or by the metadata extension for token-2022 mints
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LstMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl LstMetadata {
+    // metaplex limits, kept for token-2022 so both look the same in wallets
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_SYMBOL_LEN: usize = 10;
+    pub const MAX_URI_LEN: usize = 200;
+
+    pub fn validate_name(name: &str) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= Self::MAX_NAME_LEN,
+            NeptuneError::InvalidLstMetadata
+        );
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        Self::validate_name(&self.name)?;
+        require!(
+            !self.symbol.is_empty() && self.symbol.len() <= Self::MAX_SYMBOL_LEN,
+            NeptuneError::InvalidLstMetadata
+        );
+        require!(
+            self.uri.len() <= Self::MAX_URI_LEN,
+            NeptuneError::InvalidLstMetadata
+        );
+
+        Ok(())
+    }
+
+    pub fn to_data_v2(&self) -> DataV2 {
+        DataV2 {
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+
+    pub fn to_token_metadata(&self, update_authority: Pubkey, mint: Pubkey) -> TokenMetadata {
+        TokenMetadata {
+            update_authority: OptionalNonZeroPubkey(update_authority),
+            mint,
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+            uri: self.uri.clone(),
+            additional_metadata: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::LstMetadata;
+    use anchor_lang::prelude::Pubkey;
+
+    fn new_lst_metadata() -> LstMetadata {
+        LstMetadata {
+            name: "Neptune JUP".to_string(),
+            symbol: "nJUP".to_string(),
+            uri: "https://example.com/njup.json".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(new_lst_metadata().validate().is_ok());
+        assert!(
+            LstMetadata {
+                uri: String::new(),
+                ..new_lst_metadata()
+            }
+            .validate()
+            .is_ok(),
+            "uri is optional"
+        );
+
+        assert!(
+            LstMetadata {
+                name: String::new(),
+                ..new_lst_metadata()
+            }
+            .validate()
+            .is_err(),
+            "name is required"
+        );
+        assert!(
+            LstMetadata {
+                name: "n".repeat(LstMetadata::MAX_NAME_LEN + 1),
+                ..new_lst_metadata()
+            }
+            .validate()
+            .is_err(),
+            "name too long"
+        );
+        assert!(
+            LstMetadata {
+                symbol: "s".repeat(LstMetadata::MAX_SYMBOL_LEN + 1),
+                ..new_lst_metadata()
+            }
+            .validate()
+            .is_err(),
+            "symbol too long"
+        );
+        assert!(
+            LstMetadata {
+                uri: "u".repeat(LstMetadata::MAX_URI_LEN + 1),
+                ..new_lst_metadata()
+            }
+            .validate()
+            .is_err(),
+            "uri too long"
+        );
+    }
+
+    #[test]
+    fn test_token_metadata() {
+        let vault = Pubkey::new_unique();
+        let lst_mint = Pubkey::new_unique();
+        let token_metadata = new_lst_metadata().to_token_metadata(vault, lst_mint);
+
+        assert_eq!(
+            Option::<Pubkey>::from(token_metadata.update_authority),
+            Some(vault)
+        );
+        assert_eq!(token_metadata.mint, lst_mint);
+        assert_eq!(token_metadata.name, "Neptune JUP");
+        assert_eq!(token_metadata.symbol, "nJUP");
+        assert!(token_metadata.additional_metadata.is_empty());
+        assert!(token_metadata.tlv_size_of().unwrap() > 0);
+    }
+}
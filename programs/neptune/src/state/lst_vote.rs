@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// lst locked to vote on a vault proposal
+#[account]
+#[derive(InitSpace, Debug, Default)]
+pub struct LstVote {
+    pub owner: Pubkey,
+    pub vault_proposal: Pubkey,
+    pub side: u8,
+    // lst locked in lst_vote_escrow_ata
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl LstVote {
+    pub const LST_VOTE_SEED: &'static [u8] = b"lst_vote";
+    pub const LST_VOTE_ESCROW_ATA_SEED: &'static [u8] = b"lst_vote_escrow";
+}
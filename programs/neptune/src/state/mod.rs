@@ -1,5 +1,17 @@
+pub use config::*;
+pub use lst_metadata::*;
+pub use lst_vote::*;
 pub use unstaking::*;
+pub use user_state::*;
 pub use vault::*;
+pub use vault_list::*;
+pub use vault_proposal::*;
 
+mod config;
+mod lst_metadata;
+mod lst_vote;
 mod unstaking;
+mod user_state;
 mod vault;
+mod vault_list;
+mod vault_proposal;
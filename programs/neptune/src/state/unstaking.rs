@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{unwrap_ops, NeptuneError};
+
 #[account]
 #[derive(InitSpace, Debug, Default)]
 pub struct Unstaking {
@@ -12,6 +14,97 @@ pub struct Unstaking {
 }
 
 impl Unstaking {
+    pub const UNSTAKING_SEED: &'static [u8] = b"unstaking";
+    pub const PARTIAL_UNSTAKING_SEED: &'static [u8] = b"partial_unstaking";
     pub const UNSTAKING_ESCROW_ATA_SEED: &'static [u8] = b"unstaking_escrow";
     pub const PARTIAL_UNSTAKING_MEMO: &'static str = "Neptune LST";
+
+    // bump of the partial unstaking pda, none for keypair tickets from
+    // before UserState, those were never taken out of the vault totals
+    pub fn find_partial_unstaking_bump(&self, unstaking_key: &Pubkey) -> Option<u8> {
+        let (partial_unstaking, bump) = Pubkey::find_program_address(
+            &[Self::PARTIAL_UNSTAKING_SEED, unstaking_key.as_ref()],
+            &crate::ID,
+        );
+
+        (partial_unstaking == self.partial_unstaking).then_some(bump)
+    }
+
+    // takes `lst_amt` out of the ticket with its pro rata utoken_amt,
+    // rounded down so the rest of the ticket keeps any dust
+    pub fn split(&mut self, lst_amt: u64) -> Result<u64> {
+        require!(
+            lst_amt > 0 && lst_amt <= self.lst_amt,
+            NeptuneError::InvalidUnstakeAmt
+        );
+
+        let utoken_amt =
+            u64::try_from(self.utoken_amt as u128 * lst_amt as u128 / self.lst_amt as u128)
+                .map_err(|_| NeptuneError::ArithmeticOverflow)?;
+        self.lst_amt = unwrap_ops!(self.lst_amt.checked_sub(lst_amt));
+        self.utoken_amt = unwrap_ops!(self.utoken_amt.checked_sub(utoken_amt));
+
+        Ok(utoken_amt)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn unstaking(lst_amt: u64, utoken_amt: u64) -> Unstaking {
+        Unstaking {
+            lst_amt,
+            utoken_amt,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let mut ticket = unstaking(1_000, 1_100);
+        assert_eq!(ticket.split(250).unwrap(), 275);
+        assert_eq!((ticket.lst_amt, ticket.utoken_amt), (750, 825));
+
+        // the whole ticket
+        assert_eq!(ticket.split(750).unwrap(), 825);
+        assert_eq!((ticket.lst_amt, ticket.utoken_amt), (0, 0));
+
+        // rounded down, the rest keeps the dust
+        let mut ticket = unstaking(3, 10);
+        assert_eq!(ticket.split(1).unwrap(), 3);
+        assert_eq!(ticket.utoken_amt, 7);
+    }
+
+    #[test]
+    fn test_find_partial_unstaking_bump() {
+        let unstaking_key = Pubkey::new_unique();
+        let (partial_unstaking, bump) = Pubkey::find_program_address(
+            &[Unstaking::PARTIAL_UNSTAKING_SEED, unstaking_key.as_ref()],
+            &crate::ID,
+        );
+        let ticket = Unstaking {
+            partial_unstaking,
+            ..Default::default()
+        };
+        assert_eq!(
+            ticket.find_partial_unstaking_bump(&unstaking_key),
+            Some(bump)
+        );
+
+        // legacy keypair partial unstaking
+        let ticket = Unstaking {
+            partial_unstaking: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert_eq!(ticket.find_partial_unstaking_bump(&unstaking_key), None);
+    }
+
+    #[test]
+    fn test_split_fails() {
+        let mut ticket = unstaking(1_000, 1_100);
+        assert!(ticket.split(0).is_err());
+        assert!(ticket.split(1_001).is_err());
+        assert_eq!((ticket.lst_amt, ticket.utoken_amt), (1_000, 1_100));
+    }
 }
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{unwrap_ops, NeptuneError};
+
+// per user and vault, numbers the user's unstaking tickets
+#[account]
+#[derive(InitSpace, Debug, Default)]
+pub struct UserState {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    // seeds the next Unstaking, tickets 0..nonce were opened
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+impl UserState {
+    pub const USER_STATE_SEED: &'static [u8] = b"user_state";
+
+    // returns the nonce of the ticket being opened
+    pub fn next_nonce(&mut self) -> Result<u64> {
+        let nonce = self.nonce;
+        self.nonce = unwrap_ops!(nonce.checked_add(1));
+        Ok(nonce)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_next_nonce() {
+        let mut user_state = UserState::default();
+        assert_eq!(user_state.next_nonce().unwrap(), 0);
+        assert_eq!(user_state.next_nonce().unwrap(), 1);
+        assert_eq!(user_state.nonce, 2);
+
+        user_state.nonce = u64::MAX;
+        assert!(user_state.next_nonce().is_err());
+    }
+}
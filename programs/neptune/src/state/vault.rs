@@ -1,7 +1,62 @@
+use std::cmp::Ordering;
+
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::MAX_FEE_BASIS_POINTS;
 
 use crate::{unwrap_ops, NeptuneError};
 
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+pub enum LockPolicy {
+    // escrow always at max lock, max voting power
+    #[default]
+    MaxLock,
+    // escrow lock kept ending at least this many seconds out,
+    // topped up by crank, extended by owner
+    Duration(i64),
+}
+
+// what fee lst is claimed as
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeePayout {
+    // minted
+    #[default]
+    Lst,
+    // redeemed out of the buffer at the current rate
+    Utoken,
+}
+
+impl LockPolicy {
+    pub fn is_max_lock(&self) -> bool {
+        *self == LockPolicy::MaxLock
+    }
+
+    // within the locker's stake duration bounds
+    pub fn validate(&self, min_stake_duration: u64, max_stake_duration: u64) -> Result<()> {
+        if let LockPolicy::Duration(duration) = *self {
+            let duration = u64::try_from(duration).map_err(|_| NeptuneError::InvalidLockPolicy)?;
+            require!(
+                (min_stake_duration.max(1)..=max_stake_duration).contains(&duration),
+                NeptuneError::InvalidLockPolicy
+            );
+        }
+
+        Ok(())
+    }
+
+    // seconds an escrow ending at escrow_ends_at is short of the policy
+    pub fn lock_extension(&self, escrow_ends_at: i64, now: i64) -> Result<i64> {
+        match *self {
+            LockPolicy::MaxLock => Ok(0),
+            LockPolicy::Duration(duration) => {
+                let target_ends_at = unwrap_ops!(now.checked_add(duration));
+                Ok(target_ends_at.saturating_sub(escrow_ends_at).max(0))
+            }
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace, Debug, Default)]
 pub struct Vault {
@@ -13,39 +68,117 @@ pub struct Vault {
     pub total_utoken_staked: u64,
     pub fees_bps: u16,
     pub bump: u8,
+    // owner's share of fees taken from rewards, in lst, not minted yet
+    pub accrued_fees_lst: u64,
+    // escrow has less than total_utoken_staked, staking halted
+    pub in_deficit: bool,
+    // casts vault's votes on WAGMI proposals
+    pub voter: Pubkey,
+    // WAGMI escrow vote delegate, vault itself by default
+    pub vote_delegate: Pubkey,
+    // two-step ownership transfer
+    pub pending_owner: Pubkey,
+    // stake & begin_unstaking halted by owner
+    pub paused: bool,
+    // share of total_utoken_staked kept liquid for instant unstake
+    pub buffer_bps: u16,
+    pub instant_unstake_fee_bps: u16,
+    // utoken in buffer, counted in total_utoken_staked
+    pub buffer_utoken_amt: u64,
+    // utoken being unstaked from escrow to refill buffer
+    pub buffer_refill_amt: u64,
+    // WAGMI escrow lock, enforced by crank
+    pub lock_policy: LockPolicy,
+    pub lst_mint_bump: u8,
+    // signer the vault pda is derived from,
+    // the escrow is derived from the vault so it cannot seed it
+    pub base: Pubkey,
+    // lst escrowed in unstaking tickets, already out of the totals
+    pub pending_unstake_lst_amt: u64,
+    // utoken owed to unstaking tickets, still in the WAGMI escrow
+    pub pending_unstake_utoken_amt: u64,
+    // Config::protocol_fee_bps share of fees, in lst, not minted yet
+    pub accrued_protocol_fees_lst: u64,
 }
 
 impl Vault {
     pub const VAULT_SEED: &'static [u8] = b"vault";
     pub const VAULT_LST_MINT: &'static [u8] = b"lst";
-    // price = total_underlying / total_lst
+    pub const VAULT_BUFFER_SEED: &'static [u8] = b"buffer";
+    pub const VAULT_BUFFER_REFILL_SEED: &'static [u8] = b"buffer_refill";
+    pub const BUFFER_REFILL_MEMO: &'static str = "Neptune buffer";
+    pub const EXCHANGE_RATE_PRECISION: u64 = 1_000_000_000;
+    // virtual lst & utoken on both sides of the rate, a donation to a near
+    // empty vault mostly goes to them, so inflating the rate costs more
+    // than it can take from the next staker
+    pub const VIRTUAL_SHARES: u64 = 1_000;
+    // first stake into a vault with no lst out, seeds the rate
+    pub const MIN_FIRST_STAKE_AMT: u64 = 1_000;
+    // price = (total_underlying + virtual) / (total_lst + virtual)
     // new_lst_amt = underlying_amt / price
     // new_underlying_amt = minted_amt * price
+    // both rounded down, in the vault's favour
 
-    // new_lst_amt = underlying_amt / (total_underlying / total_lst)
-    // or new_lst_amt = underlying_amt * total_lst / total_underlying
-    // new_underlying_amt = lst_amt * total_underlying / total_lst
+    // the vault pda signs with the seeds create_vault derives it from
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [
+            Self::VAULT_SEED,
+            self.base.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
 
-    pub fn get_lst_amt(&self, utoken_amt: u64) -> Result<u64> {
-        if self.total_utoken_staked == 0 {
-            return Ok(utoken_amt);
-        }
+    pub fn set_fees_bps(&mut self, fees_bps: u16) -> Result<()> {
+        require!(fees_bps < MAX_FEE_BASIS_POINTS, NeptuneError::InvalidBPS);
 
-        u64::try_from(
-            utoken_amt as u128 * self.total_lst_minted as u128 / self.total_utoken_staked as u128,
+        self.fees_bps = fees_bps;
+
+        Ok(())
+    }
+
+    pub fn get_lst_amt(&self, utoken_amt: u64) -> Result<u64> {
+        mul_div(
+            utoken_amt,
+            self.virtual_total_lst(),
+            self.virtual_total_utoken(),
+            Rounding::Down,
         )
-        .map_err(|_| NeptuneError::ArithmeticOverflow.into())
     }
 
     pub fn get_utoken_amt(&self, lst_amt: u64) -> Result<u64> {
-        if self.total_lst_minted == 0 {
-            return Ok(lst_amt);
-        }
-
-        u64::try_from(
-            lst_amt as u128 * self.total_utoken_staked as u128 / self.total_lst_minted as u128,
+        mul_div(
+            lst_amt,
+            self.virtual_total_utoken(),
+            self.virtual_total_lst(),
+            Rounding::Down,
         )
-        .map_err(|_| NeptuneError::ArithmeticOverflow.into())
+    }
+
+    // lst minted for a new stake, the first one into a vault
+    // with no lst out seeds the rate
+    pub fn get_stake_lst_amt(&self, utoken_amt: u64) -> Result<u64> {
+        require!(
+            self.total_lst_minted > 0 || utoken_amt >= Self::MIN_FIRST_STAKE_AMT,
+            NeptuneError::FirstStakeTooSmall
+        );
+
+        let lst_amt = self.get_lst_amt(utoken_amt)?;
+        require!(lst_amt > 0, NeptuneError::InvalidStakeAmt);
+
+        Ok(lst_amt)
+    }
+
+    fn virtual_total_lst(&self) -> u128 {
+        self.total_lst_minted as u128 + Self::VIRTUAL_SHARES as u128
+    }
+
+    fn virtual_total_utoken(&self) -> u128 {
+        self.total_utoken_staked as u128 + Self::VIRTUAL_SHARES as u128
+    }
+
+    // utoken amt for EXCHANGE_RATE_PRECISION lst
+    pub fn get_exchange_rate(&self) -> Result<u64> {
+        self.get_utoken_amt(Self::EXCHANGE_RATE_PRECISION)
     }
 
     pub fn unstake(&mut self, lst_amt: u64, utoken_amt: u64) -> Result<u64> {
@@ -76,18 +209,289 @@ impl Vault {
         Ok(lst_amt)
     }
 
-    pub fn add_reward(&mut self, utoken_amt: u64) -> Result<()> {
-        self.total_utoken_staked = unwrap_ops!(self.total_utoken_staked.checked_add(utoken_amt));
+    // ticket leaves the totals at the current rate,
+    // rewards accrued during its cooldown go to the remaining stakers
+    pub fn begin_unstake(&mut self, lst_amt: u64) -> Result<u64> {
+        let utoken_amt = self.get_utoken_amt(lst_amt)?;
+        self.unstake(lst_amt, utoken_amt)?;
+
+        self.pending_unstake_lst_amt =
+            unwrap_ops!(self.pending_unstake_lst_amt.checked_add(lst_amt));
+        self.pending_unstake_utoken_amt =
+            unwrap_ops!(self.pending_unstake_utoken_amt.checked_add(utoken_amt));
+
+        Ok(utoken_amt)
+    }
+
+    // ticket paid out, its lst is burnt
+    pub fn end_unstake(&mut self, lst_amt: u64, utoken_amt: u64) -> Result<()> {
+        self.pending_unstake_lst_amt = unwrap_ops!(
+            self.pending_unstake_lst_amt.checked_sub(lst_amt),
+            NeptuneError::InvalidUnstakeAmt
+        );
+        self.pending_unstake_utoken_amt = unwrap_ops!(
+            self.pending_unstake_utoken_amt.checked_sub(utoken_amt),
+            NeptuneError::InvalidUnstakeAmt
+        );
+
+        Ok(())
+    }
+
+    // ticket back in the totals at the current rate, capped at its own lst
+    // so it cannot pick up rewards it missed, the rest of its lst is burnt
+    // returns lst given back to the user
+    pub fn cancel_unstake(&mut self, lst_amt: u64, utoken_amt: u64) -> Result<u64> {
+        self.end_unstake(lst_amt, utoken_amt)?;
+        let returned_lst_amt = self.get_lst_amt(utoken_amt)?.min(lst_amt);
+
+        self.stake(utoken_amt, returned_lst_amt)
+    }
+
+    pub fn get_fee_amt(&self, utoken_amt: u64) -> Result<u64> {
+        // rounded down, what is not taken as fee stays with stakers
+        get_bps_amt(utoken_amt, self.fees_bps, Rounding::Down)
+    }
+
+    // fee is taken as lst at the post-reward price,
+    // so stakers only see the net reward
+    // net_amt = reward - fee
+    // fee_lst_amt = fee * (total_lst + virtual) / (total_underlying + net_amt + virtual)
+    // protocol_fee_bps of the fee lst is the protocol's, rounded down
+    pub fn add_reward(&mut self, utoken_amt: u64, protocol_fee_bps: u16) -> Result<u64> {
+        // nobody to reward, reward would be stuck with no lst
+        require!(self.total_lst_minted > 0, NeptuneError::NoStakers);
+
+        let fee_amt = self.get_fee_amt(utoken_amt)?;
+        let net_amt = unwrap_ops!(utoken_amt.checked_sub(fee_amt));
+
+        self.total_utoken_staked = unwrap_ops!(self.total_utoken_staked.checked_add(net_amt));
+
+        let fee_lst_amt = self.get_lst_amt(fee_amt)?;
+        self.stake(fee_amt, fee_lst_amt)?;
+
+        let protocol_fee_lst_amt = get_bps_amt(fee_lst_amt, protocol_fee_bps, Rounding::Down)?;
+        let owner_fee_lst_amt = unwrap_ops!(fee_lst_amt.checked_sub(protocol_fee_lst_amt));
+        self.accrued_fees_lst = unwrap_ops!(self.accrued_fees_lst.checked_add(owner_fee_lst_amt));
+        self.accrued_protocol_fees_lst = unwrap_ops!(self
+            .accrued_protocol_fees_lst
+            .checked_add(protocol_fee_lst_amt));
+
+        Ok(fee_lst_amt)
+    }
+
+    // utoken_amt is what locked_voter actually holds for the vault
+    // surplus is reward, deficit halts staking until resolved
+    pub fn sync(&mut self, utoken_amt: u64, protocol_fee_bps: u16) -> Result<()> {
+        match utoken_amt.cmp(&self.total_utoken_staked) {
+            // surplus in an empty vault has nobody to go to yet
+            Ordering::Greater if self.total_lst_minted == 0 => {}
+            Ordering::Greater => {
+                self.add_reward(utoken_amt - self.total_utoken_staked, protocol_fee_bps)?;
+            }
+            Ordering::Less => {
+                self.total_utoken_staked = utoken_amt;
+                self.in_deficit = true;
+            }
+            Ordering::Equal => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn set_buffer(&mut self, buffer_bps: u16, instant_unstake_fee_bps: u16) -> Result<()> {
+        require!(buffer_bps < MAX_FEE_BASIS_POINTS, NeptuneError::InvalidBPS);
+        require!(
+            instant_unstake_fee_bps < MAX_FEE_BASIS_POINTS,
+            NeptuneError::InvalidBPS
+        );
+
+        self.buffer_bps = buffer_bps;
+        self.instant_unstake_fee_bps = instant_unstake_fee_bps;
+
+        Ok(())
+    }
+
+    pub fn get_buffer_target(&self) -> Result<u64> {
+        get_bps_amt(self.total_utoken_staked, self.buffer_bps, Rounding::Down)
+    }
+
+    // utoken of a new stake kept in buffer instead of escrow
+    pub fn get_buffer_skim_amt(&self, utoken_amt: u64) -> Result<u64> {
+        let buffer_amt = unwrap_ops!(self.buffer_utoken_amt.checked_add(self.buffer_refill_amt));
+        let missing_amt = self.get_buffer_target()?.saturating_sub(buffer_amt);
+
+        Ok(get_bps_amt(utoken_amt, self.buffer_bps, Rounding::Down)?.min(missing_amt))
+    }
+
+    pub fn add_to_buffer(&mut self, utoken_amt: u64) -> Result<()> {
+        self.buffer_utoken_amt = unwrap_ops!(self.buffer_utoken_amt.checked_add(utoken_amt));
+
+        Ok(())
+    }
+
+    // utoken to unstake from escrow to bring buffer back to target
+    pub fn begin_buffer_refill(&mut self) -> Result<u64> {
+        require!(
+            self.buffer_refill_amt == 0,
+            NeptuneError::BufferRefillPending
+        );
+
+        let refill_amt = self
+            .get_buffer_target()?
+            .saturating_sub(self.buffer_utoken_amt);
+        require!(refill_amt > 0, NeptuneError::BufferIsFull);
+
+        self.buffer_refill_amt = refill_amt;
+
+        Ok(refill_amt)
+    }
+
+    pub fn end_buffer_refill(&mut self) -> Result<u64> {
+        let refill_amt = self.buffer_refill_amt;
+        self.add_to_buffer(refill_amt)?;
+        self.buffer_refill_amt = 0;
+
+        Ok(refill_amt)
+    }
+
+    // instant unstake fee stays in vault as reward, rounded up
+    // last one out has nobody to pay the fee to
+    // returns utoken paid out of buffer
+    pub fn instant_unstake(&mut self, lst_amt: u64, protocol_fee_bps: u16) -> Result<u64> {
+        // the loss is not priced in until resolved
+        require!(!self.in_deficit, NeptuneError::VaultInDeficit);
+
+        let utoken_amt = self.get_utoken_amt(lst_amt)?;
+        // unminted fee lst is counted in total_lst_minted but held by nobody
+        let circulating_lst_amt = unwrap_ops!(self
+            .total_lst_minted
+            .checked_sub(self.accrued_fees_lst)
+            .and_then(|amt| amt.checked_sub(self.accrued_protocol_fees_lst)));
+        let fee_amt = if lst_amt < circulating_lst_amt {
+            get_bps_amt(utoken_amt, self.instant_unstake_fee_bps, Rounding::Up)?
+        } else {
+            0
+        };
+        let paid_amt = unwrap_ops!(utoken_amt.checked_sub(fee_amt));
+
+        self.buffer_utoken_amt = unwrap_ops!(
+            self.buffer_utoken_amt.checked_sub(paid_amt),
+            NeptuneError::InsufficientBuffer
+        );
+        self.unstake(lst_amt, utoken_amt)?;
+        if fee_amt > 0 {
+            self.add_reward(fee_amt, protocol_fee_bps)?;
+        }
+
+        Ok(paid_amt)
+    }
+
+    // run at the end of every instruction touching totals or lst supply
+    pub fn assert_invariants(&self, vault_key: &Pubkey, lst_supply: u64) -> Result<()> {
+        let lst_mint = Pubkey::create_program_address(
+            &[
+                Self::VAULT_LST_MINT,
+                vault_key.as_ref(),
+                &[self.lst_mint_bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| NeptuneError::VaultInvariantViolated)?;
+        require_keys_eq!(
+            self.lst_mint,
+            lst_mint,
+            NeptuneError::VaultInvariantViolated
+        );
+
+        // unminted fee lst is counted in total_lst_minted,
+        // lst escrowed in tickets is minted but out of it
+        require!(
+            lst_supply
+                .checked_add(self.accrued_fees_lst)
+                .and_then(|amt| amt.checked_add(self.accrued_protocol_fees_lst))
+                == self
+                    .total_lst_minted
+                    .checked_add(self.pending_unstake_lst_amt),
+            NeptuneError::VaultInvariantViolated
+        );
+
+        // a deficit can wipe out utoken while lst is still out,
+        // utoken without lst is dust left to the virtual shares
+        if !self.in_deficit {
+            require!(
+                self.total_lst_minted == 0 || self.total_utoken_staked > 0,
+                NeptuneError::VaultInvariantViolated
+            );
+            require!(
+                self.buffer_utoken_amt
+                    .checked_add(self.buffer_refill_amt)
+                    .is_some_and(|buffer_amt| buffer_amt <= self.total_utoken_staked),
+                NeptuneError::VaultInvariantViolated
+            );
+        }
 
         Ok(())
     }
+
+    pub fn claim_owner_fees(&mut self) -> Result<u64> {
+        let fee_lst_amt = self.accrued_fees_lst;
+        require!(fee_lst_amt > 0, NeptuneError::NoFeesToClaim);
+
+        self.accrued_fees_lst = 0;
+
+        Ok(fee_lst_amt)
+    }
+
+    pub fn claim_protocol_fees(&mut self) -> Result<u64> {
+        let fee_lst_amt = self.accrued_protocol_fees_lst;
+        require!(fee_lst_amt > 0, NeptuneError::NoFeesToClaim);
+
+        self.accrued_protocol_fees_lst = 0;
+
+        Ok(fee_lst_amt)
+    }
+
+    // claimed fee lst leaves the totals at the current rate,
+    // paid out of the buffer without instant unstake fee
+    // returns utoken paid
+    pub fn redeem_fees(&mut self, fee_lst_amt: u64) -> Result<u64> {
+        let utoken_amt = self.get_utoken_amt(fee_lst_amt)?;
+        self.buffer_utoken_amt = unwrap_ops!(
+            self.buffer_utoken_amt.checked_sub(utoken_amt),
+            NeptuneError::InsufficientBuffer
+        );
+
+        self.unstake(fee_lst_amt, utoken_amt)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rounding {
+    Down,
+    Up,
+}
+
+fn mul_div(amt: u64, numerator: u128, denominator: u128, rounding: Rounding) -> Result<u64> {
+    let product = unwrap_ops!((amt as u128).checked_mul(numerator));
+    let quotient = unwrap_ops!(product.checked_div(denominator));
+    let quotient = match rounding {
+        Rounding::Up if product % denominator > 0 => quotient + 1,
+        _ => quotient,
+    };
+
+    u64::try_from(quotient).map_err(|_| NeptuneError::ArithmeticOverflow.into())
+}
+
+fn get_bps_amt(amt: u64, bps: u16, rounding: Rounding) -> Result<u64> {
+    mul_div(amt, bps as u128, MAX_FEE_BASIS_POINTS as u128, rounding)
 }
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use super::Vault;
+    use super::{LockPolicy, Vault};
     use anchor_lang::prelude::Pubkey;
+    use proptest::prelude::*;
 
     fn new_vault() -> Vault {
         Vault {
@@ -98,96 +502,901 @@ mod test {
             owner: Pubkey::new_unique(),
             total_lst_minted: 0,
             total_utoken_staked: 0,
+            accrued_fees_lst: 0,
+            in_deficit: false,
+            voter: Pubkey::new_unique(),
+            vote_delegate: Pubkey::new_unique(),
+            pending_owner: Pubkey::default(),
+            paused: false,
+            buffer_bps: 0,
+            instant_unstake_fee_bps: 0,
+            buffer_utoken_amt: 0,
+            buffer_refill_amt: 0,
+            lock_policy: LockPolicy::MaxLock,
+            lst_mint_bump: 255,
+            base: Pubkey::new_unique(),
+            pending_unstake_lst_amt: 0,
+            pending_unstake_utoken_amt: 0,
+            accrued_protocol_fees_lst: 0,
         }
     }
 
+    fn new_vault_with_key() -> (Pubkey, Vault) {
+        let vault_key = Pubkey::new_unique();
+        let (lst_mint, lst_mint_bump) =
+            Pubkey::find_program_address(&[Vault::VAULT_LST_MINT, vault_key.as_ref()], &crate::ID);
+
+        (
+            vault_key,
+            Vault {
+                lst_mint,
+                lst_mint_bump,
+                ..new_vault()
+            },
+        )
+    }
+
     #[test]
     fn test_lst_amt() {
-        let mut vault = new_vault();
+        // no fee, rewards go to stakers in full
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
 
         assert_eq!(
-            vault.stake(1, vault.get_lst_amt(1).unwrap()).unwrap(),
-            1,
-            "should receive 1 lst"
+            vault
+                .stake(100_000_000, vault.get_lst_amt(100_000_000).unwrap())
+                .unwrap(),
+            100_000_000,
+            "should receive 1:1 lst"
         );
         assert_eq!(
             vault.total_lst_minted, vault.total_utoken_staked,
             "total minted & total staked should be eq"
         );
 
-        vault.add_reward(2).unwrap();
-
+        // virtual shares hold a sliver of the reward, rounded down
+        vault.add_reward(10_000_000, 0).unwrap();
+        assert_eq!(
+            vault
+                .unstake(1_000_000, vault.get_utoken_amt(1_000_000).unwrap())
+                .unwrap(),
+            1_099_999,
+            "should unstake 1_000_000 with extra 10%"
+        );
         assert_eq!(
-            vault.unstake(1, vault.get_utoken_amt(1).unwrap()).unwrap(),
-            3,
-            "should receive 3 utoken"
+            vault
+                .unstake(4_000_000, vault.get_utoken_amt(4_000_000).unwrap())
+                .unwrap(),
+            4_399_996,
+            "should unstake 4_000_000 with extra 10%"
         );
 
+        vault.add_reward(10_000_000, 0).unwrap();
         assert_eq!(
-            vault.total_lst_minted, 0,
-            "total lst minted should be 0 after unstaked all"
+            vault
+                .unstake(33_333_333, vault.get_utoken_amt(33_333_333).unwrap())
+                .unwrap(),
+            40_175_367
         );
         assert_eq!(
-            vault.total_utoken_staked, 0,
-            "total utoken staked should be 0 after unstaked all"
+            100_000_000 + 10_000_000 + 10_000_000 - 1_099_999 - 4_399_996 - 40_175_367,
+            vault.total_utoken_staked,
+            "final utoken amt should match up"
         );
 
-        vault
-            .stake(100_000_000, vault.get_lst_amt(100_000_000).unwrap())
-            .unwrap();
-        vault.add_reward(10_000_000).unwrap();
-
         assert_eq!(
             vault
-                .unstake(1_000_000, vault.get_utoken_amt(1_000_000).unwrap())
+                .stake(2_222_222, vault.get_lst_amt(2_222_222).unwrap())
                 .unwrap(),
-            1_100_000_u64,
-            "should unstake 1_000_000 with extra 10%"
+            1_843_768
+        );
+        assert_eq!(
+            vault.total_lst_minted,
+            100_000_000 - 1_000_000 - 4_000_000 - 33_333_333 + 1_843_768,
+            "final lst minted should match"
         );
 
+        vault
+            .unstake(
+                vault.total_lst_minted,
+                vault.get_utoken_amt(vault.total_lst_minted).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(vault.total_lst_minted, 0, "vault should empty");
         assert_eq!(
+            vault.total_utoken_staked, 206,
+            "only the virtual shares' dust should be left"
+        );
+    }
+
+    #[test]
+    fn test_virtual_shares() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+
+        // a donation to a near empty vault mostly goes to the virtual shares
+        vault.stake(1, vault.get_lst_amt(1).unwrap()).unwrap();
+        vault.add_reward(1_000, 0).unwrap();
+        assert_eq!(vault.get_utoken_amt(1).unwrap(), 1);
+        vault.unstake(1, 1).unwrap();
+        assert_eq!(vault.total_utoken_staked, 1_000);
+
+        // the dust is shared with whoever stakes next
+        assert_eq!(vault.get_lst_amt(1_000_000).unwrap(), 500_000);
+        vault.stake(1_000_000, 500_000).unwrap();
+        assert_eq!(vault.get_utoken_amt(500_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn test_inflation_attack() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+
+        // attacker seeds the vault and donates to inflate the rate
+        let attacker_lst_amt = vault.get_stake_lst_amt(Vault::MIN_FIRST_STAKE_AMT).unwrap();
+        vault
+            .stake(Vault::MIN_FIRST_STAKE_AMT, attacker_lst_amt)
+            .unwrap();
+        vault.add_reward(1_000_000, 0).unwrap();
+
+        let victim_lst_amt = vault.get_stake_lst_amt(1_000_000).unwrap();
+        assert_eq!(victim_lst_amt, 1_996);
+        vault.stake(1_000_000, victim_lst_amt).unwrap();
+
+        // attacker loses half the donation, victim a few units of rounding
+        let attacker_utoken_amt = vault.get_utoken_amt(attacker_lst_amt).unwrap();
+        vault
+            .unstake(attacker_lst_amt, attacker_utoken_amt)
+            .unwrap();
+        assert_eq!(attacker_utoken_amt, 501_001);
+        assert_eq!(vault.get_utoken_amt(victim_lst_amt).unwrap(), 999_997);
+    }
+
+    #[test]
+    fn test_first_stake() {
+        let mut vault = new_vault();
+
+        assert!(
             vault
-                .unstake(4_000_000_u64, vault.get_utoken_amt(4_000_000).unwrap())
-                .unwrap(),
-            4_400_000_u64,
-            "should unstake 4_000_000 with extra 10%"
+                .get_stake_lst_amt(Vault::MIN_FIRST_STAKE_AMT - 1)
+                .is_err(),
+            "first stake should seed the rate"
+        );
+        assert_eq!(
+            vault.get_stake_lst_amt(Vault::MIN_FIRST_STAKE_AMT).unwrap(),
+            Vault::MIN_FIRST_STAKE_AMT
         );
 
-        vault.add_reward(10_000_000).unwrap();
         vault
-            .unstake(33_333_333_u64, vault.get_utoken_amt(33_333_333).unwrap())
+            .stake(Vault::MIN_FIRST_STAKE_AMT, Vault::MIN_FIRST_STAKE_AMT)
             .unwrap();
+        assert_eq!(vault.get_stake_lst_amt(1).unwrap(), 1);
 
-        let total_reward_dis = (1_000_000_u64 * 10_u64 / 100_u64)
-            + (4_000_000_u64 * 10_u64 / 100_u64)
-            + (vault.get_utoken_amt(33_333_333_u64).unwrap() - 33_333_333_u64);
+        // a stake worth less than 1 lst would be lost
+        vault.add_reward(10_000, 0).unwrap();
+        assert!(vault.get_stake_lst_amt(1).is_err());
+    }
+
+    #[test]
+    fn test_rounding() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+        vault.set_buffer(5_000, 100).unwrap();
+        vault.stake(10_000, 10_000).unwrap();
+        vault.add_reward(5_000, 0).unwrap();
+
+        // both ways round down, in the vault's favour
+        assert_eq!(vault.get_lst_amt(3).unwrap(), 2);
+        assert_eq!(vault.get_utoken_amt(3).unwrap(), 4);
+
+        // instant unstake fee rounds up
+        vault.add_to_buffer(1_000).unwrap();
+        assert_eq!(vault.get_utoken_amt(100).unwrap(), 145);
+        assert_eq!(vault.instant_unstake(100, 0).unwrap(), 143);
+    }
+
+    #[test]
+    fn test_reward_fee() {
+        let mut vault = new_vault();
+
+        vault
+            .stake(9_900_000, vault.get_lst_amt(9_900_000).unwrap())
+            .unwrap();
+
+        // 1% of 10_000_000
+        assert_eq!(vault.get_fee_amt(10_000_000).unwrap(), 100_000);
+
+        // fee lst = 100_000 * (9_900_000 + 1_000) / (9_900_000 + 9_900_000 + 1_000)
+        assert_eq!(vault.add_reward(10_000_000, 0).unwrap(), 50_002);
         assert_eq!(
-            100_000_000_u64 + 10_000_000_u64 + 10_000_000_u64
-                - 1_000_000_u64
-                - 4_000_000_u64
-                - 33_333_333_u64,
-            total_reward_dis as u64 + vault.total_utoken_staked,
-            "final utoken amt should match up"
+            vault.accrued_fees_lst, 50_002,
+            "fee should be accrued as lst"
         );
+        assert_eq!(
+            vault.total_lst_minted, 9_950_002,
+            "accrued fee lst should count as minted"
+        );
+
+        // virtual shares hold the rest, rounded down
+        assert_eq!(
+            vault.get_utoken_amt(9_900_000).unwrap(),
+            9_900_000 + 9_899_001,
+            "stakers should receive the net reward"
+        );
+        assert_eq!(
+            vault.get_utoken_amt(50_002).unwrap(),
+            99_998,
+            "fee lst should be worth the fee"
+        );
+        assert_eq!(
+            vault.get_lst_amt(19_799_001).unwrap(),
+            9_899_999,
+            "new stakers should get the same rate"
+        );
+
+        assert_eq!(vault.claim_owner_fees().unwrap(), 50_002);
+        assert_eq!(vault.accrued_fees_lst, 0, "fee should be claimed");
+        assert!(vault.claim_owner_fees().is_err(), "nothing left to claim");
+    }
+
+    #[test]
+    fn test_protocol_fee() {
+        let mut vault = new_vault();
+        vault
+            .stake(9_900_000, vault.get_lst_amt(9_900_000).unwrap())
+            .unwrap();
+
+        // 10% of the 50_002 fee lst, rounded down
+        assert_eq!(vault.add_reward(10_000_000, 1_000).unwrap(), 50_002);
+        assert_eq!(vault.accrued_protocol_fees_lst, 5_000);
+        assert_eq!(vault.accrued_fees_lst, 45_002);
+        assert_eq!(vault.total_lst_minted, 9_950_002);
+
+        assert_eq!(vault.claim_protocol_fees().unwrap(), 5_000);
+        assert_eq!(vault.accrued_protocol_fees_lst, 0);
+        assert!(vault.claim_protocol_fees().is_err());
+        assert_eq!(vault.accrued_fees_lst, 45_002, "owner share is kept");
+
+        // all of it
+        vault.add_reward(10_000_000, 10_000).unwrap();
+        assert_eq!(vault.accrued_fees_lst, 45_002);
+        assert!(vault.accrued_protocol_fees_lst > 0);
+    }
+
+    #[test]
+    fn test_redeem_fees() {
+        let mut vault = new_vault();
+        vault
+            .stake(9_900_000, vault.get_lst_amt(9_900_000).unwrap())
+            .unwrap();
+        vault.add_reward(10_000_000, 0).unwrap();
+        let fee_lst_amt = vault.claim_owner_fees().unwrap();
+        let rate = vault.get_exchange_rate().unwrap();
+
+        // buffer too small
+        vault.add_to_buffer(99_997).unwrap();
+        assert!(vault.clone().redeem_fees(fee_lst_amt).is_err());
+
+        vault.add_to_buffer(1).unwrap();
+        assert_eq!(vault.redeem_fees(fee_lst_amt).unwrap(), 99_998);
+        assert_eq!(vault.buffer_utoken_amt, 0);
+        assert_eq!(vault.total_lst_minted, 9_900_000);
+        assert!(
+            vault.get_exchange_rate().unwrap() >= rate,
+            "stakers should not pay for the redemption"
+        );
+    }
+
+    #[test]
+    fn test_reward_fee_rounding() {
+        let mut vault = Vault {
+            fees_bps: 1_000,
+            ..new_vault()
+        };
+
+        vault
+            .stake(1_000_000_000, vault.get_lst_amt(1_000_000_000).unwrap())
+            .unwrap();
+        vault.add_reward(1_000_000_000, 0).unwrap();
+
+        // 10% fee, stakers get 900_000_000
+        // fee lst = 100_000_000 * (1_000_000_000 + 1_000) / (1_900_000_000 + 1_000),
+        // rounded down in stakers' favour
+        assert_eq!(vault.accrued_fees_lst, 52_631_603);
+        assert!(vault.get_utoken_amt(52_631_603).unwrap() <= 100_000_000);
+
+        // fee does not leak when everyone leaves
+        vault
+            .unstake(1_000_000_000, vault.get_utoken_amt(1_000_000_000).unwrap())
+            .unwrap();
+        let fee_lst_amt = vault.claim_owner_fees().unwrap();
+        vault
+            .unstake(fee_lst_amt, vault.get_utoken_amt(fee_lst_amt).unwrap())
+            .unwrap();
+        assert_eq!(vault.total_lst_minted, 0, "vault should empty");
+        assert!(
+            vault.total_utoken_staked < Vault::VIRTUAL_SHARES,
+            "only the virtual shares' dust should be left"
+        );
+    }
+
+    #[test]
+    fn test_exchange_rate() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+
+        assert_eq!(vault.get_exchange_rate().unwrap(), 1_000_000_000, "1:1");
 
         vault
-            .stake(2_222_222_u64, vault.get_lst_amt(2_222_222_u64).unwrap())
+            .stake(1_000_000, vault.get_lst_amt(1_000_000).unwrap())
             .unwrap();
-        let final_lst_minted = 100_000_000_u64 - 1_000_000_u64 - 4_000_000_u64 - 33_333_333_u64
-            + vault.get_lst_amt(2_222_222_u64).unwrap();
+        vault.add_reward(100_000, 0).unwrap();
+        // (1_100_000 + 1_000) / (1_000_000 + 1_000)
+        assert_eq!(vault.get_exchange_rate().unwrap(), 1_099_900_099);
+    }
+
+    #[test]
+    fn test_lock_policy() {
+        // migrated vaults are zeroed, should default to max lock
+        assert_eq!(LockPolicy::default(), LockPolicy::MaxLock);
+        assert_eq!(Vault::default().lock_policy, LockPolicy::MaxLock);
+
+        assert!(LockPolicy::MaxLock.is_max_lock());
+        assert!(!LockPolicy::Duration(86_400).is_max_lock());
+    }
+
+    #[test]
+    fn test_lock_policy_duration() {
+        assert!(LockPolicy::Duration(86_400).validate(0, 86_400).is_ok());
+        assert!(LockPolicy::MaxLock.validate(0, 86_400).is_ok());
+        assert!(LockPolicy::Duration(0).validate(0, 86_400).is_err());
+        assert!(LockPolicy::Duration(-1).validate(0, 86_400).is_err());
+        assert!(LockPolicy::Duration(3_600).validate(7_200, 86_400).is_err());
+        assert!(LockPolicy::Duration(86_401).validate(0, 86_400).is_err());
+
+        // escrow is brought up to now + duration, never shortened
+        let policy = LockPolicy::Duration(1_000);
+        assert_eq!(policy.lock_extension(0, 5_000).unwrap(), 6_000);
+        assert_eq!(policy.lock_extension(5_500, 5_000).unwrap(), 500);
+        assert_eq!(policy.lock_extension(7_000, 5_000).unwrap(), 0);
+        assert_eq!(LockPolicy::MaxLock.lock_extension(0, 5_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_set_fees_bps() {
+        let mut vault = new_vault();
+
+        vault.set_fees_bps(0).unwrap();
+        assert_eq!(vault.fees_bps, 0);
 
+        vault.set_fees_bps(9_999).unwrap();
+        assert_eq!(vault.fees_bps, 9_999);
+
+        assert!(vault.set_fees_bps(10_000).is_err(), "fee cannot take all");
+        assert_eq!(vault.fees_bps, 9_999, "fee should not change");
+    }
+
+    #[test]
+    fn test_sync() {
+        let mut vault = new_vault();
+
+        vault
+            .stake(9_900_000, vault.get_lst_amt(9_900_000).unwrap())
+            .unwrap();
+
+        vault.sync(9_900_000, 0).unwrap();
+        assert_eq!(vault.total_utoken_staked, 9_900_000, "nothing to sync");
+        assert_eq!(vault.accrued_fees_lst, 0, "nothing to sync");
+
+        // surplus is reward
+        vault.sync(19_900_000, 0).unwrap();
+        assert_eq!(vault.total_utoken_staked, 19_900_000);
+        assert_eq!(vault.accrued_fees_lst, 50_002, "surplus should be charged");
+        assert!(!vault.in_deficit);
+
+        // deficit is a loss for everyone
+        vault.sync(9_950_000, 0).unwrap();
+        assert_eq!(vault.total_utoken_staked, 9_950_000);
+        assert_eq!(vault.total_lst_minted, 9_950_002);
+        assert!(vault.in_deficit, "vault should be in deficit");
+    }
+
+    #[test]
+    fn test_buffer_skim() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+        vault.set_buffer(1_000, 50).unwrap();
+
+        // 10% of stake, up to 10% of total staked
+        assert_eq!(vault.get_buffer_skim_amt(1_000).unwrap(), 0, "empty vault");
+
+        vault
+            .stake(10_000, vault.get_lst_amt(10_000).unwrap())
+            .unwrap();
+        assert_eq!(vault.get_buffer_target().unwrap(), 1_000);
+        assert_eq!(vault.get_buffer_skim_amt(5_000).unwrap(), 500);
+
+        vault.add_to_buffer(800).unwrap();
         assert_eq!(
-            final_lst_minted, vault.total_lst_minted,
-            "final lst minted should match"
+            vault.get_buffer_skim_amt(5_000).unwrap(),
+            200,
+            "skim should stop at target"
+        );
+
+        assert_eq!(vault.begin_buffer_refill().unwrap(), 200);
+        assert!(vault.begin_buffer_refill().is_err(), "one refill at a time");
+        assert_eq!(
+            vault.get_buffer_skim_amt(5_000).unwrap(),
+            0,
+            "pending refill should count"
         );
+        assert_eq!(vault.end_buffer_refill().unwrap(), 200);
+        assert_eq!(vault.buffer_utoken_amt, 1_000);
+        assert_eq!(vault.buffer_refill_amt, 0);
+        assert!(vault.begin_buffer_refill().is_err(), "buffer is full");
+
+        assert!(vault.set_buffer(10_000, 0).is_err());
+        assert!(vault.set_buffer(0, 10_000).is_err());
+    }
+
+    #[test]
+    fn test_instant_unstake() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+        vault.set_buffer(5_000, 100).unwrap();
 
         vault
-            .unstake(
-                vault.total_lst_minted,
-                vault.get_utoken_amt(vault.total_lst_minted).unwrap(),
-            )
+            .stake(20_000, vault.get_lst_amt(20_000).unwrap())
             .unwrap();
+        vault.add_to_buffer(10_000).unwrap();
+
+        // 1% fee on 10_000
+        assert_eq!(vault.instant_unstake(10_000, 0).unwrap(), 9_900);
+        assert_eq!(vault.buffer_utoken_amt, 100);
+        assert_eq!(vault.total_lst_minted, 10_000);
+        assert_eq!(
+            vault.total_utoken_staked, 10_100,
+            "fee should stay with stakers"
+        );
+        // less the virtual shares' sliver
+        assert_eq!(vault.get_utoken_amt(10_000).unwrap(), 10_090);
+
+        assert!(
+            vault.instant_unstake(200, 0).is_err(),
+            "cannot pay more than buffer"
+        );
+        assert_eq!(vault.buffer_utoken_amt, 100, "buffer should not change");
+
+        // last one out pays no fee
+        vault.add_to_buffer(10_000).unwrap();
+        assert_eq!(vault.instant_unstake(10_000, 0).unwrap(), 10_090);
         assert_eq!(vault.total_lst_minted, 0, "vault should empty");
-        assert_eq!(vault.total_utoken_staked, 0, "vault should empty");
+        assert_eq!(vault.total_utoken_staked, 10, "dust should be left");
+        assert_eq!(vault.buffer_utoken_amt, 10, "dust should stay in buffer");
+    }
+
+    #[test]
+    fn test_instant_unstake_last_out_with_fees() {
+        let mut vault = new_vault();
+        vault.set_buffer(5_000, 100).unwrap();
+        vault
+            .stake(20_000, vault.get_lst_amt(20_000).unwrap())
+            .unwrap();
+        vault.add_reward(2_000, 1_000).unwrap();
+        assert!(vault.accrued_fees_lst > 0 && vault.accrued_protocol_fees_lst > 0);
+        vault.add_to_buffer(22_000).unwrap();
+
+        // the only holder is the last one out, fee lst is not held by anybody
+        let utoken_amt = vault.get_utoken_amt(20_000).unwrap();
+        assert_eq!(
+            vault.instant_unstake(20_000, 1_000).unwrap(),
+            utoken_amt,
+            "last one out should pay no fee"
+        );
+        assert_eq!(
+            vault.total_lst_minted,
+            vault.accrued_fees_lst + vault.accrued_protocol_fees_lst
+        );
+    }
+
+    #[test]
+    fn test_instant_unstake_in_deficit() {
+        let mut vault = new_vault();
+        vault.set_buffer(5_000, 100).unwrap();
+        vault
+            .stake(20_000, vault.get_lst_amt(20_000).unwrap())
+            .unwrap();
+        vault.add_to_buffer(10_000).unwrap();
+        vault.sync(19_000, 0).unwrap();
+
+        assert!(vault.in_deficit);
+        assert!(
+            vault.instant_unstake(1_000, 0).is_err(),
+            "deficit should halt instant unstake"
+        );
+        assert_eq!(vault.buffer_utoken_amt, 10_000, "buffer should not change");
+    }
+
+    #[test]
+    fn test_unstake_cooldown() {
+        let (vault_key, mut vault) = new_vault_with_key();
+        vault.set_fees_bps(0).unwrap();
+        vault
+            .stake(2_000_000, vault.get_lst_amt(2_000_000).unwrap())
+            .unwrap();
+
+        // half the stakers begin unstaking, their lst is still out
+        assert_eq!(vault.begin_unstake(1_000_000).unwrap(), 1_000_000);
+        assert_eq!(vault.total_lst_minted, 1_000_000);
+        assert_eq!(vault.total_utoken_staked, 1_000_000);
+        assert_eq!(vault.pending_unstake_lst_amt, 1_000_000);
+        assert_eq!(vault.pending_unstake_utoken_amt, 1_000_000);
+        vault.assert_invariants(&vault_key, 2_000_000).unwrap();
+
+        // reward during cooldown goes to the stakers left
+        vault.add_reward(100_000, 0).unwrap();
+        assert_eq!(vault.get_utoken_amt(1_000_000).unwrap(), 1_099_900);
+
+        // cancelled ticket comes back at the new rate
+        assert_eq!(vault.cancel_unstake(500_000, 500_000).unwrap(), 454_586);
+        assert_eq!(vault.total_lst_minted, 1_454_586);
+        assert_eq!(vault.total_utoken_staked, 1_600_000);
+        assert!(vault.get_utoken_amt(1_000_000).unwrap() >= 1_099_900);
+        assert!(
+            vault.assert_invariants(&vault_key, 2_000_000).is_err(),
+            "unreturned ticket lst should be burnt"
+        );
+        vault
+            .assert_invariants(&vault_key, 2_000_000 - 45_414)
+            .unwrap();
+
+        // withdrawn ticket is paid what it was quoted
+        vault.end_unstake(500_000, 500_000).unwrap();
+        assert_eq!(vault.pending_unstake_lst_amt, 0);
+        assert_eq!(vault.pending_unstake_utoken_amt, 0);
+        vault
+            .assert_invariants(&vault_key, 2_000_000 - 45_414 - 500_000)
+            .unwrap();
+
+        assert!(
+            vault.end_unstake(1, 1).is_err(),
+            "no ticket left to pay out"
+        );
+    }
+
+    #[test]
+    fn test_cancel_unstake_after_loss() {
+        let mut vault = Vault {
+            fees_bps: 0,
+            ..new_vault()
+        };
+        vault
+            .stake(2_000_000, vault.get_lst_amt(2_000_000).unwrap())
+            .unwrap();
+        vault.begin_unstake(1_000_000).unwrap();
+
+        // a loss during cooldown does not give the ticket more lst
+        vault.sync(500_000, 0).unwrap();
+        assert_eq!(
+            vault.cancel_unstake(1_000_000, 1_000_000).unwrap(),
+            1_000_000
+        );
+        assert_eq!(vault.total_lst_minted, 2_000_000);
+        assert_eq!(vault.total_utoken_staked, 1_500_000);
+    }
+
+    #[test]
+    fn test_signer_seeds() {
+        let base = Pubkey::new_unique();
+        let (vault_key, bump) =
+            Pubkey::find_program_address(&[Vault::VAULT_SEED, base.as_ref()], &crate::ID);
+        let vault = Vault {
+            base,
+            bump,
+            escrow: Pubkey::new_unique(),
+            ..new_vault()
+        };
+
+        // signs for the pda create_vault derives, whatever the escrow
+        assert_eq!(
+            Pubkey::create_program_address(&vault.signer_seeds(), &crate::ID),
+            Ok(vault_key)
+        );
+
+        // stale bump or base signs for some other address, if any
+        let vault = Vault {
+            bump: bump.wrapping_sub(1),
+            ..vault
+        };
+        assert_ne!(
+            Pubkey::create_program_address(&vault.signer_seeds(), &crate::ID).ok(),
+            Some(vault_key)
+        );
+        let vault = Vault {
+            base: Pubkey::new_unique(),
+            bump,
+            ..vault
+        };
+        assert_ne!(
+            Pubkey::create_program_address(&vault.signer_seeds(), &crate::ID).ok(),
+            Some(vault_key)
+        );
+    }
+
+    #[test]
+    fn test_assert_invariants() {
+        let (vault_key, mut vault) = new_vault_with_key();
+        vault.assert_invariants(&vault_key, 0).unwrap();
+
+        assert!(
+            vault.assert_invariants(&Pubkey::new_unique(), 0).is_err(),
+            "lst mint should belong to vault"
+        );
+        assert!(
+            Vault {
+                lst_mint: Pubkey::new_unique(),
+                ..Vault::default()
+            }
+            .assert_invariants(&vault_key, 0)
+            .is_err(),
+            "lst mint should be the vault pda"
+        );
+
+        vault
+            .stake(9_900_000, vault.get_lst_amt(9_900_000).unwrap())
+            .unwrap();
+        vault.assert_invariants(&vault_key, 9_900_000).unwrap();
+        assert!(
+            vault.assert_invariants(&vault_key, 9_899_999).is_err(),
+            "supply should match"
+        );
+
+        // fee lst is not minted yet
+        vault.add_reward(10_000_000, 0).unwrap();
+        vault.assert_invariants(&vault_key, 9_900_000).unwrap();
+        let fee_lst_amt = vault.claim_owner_fees().unwrap();
+        vault
+            .assert_invariants(&vault_key, 9_900_000 + fee_lst_amt)
+            .unwrap();
+
+        assert!(vault.add_reward(1, 0).is_ok());
+        vault.total_utoken_staked = 0;
+        assert!(
+            vault
+                .assert_invariants(&vault_key, 9_900_000 + fee_lst_amt)
+                .is_err(),
+            "totals should be zero together"
+        );
+        vault.in_deficit = true;
+        vault
+            .assert_invariants(&vault_key, 9_900_000 + fee_lst_amt)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reward_empty_vault() {
+        let mut vault = new_vault();
+
+        assert!(vault.add_reward(1_000, 0).is_err(), "nobody to reward");
+        assert_eq!(vault.total_utoken_staked, 0);
+    }
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Stake(u64),
+        // per mille of total lst minted
+        Unstake(u64),
+        // per mille of total lst minted
+        BeginUnstake(u64),
+        // oldest ticket
+        EndUnstake,
+        // per mille of the oldest ticket
+        CancelUnstake(u64),
+        Reward(u64),
+        ClaimFees,
+    }
+
+    fn op_strategy() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (1..1_000_000_000_000_u64).prop_map(Op::Stake),
+            (1..=1_000_u64).prop_map(Op::Unstake),
+            (1..=1_000_u64).prop_map(Op::BeginUnstake),
+            Just(Op::EndUnstake),
+            (1..=1_000_u64).prop_map(Op::CancelUnstake),
+            (1..1_000_000_000_u64).prop_map(Op::Reward),
+            Just(Op::ClaimFees),
+        ]
+    }
+
+    proptest! {
+        // attacker seeds the vault, donates, and leaves after a victim stakes
+        #[test]
+        fn prop_no_value_extraction(
+            fees_bps in 0..10_000_u16,
+            attacker_amt in Vault::MIN_FIRST_STAKE_AMT..1_000_000_000_000_u64,
+            donation_amt in 0..1_000_000_000_000_u64,
+            victim_amt in 1..1_000_000_000_000_u64,
+        ) {
+            let mut vault = new_vault();
+            vault.set_fees_bps(fees_bps).unwrap();
+
+            let attacker_lst_amt = vault.get_stake_lst_amt(attacker_amt).unwrap();
+            vault.stake(attacker_amt, attacker_lst_amt).unwrap();
+            if donation_amt > 0 {
+                vault.add_reward(donation_amt, 0).unwrap();
+            }
+
+            // the victim's slippage check refuses a mint of nothing
+            let Ok(victim_lst_amt) = vault.get_stake_lst_amt(victim_amt) else {
+                return Ok(());
+            };
+            vault.stake(victim_amt, victim_lst_amt).unwrap();
+            // the victim's lst is worth at most what they put in
+            prop_assert!(vault.get_utoken_amt(victim_lst_amt).unwrap() <= victim_amt);
+
+            let attacker_out = vault.get_utoken_amt(attacker_lst_amt).unwrap();
+            vault.unstake(attacker_lst_amt, attacker_out).unwrap();
+            prop_assert!(attacker_out <= attacker_amt + donation_amt);
+
+            // the attack costs more than it takes, up to a unit of rounding
+            let attacker_loss = attacker_amt + donation_amt - attacker_out;
+            let victim_loss = victim_amt - vault.get_utoken_amt(victim_lst_amt).unwrap();
+            prop_assert!(victim_loss <= attacker_loss + 1);
+        }
+
+        // stake & unstake right away never returns more than staked
+        #[test]
+        fn prop_round_trip(
+            fees_bps in 0..10_000_u16,
+            ops in prop::collection::vec(op_strategy(), 1..32),
+            utoken_amt in 1..1_000_000_000_000_u64,
+        ) {
+            let mut vault = new_vault();
+            vault.set_fees_bps(fees_bps).unwrap();
+            for op in ops {
+                match op {
+                    Op::Stake(utoken_amt) => {
+                        if let Ok(lst_amt) = vault.get_stake_lst_amt(utoken_amt) {
+                            vault.stake(utoken_amt, lst_amt).unwrap();
+                        }
+                    }
+                    Op::Reward(utoken_amt) => {
+                        let _ = vault.add_reward(utoken_amt, 0);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Ok(lst_amt) = vault.get_stake_lst_amt(utoken_amt) {
+                vault.stake(utoken_amt, lst_amt).unwrap();
+                prop_assert!(vault.get_utoken_amt(lst_amt).unwrap() <= utoken_amt);
+            }
+        }
+
+        #[test]
+        fn prop_invariants(
+            fees_bps in 0..10_000_u16,
+            protocol_fee_bps in 0..=10_000_u16,
+            ops in prop::collection::vec(op_strategy(), 1..64),
+        ) {
+            let (vault_key, mut vault) = new_vault_with_key();
+            vault.set_fees_bps(fees_bps).unwrap();
+
+            let mut lst_supply = 0_u64;
+            let mut utoken_in = 0_u64;
+            let mut utoken_out = 0_u64;
+            // (lst_amt, utoken_amt) of open tickets
+            let mut tickets = std::collections::VecDeque::new();
+
+            for op in ops {
+                match op {
+                    Op::Stake(utoken_amt) => {
+                        let lst_amt = vault.get_lst_amt(utoken_amt).unwrap();
+                        vault.stake(utoken_amt, lst_amt).unwrap();
+                        lst_supply += lst_amt;
+                        utoken_in += utoken_amt;
+                    }
+                    Op::Unstake(per_mille) => {
+                        let lst_amt = vault.total_lst_minted * per_mille / 1_000;
+                        let lst_amt = lst_amt.min(lst_supply - vault.pending_unstake_lst_amt);
+                        let utoken_amt = vault.get_utoken_amt(lst_amt).unwrap();
+                        vault.unstake(lst_amt, utoken_amt).unwrap();
+                        lst_supply -= lst_amt;
+                        utoken_out += utoken_amt;
+                    }
+                    Op::BeginUnstake(per_mille) => {
+                        let lst_amt = vault.total_lst_minted * per_mille / 1_000;
+                        let lst_amt = lst_amt.min(lst_supply - vault.pending_unstake_lst_amt);
+                        if lst_amt > 0 {
+                            let utoken_amt = vault.begin_unstake(lst_amt).unwrap();
+                            tickets.push_back((lst_amt, utoken_amt));
+                        }
+                    }
+                    Op::EndUnstake => {
+                        if let Some((lst_amt, utoken_amt)) = tickets.pop_front() {
+                            vault.end_unstake(lst_amt, utoken_amt).unwrap();
+                            lst_supply -= lst_amt;
+                            utoken_out += utoken_amt;
+                        }
+                    }
+                    Op::CancelUnstake(per_mille) => {
+                        if let Some((lst_amt, utoken_amt)) = tickets.pop_front() {
+                            let cancel_lst_amt = (lst_amt * per_mille / 1_000).max(1);
+                            let cancel_utoken_amt = u64::try_from(
+                                utoken_amt as u128 * cancel_lst_amt as u128 / lst_amt as u128,
+                            )
+                            .unwrap();
+                            let stakers_utoken_amt = vault
+                                .get_utoken_amt(vault.total_lst_minted)
+                                .unwrap();
+                            let returned_lst_amt = vault
+                                .cancel_unstake(cancel_lst_amt, cancel_utoken_amt)
+                                .unwrap();
+                            lst_supply -= cancel_lst_amt - returned_lst_amt;
+                            if cancel_lst_amt < lst_amt {
+                                tickets.push_front((
+                                    lst_amt - cancel_lst_amt,
+                                    utoken_amt - cancel_utoken_amt,
+                                ));
+                            }
+
+                            // the ticket cannot take from the stakers
+                            prop_assert!(returned_lst_amt <= cancel_lst_amt);
+                            prop_assert!(
+                                vault.get_utoken_amt(returned_lst_amt).unwrap()
+                                    <= cancel_utoken_amt
+                            );
+                            prop_assert!(
+                                vault
+                                    .get_utoken_amt(vault.total_lst_minted - returned_lst_amt)
+                                    .unwrap()
+                                    >= stakers_utoken_amt
+                            );
+                        }
+                    }
+                    Op::Reward(utoken_amt) => {
+                        if vault.add_reward(utoken_amt, protocol_fee_bps).is_ok() {
+                            utoken_in += utoken_amt;
+                        }
+                    }
+                    Op::ClaimFees => {
+                        if let Ok(fee_lst_amt) = vault.claim_owner_fees() {
+                            lst_supply += fee_lst_amt;
+                        }
+                        // the protocol redeems, out of an unlimited buffer
+                        if let Ok(fee_lst_amt) = vault.claim_protocol_fees() {
+                            vault.buffer_utoken_amt = u64::MAX;
+                            utoken_out += vault.redeem_fees(fee_lst_amt).unwrap();
+                            vault.buffer_utoken_amt = 0;
+                        }
+                    }
+                }
+
+                prop_assert!(vault.assert_invariants(&vault_key, lst_supply).is_ok());
+                prop_assert_eq!(
+                    utoken_in,
+                    utoken_out + vault.total_utoken_staked + vault.pending_unstake_utoken_amt,
+                    "utoken should not leak"
+                );
+                prop_assert_eq!(
+                    tickets.iter().map(|(lst_amt, _)| lst_amt).sum::<u64>(),
+                    vault.pending_unstake_lst_amt
+                );
+            }
+        }
     }
 }
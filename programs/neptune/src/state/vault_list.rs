@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+
+use crate::{state::Vault, NeptuneError};
+
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq,
+)]
+pub enum VaultStatus {
+    #[default]
+    Active,
+    // stake & begin_unstaking halted by owner
+    Paused,
+}
+
+// what a frontend shows of a vault without loading it
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultEntry {
+    pub vault: Pubkey,
+    pub lst_mint: Pubkey,
+    // lst name, LstMetadata::MAX_NAME_LEN
+    #[max_len(32)]
+    pub name: String,
+    pub fees_bps: u16,
+    pub status: VaultStatus,
+}
+
+impl VaultStatus {
+    pub fn of(vault: &Vault) -> Self {
+        if vault.paused {
+            Self::Paused
+        } else {
+            Self::Active
+        }
+    }
+}
+
+impl VaultEntry {
+    pub fn new(vault_key: Pubkey, vault: &Vault, name: String) -> Self {
+        Self {
+            vault: vault_key,
+            lst_mint: vault.lst_mint,
+            name,
+            fees_bps: vault.fees_bps,
+            status: VaultStatus::of(vault),
+        }
+    }
+}
+
+// vaults over a WAGMI locker, in creation order, up to MAX_VAULTS,
+// grown by one entry per vault
+#[account]
+#[derive(InitSpace, Debug, Default)]
+pub struct VaultList {
+    pub locker: Pubkey,
+    pub bump: u8,
+    #[max_len(0)]
+    pub vaults: Vec<VaultEntry>,
+}
+
+// one page of a VaultList, `total` to page through the rest
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultPage {
+    pub total: u32,
+    pub vaults: Vec<VaultEntry>,
+}
+
+impl VaultList {
+    pub const VAULT_LIST_SEED: &'static [u8] = b"vault_list";
+    // a full page of max size entries stays under the 1024 byte return data
+    pub const MAX_PAGE_LEN: u8 = 8;
+    // keeps loading the list within heap & compute of the
+    // instructions updating it, later vaults are left unlisted
+    pub const MAX_VAULTS: usize = 64;
+
+    // account size holding `len` entries, entries take their max size
+    // so renames never realloc
+    pub fn space(len: usize) -> usize {
+        8 + Self::INIT_SPACE + len * VaultEntry::INIT_SPACE
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.vaults.len() >= Self::MAX_VAULTS
+    }
+
+    pub fn add(&mut self, entry: VaultEntry) -> Result<()> {
+        require!(
+            self.vaults.iter().all(|cur| cur.vault != entry.vault),
+            NeptuneError::VaultAlreadyListed
+        );
+        require!(!self.is_full(), NeptuneError::VaultListFull);
+
+        self.vaults.push(entry);
+
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self, vault: &Pubkey) -> Option<&mut VaultEntry> {
+        self.vaults.iter_mut().find(|entry| &entry.vault == vault)
+    }
+
+    pub fn page(&self, offset: u32, limit: u8) -> VaultPage {
+        let limit = limit.min(Self::MAX_PAGE_LEN) as usize;
+
+        VaultPage {
+            total: self.vaults.len() as u32,
+            vaults: self
+                .vaults
+                .iter()
+                .skip(offset as usize)
+                .take(limit)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(name: &str) -> VaultEntry {
+        VaultEntry {
+            vault: Pubkey::new_unique(),
+            lst_mint: Pubkey::new_unique(),
+            name: name.to_string(),
+            fees_bps: 100,
+            status: VaultStatus::Active,
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let mut vault_list = VaultList::default();
+        let first = entry("first");
+        vault_list.add(first.clone()).unwrap();
+        vault_list.add(entry("second")).unwrap();
+        assert_eq!(vault_list.vaults.len(), 2);
+
+        // listed once
+        assert!(vault_list.add(first.clone()).is_err());
+        assert_eq!(vault_list.vaults.len(), 2);
+
+        vault_list.get_mut(&first.vault).unwrap().status = VaultStatus::Paused;
+        assert_eq!(vault_list.vaults[0].status, VaultStatus::Paused);
+        assert!(vault_list.get_mut(&Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    fn test_add_full() {
+        let mut vault_list = VaultList::default();
+        for i in 0..VaultList::MAX_VAULTS {
+            assert!(!vault_list.is_full());
+            vault_list.add(entry(&i.to_string())).unwrap();
+        }
+        assert!(vault_list.is_full());
+        assert!(vault_list.add(entry("last")).is_err());
+        assert_eq!(vault_list.vaults.len(), VaultList::MAX_VAULTS);
+    }
+
+    #[test]
+    fn test_page() {
+        let mut vault_list = VaultList::default();
+        for i in 0..20 {
+            vault_list.add(entry(&i.to_string())).unwrap();
+        }
+
+        let page = vault_list.page(0, 5);
+        assert_eq!(page.total, 20);
+        assert_eq!(page.vaults, vault_list.vaults[..5]);
+
+        // capped at MAX_PAGE_LEN
+        let page = vault_list.page(4, u8::MAX);
+        assert_eq!(page.vaults, vault_list.vaults[4..12]);
+
+        // tail & past the end
+        assert_eq!(vault_list.page(18, 5).vaults, vault_list.vaults[18..]);
+        assert!(vault_list.page(20, 5).vaults.is_empty());
+        assert!(vault_list.page(u32::MAX, 5).vaults.is_empty());
+    }
+
+    #[test]
+    fn test_page_fits_return_data() {
+        let mut vault_list = VaultList::default();
+        for _ in 0..VaultList::MAX_PAGE_LEN {
+            vault_list.add(entry(&"n".repeat(32))).unwrap();
+        }
+
+        let mut data = vec![];
+        vault_list
+            .page(0, VaultList::MAX_PAGE_LEN)
+            .serialize(&mut data)
+            .unwrap();
+        assert!(data.len() <= 1024);
+    }
+
+    #[test]
+    fn test_space() {
+        let mut vault_list = VaultList {
+            locker: Pubkey::new_unique(),
+            bump: 255,
+            vaults: vec![],
+        };
+        for len in 0..3 {
+            let mut data = vec![];
+            vault_list.try_serialize(&mut data).unwrap();
+            assert!(data.len() <= VaultList::space(len));
+            vault_list.add(entry(&"n".repeat(32))).unwrap();
+        }
+    }
+}
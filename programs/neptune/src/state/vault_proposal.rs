@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+
+use crate::{unwrap_ops, NeptuneError};
+
+// neptune mirror of a WAGMI proposal, tallies lst votes
+#[account]
+#[derive(InitSpace, Debug, Default)]
+pub struct VaultProposal {
+    pub vault: Pubkey,
+    // WAGMI proposal
+    pub proposal: Pubkey,
+    pub max_option: u8,
+    pub voting_ends_at: i64,
+    pub option_weights: [u64; VaultProposal::MAX_OPTION],
+    pub total_weight: u64,
+    pub bump: u8,
+}
+
+impl VaultProposal {
+    pub const VAULT_PROPOSAL_SEED: &'static [u8] = b"vault_proposal";
+    // as in WAGMI govern
+    pub const MAX_OPTION: usize = 10;
+
+    pub fn is_voting(&self, now: i64) -> bool {
+        now < self.voting_ends_at
+    }
+
+    pub fn add_vote(&mut self, side: u8, weight: u64) -> Result<()> {
+        require!(side < self.max_option, NeptuneError::InvalidVoteSide);
+
+        let option_weight = &mut self.option_weights[side as usize];
+        *option_weight = unwrap_ops!(option_weight.checked_add(weight));
+        self.total_weight = unwrap_ops!(self.total_weight.checked_add(weight));
+
+        Ok(())
+    }
+
+    pub fn remove_vote(&mut self, side: u8, weight: u64) -> Result<()> {
+        require!(side < self.max_option, NeptuneError::InvalidVoteSide);
+
+        let option_weight = &mut self.option_weights[side as usize];
+        *option_weight = unwrap_ops!(option_weight.checked_sub(weight));
+        self.total_weight = unwrap_ops!(self.total_weight.checked_sub(weight));
+
+        Ok(())
+    }
+
+    // WAGMI vote takes a single side, so the vault votes with the plurality,
+    // no side is picked on a tie
+    pub fn winning_side(&self) -> Result<u8> {
+        require!(self.total_weight > 0, NeptuneError::NoLstVotes);
+
+        let mut winning_side = 0;
+        let mut is_tied = false;
+        for side in 1..self.max_option {
+            let weight = self.option_weights[side as usize];
+            let winning_weight = self.option_weights[winning_side as usize];
+            if weight > winning_weight {
+                winning_side = side;
+                is_tied = false;
+            } else if weight == winning_weight {
+                is_tied = true;
+            }
+        }
+        require!(!is_tied, NeptuneError::LstVoteTied);
+
+        Ok(winning_side)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::VaultProposal;
+    use anchor_lang::prelude::Pubkey;
+
+    fn new_vault_proposal(max_option: u8) -> VaultProposal {
+        VaultProposal {
+            vault: Pubkey::new_unique(),
+            proposal: Pubkey::new_unique(),
+            max_option,
+            voting_ends_at: 1_000,
+            bump: 255,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        let mut vault_proposal = new_vault_proposal(3);
+
+        assert!(vault_proposal.winning_side().is_err(), "no votes yet");
+        assert!(vault_proposal.add_vote(3, 1).is_err(), "side out of range");
+
+        vault_proposal.add_vote(2, 100).unwrap();
+        vault_proposal.add_vote(1, 60).unwrap();
+        vault_proposal.add_vote(1, 30).unwrap();
+        assert_eq!(vault_proposal.total_weight, 190);
+        assert_eq!(vault_proposal.winning_side().unwrap(), 2, "for should win");
+
+        vault_proposal.add_vote(1, 20).unwrap();
+        assert_eq!(vault_proposal.winning_side().unwrap(), 1);
+
+        vault_proposal.remove_vote(1, 60).unwrap();
+        assert_eq!(vault_proposal.total_weight, 150);
+        assert_eq!(vault_proposal.winning_side().unwrap(), 2);
+        assert!(
+            vault_proposal.remove_vote(1, 51).is_err(),
+            "cannot remove more than voted"
+        );
+
+        assert!(vault_proposal.is_voting(999));
+        assert!(!vault_proposal.is_voting(1_000));
+    }
+
+    #[test]
+    fn test_option_tally() {
+        let mut vault_proposal = new_vault_proposal(VaultProposal::MAX_OPTION as u8);
+
+        vault_proposal.add_vote(9, 5).unwrap();
+        vault_proposal.add_vote(4, 3).unwrap();
+        vault_proposal.add_vote(0, 1).unwrap();
+        assert_eq!(vault_proposal.winning_side().unwrap(), 9);
+    }
+
+    #[test]
+    fn test_tied_tally() {
+        let mut vault_proposal = new_vault_proposal(3);
+
+        vault_proposal.add_vote(0, 50).unwrap();
+        vault_proposal.add_vote(2, 50).unwrap();
+        assert!(vault_proposal.winning_side().is_err(), "tie at the top");
+
+        // a tie below the plurality does not matter
+        vault_proposal.add_vote(1, 50).unwrap();
+        vault_proposal.add_vote(2, 1).unwrap();
+        assert_eq!(vault_proposal.winning_side().unwrap(), 2);
+
+        vault_proposal.add_vote(1, 1).unwrap();
+        assert!(vault_proposal.winning_side().is_err(), "tie at the top");
+    }
+
+    #[test]
+    fn test_zero_turnout() {
+        let mut vault_proposal = new_vault_proposal(3);
+        assert!(vault_proposal.winning_side().is_err(), "nobody voted");
+
+        // every vote withdrawn before the crank
+        vault_proposal.add_vote(1, 10).unwrap();
+        vault_proposal.remove_vote(1, 10).unwrap();
+        assert_eq!(vault_proposal.total_weight, 0);
+        assert!(vault_proposal.winning_side().is_err(), "nobody voted");
+
+        // all weight zero on a single option proposal
+        let vault_proposal = new_vault_proposal(1);
+        assert!(vault_proposal.winning_side().is_err(), "nobody voted");
+    }
+}
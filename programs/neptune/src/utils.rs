@@ -0,0 +1,123 @@
+use crate::{
+    lock_voter::{
+        accounts::Escrow,
+        cpi::{
+            self as locked_voter,
+            accounts::{ExtendLockDuration, ToggleMaxLock},
+        },
+    },
+    state::Vault,
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+use anchor_spl::token_interface::{spl_token_2022::onchain, TransferChecked};
+
+// transfer_checked that also works for token-2022 mints with a transfer hook,
+// hook accounts are passed as remaining accounts
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// fund account so it stays rent exempt at new_len,
+// for cpis that realloc without paying rent
+pub fn top_up_rent<'info>(
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent_amt = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_amt == 0 {
+        return Ok(());
+    }
+
+    let xfer_rent_cpi = CpiContext::new(
+        system_program,
+        Transfer {
+            from: payer,
+            to: account,
+        },
+    );
+    system_program::transfer(xfer_rent_cpi, rent_amt)
+}
+
+// grow account to new_len, new bytes zeroed, rent paid by payer
+pub fn grow_account<'info>(
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    top_up_rent(payer, account.clone(), system_program, new_len)?;
+
+    #[allow(deprecated)]
+    account.realloc(new_len, true)?;
+
+    Ok(())
+}
+
+// toggles max lock & extends the escrow as the vault's lock policy requires,
+// false if the escrow already complies
+pub fn apply_lock_policy<'info>(
+    vault: &Account<'info, Vault>,
+    escrow: &mut Account<'info, Escrow>,
+    locker: AccountInfo<'info>,
+    locked_voter: AccountInfo<'info>,
+) -> Result<bool> {
+    let is_max_lock = vault.lock_policy.is_max_lock();
+    let needs_toggle = escrow.is_max_lock != is_max_lock;
+    let vault_seeds: &[&[&[u8]]] = &[&vault.signer_seeds()];
+
+    if needs_toggle {
+        let toggle_max_lock_cpi = CpiContext::new_with_signer(
+            locked_voter.clone(),
+            ToggleMaxLock {
+                locker: locker.clone(),
+                escrow: escrow.to_account_info(),
+                escrow_owner: vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::toggle_max_lock(toggle_max_lock_cpi, is_max_lock)?;
+
+        // leaving max lock moves escrow_ends_at
+        escrow.reload()?;
+    }
+
+    let lock_extension = vault
+        .lock_policy
+        .lock_extension(escrow.escrow_ends_at, Clock::get()?.unix_timestamp)?;
+    if lock_extension > 0 {
+        let extend_lock_duration_cpi = CpiContext::new_with_signer(
+            locked_voter,
+            ExtendLockDuration {
+                locker,
+                escrow: escrow.to_account_info(),
+                escrow_owner: vault.to_account_info(),
+            },
+            vault_seeds,
+        );
+        locked_voter::extend_lock_duration(extend_lock_duration_cpi, lock_extension)?;
+    }
+
+    Ok(needs_toggle || lock_extension > 0)
+}
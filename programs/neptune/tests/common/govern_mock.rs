@@ -0,0 +1,125 @@
+// stand-in for WAGMI govern, votes only: new_vote & set_vote
+// over the account layouts in idls/govern.json
+use anchor_lang::{
+    prelude::*,
+    solana_program::{entrypoint::ProgramResult, program::invoke_signed, system_instruction},
+};
+use neptune::govern::{
+    accounts::{Governor, Proposal, Vote},
+    client::args,
+};
+
+pub const ERR_INVALID_VOTE_SIDE: u32 = 6000;
+pub const ERR_PROPOSAL_NOT_ACTIVE: u32 = 6005;
+
+pub fn vote_address(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"Vote", proposal.as_ref(), voter.as_ref()],
+        &neptune::govern::ID,
+    )
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (discriminator, mut ix_data) = data.split_at(8);
+    let ix_data = &mut ix_data;
+    match discriminator {
+        d if d == args::NewVote::DISCRIMINATOR => {
+            let args = args::NewVote::deserialize(ix_data)?;
+            new_vote(program_id, accounts, args.voter)
+        }
+        d if d == args::SetVote::DISCRIMINATOR => {
+            let args = args::SetVote::deserialize(ix_data)?;
+            set_vote(accounts, args.side, args.weight)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn load<T: AccountDeserialize + Owner>(info: &AccountInfo) -> std::result::Result<T, ProgramError> {
+    if info.owner != &T::owner() {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(T::try_deserialize(&mut &info.try_borrow_data()?[..])?)
+}
+
+fn store<T: AccountSerialize>(info: &AccountInfo, value: &T) -> ProgramResult {
+    let mut data = info.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+// accounts: proposal, vote, payer, system_program
+fn new_vote(program_id: &Pubkey, accounts: &[AccountInfo], voter: Pubkey) -> ProgramResult {
+    let [proposal_info, vote_info, payer, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    load::<Proposal>(proposal_info)?;
+    let (vote_key, bump) = vote_address(proposal_info.key, &voter);
+    if vote_info.key != &vote_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let vote = Vote {
+        proposal: *proposal_info.key,
+        voter,
+        bump,
+        side: 0,
+        voting_power: 0,
+        claimed: false,
+        buffers: [0; 32],
+    };
+    let mut data = vec![];
+    vote.try_serialize(&mut data)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            vote_info.key,
+            Rent::get()?.minimum_balance(data.len()),
+            data.len() as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[b"Vote", proposal_info.key.as_ref(), voter.as_ref(), &[bump]]],
+    )?;
+    vote_info.try_borrow_mut_data()?.copy_from_slice(&data);
+    Ok(())
+}
+
+// accounts: governor, proposal, vote, locker
+// only the governor's locker sets votes, the side is replaced
+fn set_vote(accounts: &[AccountInfo], side: u8, weight: u64) -> ProgramResult {
+    let [governor_info, proposal_info, vote_info, locker, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let governor: Governor = load(governor_info)?;
+    let mut proposal: Proposal = load(proposal_info)?;
+    let mut vote: Vote = load(vote_info)?;
+    if governor.locker != *locker.key || !locker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if proposal.governor != *governor_info.key || vote.proposal != *proposal_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if proposal.activated_at == 0 || proposal.canceled_at > 0 || now >= proposal.voting_ends_at {
+        return Err(ProgramError::Custom(ERR_PROPOSAL_NOT_ACTIVE));
+    }
+    if side >= proposal.max_option {
+        return Err(ProgramError::Custom(ERR_INVALID_VOTE_SIDE));
+    }
+
+    if vote.voting_power > 0 {
+        proposal.option_votes[vote.side as usize] -= vote.voting_power;
+    }
+    proposal.option_votes[side as usize] += weight;
+    vote.side = side;
+    vote.voting_power = weight;
+
+    store(proposal_info, &proposal)?;
+    store(vote_info, &vote)
+}
@@ -0,0 +1,405 @@
+// stand-in for WAGMI locked_voter, escrow & partial unstaking bookkeeping
+// over the account layouts in idls/lock_voter.json
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult, instruction::Instruction, program::invoke_signed,
+        system_instruction,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token::get_associated_token_address, token_2022::spl_token_2022};
+use neptune::lock_voter::{
+    accounts::{Escrow, Locker, PartialUnstaking},
+    client::args,
+};
+
+pub const ERR_UNAUTHORIZED: u32 = 6000;
+pub const ERR_INSUFFICIENT_AMOUNT: u32 = 6001;
+pub const ERR_UNSTAKING_NOT_EXPIRED: u32 = 6002;
+pub const ERR_INVALID_PARTIAL_UNSTAKE: u32 = 6003;
+pub const ERR_LOCKUP_DURATION_TOO_LONG: u32 = 6004;
+
+pub fn locker_address(base: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"Locker", base.as_ref()], &neptune::lock_voter::ID)
+}
+
+pub fn escrow_address(locker: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"Escrow", locker.as_ref(), owner.as_ref()],
+        &neptune::lock_voter::ID,
+    )
+}
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (discriminator, mut ix_data) = data.split_at(8);
+    let ix_data = &mut ix_data;
+    match discriminator {
+        d if d == args::NewEscrow::DISCRIMINATOR => new_escrow(program_id, accounts),
+        d if d == args::IncreaseLockedAmount::DISCRIMINATOR => {
+            let args = args::IncreaseLockedAmount::deserialize(ix_data)?;
+            increase_locked_amount(accounts, args.amount)
+        }
+        d if d == args::ToggleMaxLock::DISCRIMINATOR => {
+            let args = args::ToggleMaxLock::deserialize(ix_data)?;
+            toggle_max_lock(accounts, args.is_max_lock)
+        }
+        d if d == args::ExtendLockDuration::DISCRIMINATOR => {
+            let args = args::ExtendLockDuration::deserialize(ix_data)?;
+            extend_lock_duration(accounts, args.duration)
+        }
+        d if d == args::SetVoteDelegate::DISCRIMINATOR => {
+            let args = args::SetVoteDelegate::deserialize(ix_data)?;
+            set_vote_delegate(accounts, args.new_delegate)
+        }
+        d if d == args::CastVote::DISCRIMINATOR => {
+            let args = args::CastVote::deserialize(ix_data)?;
+            cast_vote(accounts, args.side)
+        }
+        d if d == args::OpenPartialUnstaking::DISCRIMINATOR => {
+            let args = args::OpenPartialUnstaking::deserialize(ix_data)?;
+            open_partial_unstaking(program_id, accounts, args.amount, args.memo)
+        }
+        d if d == args::MergePartialUnstaking::DISCRIMINATOR => merge_partial_unstaking(accounts),
+        d if d == args::WithdrawPartialUnstaking::DISCRIMINATOR => {
+            withdraw_partial_unstaking(accounts)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn account<'a, 'info>(
+    accounts: &'a [AccountInfo<'info>],
+    index: usize,
+) -> std::result::Result<&'a AccountInfo<'info>, ProgramError> {
+    accounts
+        .get(index)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+fn load<T: AccountDeserialize + Owner>(info: &AccountInfo) -> std::result::Result<T, ProgramError> {
+    if info.owner != &T::owner() {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(T::try_deserialize(&mut &info.try_borrow_data()?[..])?)
+}
+
+fn store<T: AccountSerialize>(info: &AccountInfo, value: &T) -> ProgramResult {
+    let mut data = info.try_borrow_mut_data()?;
+    value.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+fn require_owner(escrow: &Escrow, owner: &AccountInfo) -> ProgramResult {
+    if !owner.is_signer || owner.key != &escrow.owner {
+        return Err(ProgramError::Custom(ERR_UNAUTHORIZED));
+    }
+    Ok(())
+}
+
+// drain to `destination` like anchor's close
+fn close(info: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    **destination.try_borrow_mut_lamports()? += info.lamports();
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&system_program::ID);
+    #[allow(deprecated)]
+    info.realloc(0, false)
+}
+
+// accounts: locker, escrow, escrow_owner, payer, system_program
+fn new_escrow(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let locker_info = account(accounts, 0)?;
+    let escrow_info = account(accounts, 1)?;
+    let escrow_owner = account(accounts, 2)?;
+    let payer = account(accounts, 3)?;
+
+    let mut locker: Locker = load(locker_info)?;
+    let (escrow_key, bump) = escrow_address(locker_info.key, escrow_owner.key);
+    if escrow_info.key != &escrow_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let escrow = Escrow {
+        locker: *locker_info.key,
+        owner: *escrow_owner.key,
+        bump,
+        tokens: get_associated_token_address(&escrow_key, &locker.token_mint),
+        amount: 0,
+        escrow_started_at: 0,
+        escrow_ends_at: 0,
+        vote_delegate: *escrow_owner.key,
+        is_max_lock: false,
+        partial_unstaking_amount: 0,
+        padding: 0,
+        buffers: [0; 9],
+    };
+    let mut data = vec![];
+    escrow.try_serialize(&mut data)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            escrow_info.key,
+            Rent::get()?.minimum_balance(data.len()),
+            data.len() as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[
+            b"Escrow",
+            locker_info.key.as_ref(),
+            escrow_owner.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    escrow_info.try_borrow_mut_data()?.copy_from_slice(&data);
+
+    locker.total_escrow += 1;
+    store(locker_info, &locker)
+}
+
+// accounts: locker, escrow, escrow_tokens, payer, source_tokens, token_program
+fn increase_locked_amount(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let locker_info = account(accounts, 0)?;
+    let escrow_info = account(accounts, 1)?;
+    let escrow_tokens = account(accounts, 2)?;
+    let payer = account(accounts, 3)?;
+    let source_tokens = account(accounts, 4)?;
+    let token_program = account(accounts, 5)?;
+
+    let mut locker: Locker = load(locker_info)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    if escrow_tokens.key != &escrow.tokens {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    #[allow(deprecated)]
+    let transfer_ix = spl_token_2022::instruction::transfer(
+        token_program.key,
+        source_tokens.key,
+        escrow_tokens.key,
+        payer.key,
+        &[],
+        amount,
+    )?;
+    invoke_signed(&transfer_ix, accounts, &[])?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if escrow.escrow_started_at == 0 {
+        escrow.escrow_started_at = now;
+    }
+    escrow.escrow_ends_at = now + locker.params.max_stake_duration as i64;
+    escrow.amount += amount;
+    locker.locked_supply += amount;
+
+    store(escrow_info, &escrow)?;
+    store(locker_info, &locker)
+}
+
+// accounts: locker, escrow, escrow_owner
+// leaving max lock restarts the escrow at the max duration, like WAGMI
+fn toggle_max_lock(accounts: &[AccountInfo], is_max_lock: bool) -> ProgramResult {
+    let locker: Locker = load(account(accounts, 0)?)?;
+    let escrow_info = account(accounts, 1)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, account(accounts, 2)?)?;
+
+    escrow.is_max_lock = is_max_lock;
+    if !is_max_lock {
+        let now = Clock::get()?.unix_timestamp;
+        escrow.escrow_started_at = now;
+        escrow.escrow_ends_at = now + locker.params.max_stake_duration as i64;
+    }
+    store(escrow_info, &escrow)
+}
+
+// accounts: locker, escrow, escrow_owner
+fn extend_lock_duration(accounts: &[AccountInfo], duration: i64) -> ProgramResult {
+    let locker: Locker = load(account(accounts, 0)?)?;
+    let escrow_info = account(accounts, 1)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, account(accounts, 2)?)?;
+
+    escrow.escrow_ends_at += duration;
+    let now = Clock::get()?.unix_timestamp;
+    if escrow.escrow_ends_at > now + locker.params.max_stake_duration as i64 {
+        return Err(ProgramError::Custom(ERR_LOCKUP_DURATION_TOO_LONG));
+    }
+    store(escrow_info, &escrow)
+}
+
+// accounts: escrow, escrow_owner
+fn set_vote_delegate(accounts: &[AccountInfo], new_delegate: Pubkey) -> ProgramResult {
+    let escrow_info = account(accounts, 0)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, account(accounts, 1)?)?;
+
+    escrow.vote_delegate = new_delegate;
+    store(escrow_info, &escrow)
+}
+
+// accounts: locker, escrow, vote_delegate, proposal, vote, governor, govern_program
+// votes with the escrow amount, the locker signs for govern
+fn cast_vote(accounts: &[AccountInfo], side: u8) -> ProgramResult {
+    let locker_info = account(accounts, 0)?;
+    let escrow_info = account(accounts, 1)?;
+    let vote_delegate = account(accounts, 2)?;
+
+    let locker: Locker = load(locker_info)?;
+    let escrow: Escrow = load(escrow_info)?;
+    if &escrow.locker != locker_info.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if !vote_delegate.is_signer || vote_delegate.key != &escrow.vote_delegate {
+        return Err(ProgramError::Custom(ERR_UNAUTHORIZED));
+    }
+
+    let set_vote_ix = Instruction {
+        program_id: neptune::govern::ID,
+        accounts: neptune::govern::client::accounts::SetVote {
+            governor: *account(accounts, 5)?.key,
+            proposal: *account(accounts, 3)?.key,
+            vote: *account(accounts, 4)?.key,
+            locker: *locker_info.key,
+        }
+        .to_account_metas(None),
+        data: neptune::govern::client::args::SetVote {
+            side,
+            weight: escrow.amount,
+        }
+        .data(),
+    };
+    invoke_signed(
+        &set_vote_ix,
+        accounts,
+        &[&[b"Locker", locker.base.as_ref(), &[locker.bump]]],
+    )
+}
+
+// accounts: locker, escrow, partial_unstake, owner, system_program
+fn open_partial_unstaking(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    memo: String,
+) -> ProgramResult {
+    let locker_info = account(accounts, 0)?;
+    let escrow_info = account(accounts, 1)?;
+    let partial_unstake_info = account(accounts, 2)?;
+
+    let locker: Locker = load(locker_info)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, account(accounts, 3)?)?;
+    if !partial_unstake_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !partial_unstake_info.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if amount == 0 || amount > escrow.amount - escrow.partial_unstaking_amount {
+        return Err(ProgramError::Custom(ERR_INSUFFICIENT_AMOUNT));
+    }
+
+    let partial_unstake = PartialUnstaking {
+        escrow: *escrow_info.key,
+        amount,
+        expiration: Clock::get()?.unix_timestamp + locker.params.max_stake_duration as i64,
+        buffers: [0; 6],
+        memo,
+    };
+    let mut data = vec![];
+    partial_unstake.try_serialize(&mut data)?;
+
+    // the vault owner cannot pay rent from a data account, so the stand-in
+    // allocates directly, lamports are not audited by the harness
+    #[allow(deprecated)]
+    partial_unstake_info.realloc(data.len(), true)?;
+    partial_unstake_info.assign(program_id);
+    **partial_unstake_info.try_borrow_mut_lamports()? = Rent::get()?.minimum_balance(data.len());
+    partial_unstake_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(&data);
+
+    escrow.partial_unstaking_amount += amount;
+    store(escrow_info, &escrow)
+}
+
+fn load_partial_unstake(
+    escrow_info: &AccountInfo,
+    partial_unstake_info: &AccountInfo,
+) -> std::result::Result<PartialUnstaking, ProgramError> {
+    let partial_unstake: PartialUnstaking = load(partial_unstake_info)?;
+    if &partial_unstake.escrow != escrow_info.key {
+        return Err(ProgramError::Custom(ERR_INVALID_PARTIAL_UNSTAKE));
+    }
+    Ok(partial_unstake)
+}
+
+// accounts: locker, escrow, partial_unstake, owner
+fn merge_partial_unstaking(accounts: &[AccountInfo]) -> ProgramResult {
+    let escrow_info = account(accounts, 1)?;
+    let partial_unstake_info = account(accounts, 2)?;
+    let owner = account(accounts, 3)?;
+
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, owner)?;
+    let partial_unstake = load_partial_unstake(escrow_info, partial_unstake_info)?;
+
+    escrow.partial_unstaking_amount -= partial_unstake.amount;
+    store(escrow_info, &escrow)?;
+    close(partial_unstake_info, owner)
+}
+
+// accounts: locker, escrow, partial_unstake, owner,
+// escrow_tokens, destination_tokens, payer, token_program
+fn withdraw_partial_unstaking(accounts: &[AccountInfo]) -> ProgramResult {
+    let locker_info = account(accounts, 0)?;
+    let escrow_info = account(accounts, 1)?;
+    let partial_unstake_info = account(accounts, 2)?;
+    let escrow_tokens = account(accounts, 4)?;
+    let destination_tokens = account(accounts, 5)?;
+    let payer = account(accounts, 6)?;
+    let token_program = account(accounts, 7)?;
+
+    let mut locker: Locker = load(locker_info)?;
+    let mut escrow: Escrow = load(escrow_info)?;
+    require_owner(&escrow, account(accounts, 3)?)?;
+    let partial_unstake = load_partial_unstake(escrow_info, partial_unstake_info)?;
+    if Clock::get()?.unix_timestamp < partial_unstake.expiration {
+        return Err(ProgramError::Custom(ERR_UNSTAKING_NOT_EXPIRED));
+    }
+    if escrow_tokens.key != &escrow.tokens {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    #[allow(deprecated)]
+    let transfer_ix = spl_token_2022::instruction::transfer(
+        token_program.key,
+        escrow_tokens.key,
+        destination_tokens.key,
+        escrow_info.key,
+        &[],
+        partial_unstake.amount,
+    )?;
+    invoke_signed(
+        &transfer_ix,
+        accounts,
+        &[&[
+            b"Escrow",
+            locker_info.key.as_ref(),
+            escrow.owner.as_ref(),
+            &[escrow.bump],
+        ]],
+    )?;
+
+    escrow.amount -= partial_unstake.amount;
+    escrow.partial_unstaking_amount -= partial_unstake.amount;
+    locker.locked_supply -= partial_unstake.amount;
+
+    store(escrow_info, &escrow)?;
+    store(locker_info, &locker)?;
+    close(partial_unstake_info, payer)
+}
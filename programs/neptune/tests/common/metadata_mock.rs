@@ -0,0 +1,124 @@
+// stand-in for metaplex token metadata, create v3 & update v2 only,
+// checks the pda & authorities the real program checks
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        entrypoint::ProgramResult, program::invoke_signed, program_pack::Pack, system_instruction,
+    },
+};
+use anchor_spl::{metadata::mpl_token_metadata, token::spl_token};
+use mpl_token_metadata::accounts::Metadata;
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+const KEY_METADATA_V1: u8 = 4;
+
+pub fn process(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    match data.split_first() {
+        Some((&CREATE_METADATA_ACCOUNT_V3, data)) => create(accounts, data),
+        Some((&UPDATE_METADATA_ACCOUNT_V2, data)) => update(accounts, data),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// name, symbol & uri of a borsh DataV2
+fn read_data(data: &mut &[u8]) -> std::result::Result<[String; 3], ProgramError> {
+    let mut read_string = || String::deserialize(data).map_err(|_| ProgramError::InvalidArgument);
+    Ok([read_string()?, read_string()?, read_string()?])
+}
+
+fn write_metadata(
+    info: &AccountInfo,
+    update_authority: &Pubkey,
+    mint: &Pubkey,
+    [name, symbol, uri]: [String; 3],
+) -> ProgramResult {
+    let mut data = vec![KEY_METADATA_V1];
+    (update_authority, mint, name, symbol, uri, 0u16).serialize(&mut data)?;
+    // no creators, sale not happened, mutable, no edition nonce,
+    // token standard, collection, uses, collection details & config
+    data.extend([0, 0, 1, 0, 0, 0, 0, 0, 0]);
+
+    let mut info_data = info.try_borrow_mut_data()?;
+    if info_data.len() < data.len() {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    info_data.fill(0);
+    info_data[..data.len()].copy_from_slice(&data);
+    Ok(())
+}
+
+// accounts: metadata, mint, mint_authority, payer, update_authority, system_program
+fn create(accounts: &[AccountInfo], mut data: &[u8]) -> ProgramResult {
+    let [metadata_info, mint_info, mint_authority, payer, update_authority, system_program, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (metadata, bump) = Metadata::find_pda(mint_info.key);
+    if metadata_info.key != &metadata {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let mint = spl_token::state::Mint::unpack(&mint_info.try_borrow_data()?)?;
+    if mint.mint_authority != Some(*mint_authority.key).into() || !mint_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !update_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let space = 679;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            metadata_info.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &mpl_token_metadata::ID,
+        ),
+        &[payer.clone(), metadata_info.clone(), system_program.clone()],
+        &[&[
+            Metadata::PREFIX,
+            mpl_token_metadata::ID.as_ref(),
+            mint_info.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+    write_metadata(
+        metadata_info,
+        update_authority.key,
+        mint_info.key,
+        read_data(&mut data)?,
+    )
+}
+
+// accounts: metadata, update_authority
+fn update(accounts: &[AccountInfo], mut data: &[u8]) -> ProgramResult {
+    let [metadata_info, update_authority, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if metadata_info.owner != &mpl_token_metadata::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let metadata = Metadata::from_bytes(&metadata_info.try_borrow_data()?)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if metadata.update_authority != *update_authority.key || !update_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // only data updates, authority & flags are left as they are
+    let has_data = bool::deserialize(&mut data)?;
+    if !has_data {
+        return Ok(());
+    }
+    write_metadata(
+        metadata_info,
+        &metadata.update_authority,
+        &metadata.mint,
+        read_data(&mut data)?,
+    )
+}
+
+pub fn load(data: &[u8]) -> Metadata {
+    Metadata::from_bytes(data).expect("invalid metadata")
+}
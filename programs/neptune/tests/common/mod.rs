@@ -0,0 +1,1424 @@
+#![allow(dead_code)]
+
+pub mod govern_mock;
+pub mod lock_voter_mock;
+pub mod metadata_mock;
+pub mod svm;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader_upgradeable, entrypoint::ProgramResult, instruction::Instruction,
+        program_pack::Pack,
+    },
+    system_program, InstructionData,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id, spl_associated_token_account,
+    },
+    metadata::mpl_token_metadata,
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{StateWithExtensions, StateWithExtensionsMut},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use neptune::{
+    govern::{
+        accounts::{Governor, Proposal, Vote},
+        types::{GovernanceParameters, VotingReward},
+    },
+    lock_voter::{
+        accounts::{Escrow, Locker},
+        types::LockerParams,
+    },
+    state::{
+        Config, ConfigParams, FeePayout, LockPolicy, LstMetadata, LstVote, Unstaking, UserState,
+        Vault, VaultList, VaultPage, VaultProposal,
+    },
+    NeptuneError,
+};
+
+use svm::{TestAccount, TestSvm};
+
+pub const UTOKEN_DECIMALS: u8 = 6;
+// WAGMI max_stake_duration, partial unstakings expire after it
+pub const UNSTAKE_DURATION: u64 = 30 * 24 * 60 * 60;
+// of every governor proposal
+pub const VOTING_PERIOD: i64 = 3 * 24 * 60 * 60;
+pub const FEES_BPS: u16 = 100;
+pub const MAX_FEES_BPS: u16 = 5_000;
+pub const TREASURY: Pubkey = Pubkey::new_from_array([7; 32]);
+
+pub fn neptune_err(error: NeptuneError) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+pub fn anchor_err(error: anchor_lang::error::ErrorCode) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+pub fn lst_metadata() -> LstMetadata {
+    LstMetadata {
+        name: "Neptune JUP".to_string(),
+        symbol: "nJUP".to_string(),
+        uri: "https://neptune.example/njup.json".to_string(),
+    }
+}
+
+// spl token mint with UTOKEN_DECIMALS
+pub fn set_spl_mint(svm: &mut TestSvm, mint: Pubkey, mint_authority: Pubkey) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: Some(mint_authority).into(),
+        supply: 0,
+        decimals: UTOKEN_DECIMALS,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    svm.set_account(
+        mint,
+        TestAccount {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+        },
+    );
+}
+
+// permissionless, as before the config
+pub fn config_params() -> ConfigParams {
+    ConfigParams {
+        permissionless: true,
+        max_fees_bps: MAX_FEES_BPS,
+        protocol_fee_bps: 0,
+        treasury: TREASURY,
+    }
+}
+
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[Config::CONFIG_SEED], &neptune::ID).0
+}
+
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[neptune::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+// neptune deployed upgradeable under `upgrade_authority`,
+// bincode UpgradeableLoaderState::Program & ProgramData
+pub fn set_upgrade_authority(svm: &mut TestSvm, upgrade_authority: Pubkey) {
+    let program_data = program_data_address();
+    let program = svm.account(&neptune::ID).unwrap().clone();
+    svm.set_account(
+        neptune::ID,
+        TestAccount {
+            data: [&2u32.to_le_bytes()[..], program_data.as_ref()].concat(),
+            owner: bpf_loader_upgradeable::ID,
+            ..program
+        },
+    );
+    svm.set_account(
+        program_data,
+        TestAccount {
+            lamports: 1,
+            data: [
+                &3u32.to_le_bytes()[..],
+                &0u64.to_le_bytes(),
+                &[1],
+                upgrade_authority.as_ref(),
+            ]
+            .concat(),
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+        },
+    );
+}
+
+pub fn init_config_ix(
+    upgrade_authority: &Pubkey,
+    admin: &Pubkey,
+    params: ConfigParams,
+) -> Instruction {
+    Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::InitConfig {
+            upgrade_authority: *upgrade_authority,
+            config: config_address(),
+            program: neptune::ID,
+            program_data: program_data_address(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::InitConfig {
+            admin: *admin,
+            params,
+        }
+        .data(),
+    }
+}
+
+pub struct Ticket {
+    pub nonce: u64,
+    pub unstaking: Pubkey,
+    pub partial_unstaking: Pubkey,
+    pub lst_escrow_ata: Pubkey,
+}
+
+// one vault over a mock locker, lst mint under `lst_token_program`
+pub struct Env {
+    pub svm: TestSvm,
+    pub lst_token_program: Pubkey,
+    pub utoken_mint: Pubkey,
+    pub utoken_mint_authority: Pubkey,
+    pub locker: Pubkey,
+    pub governor: Pubkey,
+    pub payer: Pubkey,
+    // upgrade authority & config admin
+    pub admin: Pubkey,
+    pub vault_owner: Pubkey,
+    pub base: Pubkey,
+    pub vault: Pubkey,
+    pub escrow: Pubkey,
+    pub lst_mint: Pubkey,
+    pub utoken_escrow_ata: Pubkey,
+}
+
+impl Env {
+    // everything but the vault
+    pub fn new(lst_token_program: Pubkey) -> Self {
+        let mut svm = TestSvm::new();
+        svm.warp_to(1_700_000_000);
+
+        let utoken_mint = Pubkey::new_unique();
+        let utoken_mint_authority = Pubkey::new_unique();
+        set_spl_mint(&mut svm, utoken_mint, utoken_mint_authority);
+
+        // locker signs govern votes, so it needs its real pda
+        let locker_base = Pubkey::new_unique();
+        let (locker, locker_bump) = lock_voter_mock::locker_address(&locker_base);
+        let governor = Pubkey::new_unique();
+        svm.set_anchor_account(
+            governor,
+            &Governor {
+                base: Pubkey::new_unique(),
+                bump: 255,
+                proposal_count: 0,
+                locker,
+                smart_wallet: Pubkey::new_unique(),
+                params: GovernanceParameters {
+                    voting_delay: 0,
+                    voting_period: VOTING_PERIOD as u64,
+                    quorum_votes: 0,
+                    timelock_delay_seconds: 0,
+                },
+                voting_reward: VotingReward {
+                    reward_mint: Pubkey::default(),
+                    reward_vault: Pubkey::default(),
+                    reward_per_proposal: 0,
+                },
+                buffers: [0; 32],
+            },
+        );
+        svm.set_anchor_account(
+            locker,
+            &Locker {
+                base: locker_base,
+                bump: locker_bump,
+                token_mint: utoken_mint,
+                locked_supply: 0,
+                total_escrow: 0,
+                governor,
+                params: LockerParams {
+                    max_stake_vote_multiplier: 10,
+                    min_stake_duration: 0,
+                    max_stake_duration: UNSTAKE_DURATION,
+                    proposal_activation_min_votes: 0,
+                },
+                buffers: [0; 32],
+            },
+        );
+
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 100_000_000_000);
+        let admin = Pubkey::new_unique();
+        svm.airdrop(&admin, 10_000_000_000);
+        set_upgrade_authority(&mut svm, admin);
+        svm.process(init_config_ix(&admin, &admin, config_params()), &[admin])
+            .unwrap();
+        // pays for the accounts owner instructions open
+        let vault_owner = Pubkey::new_unique();
+        svm.airdrop(&vault_owner, 10_000_000_000);
+        let base = Pubkey::new_unique();
+        let (vault, _) =
+            Pubkey::find_program_address(&[Vault::VAULT_SEED, base.as_ref()], &neptune::ID);
+        let (escrow, _) = lock_voter_mock::escrow_address(&locker, &vault);
+        let (lst_mint, _) =
+            Pubkey::find_program_address(&[Vault::VAULT_LST_MINT, vault.as_ref()], &neptune::ID);
+
+        Self {
+            svm,
+            lst_token_program,
+            utoken_mint,
+            utoken_mint_authority,
+            locker,
+            governor,
+            payer,
+            admin,
+            vault_owner,
+            base,
+            vault,
+            escrow,
+            lst_mint,
+            utoken_escrow_ata: get_associated_token_address_with_program_id(
+                &escrow,
+                &utoken_mint,
+                &spl_token::ID,
+            ),
+        }
+    }
+
+    // vault created & escrow token account opened
+    pub fn with_vault(lst_token_program: Pubkey) -> Self {
+        let mut env = Self::new(lst_token_program);
+        env.create_vault(None, lst_metadata()).unwrap();
+        env.create_utoken_escrow_ata();
+        env
+    }
+
+    pub fn create_utoken_escrow_ata(&mut self) {
+        let create_escrow_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &self.payer,
+                &self.escrow,
+                &self.utoken_mint,
+                &spl_token::ID,
+            );
+        self.svm
+            .process(create_escrow_ata_ix, &[self.payer])
+            .unwrap();
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.lst_token_program == spl_token_2022::ID
+    }
+
+    // metaplex accounts for spl lst mints only
+    pub fn metaplex_accounts(&self) -> (Option<Pubkey>, Option<Pubkey>) {
+        self.metaplex_accounts_of(&self.lst_mint)
+    }
+
+    fn metaplex_accounts_of(&self, lst_mint: &Pubkey) -> (Option<Pubkey>, Option<Pubkey>) {
+        if self.is_token_2022() {
+            (None, None)
+        } else {
+            let (lst_metadata, _) = mpl_token_metadata::accounts::Metadata::find_pda(lst_mint);
+            (Some(lst_metadata), Some(mpl_token_metadata::ID))
+        }
+    }
+
+    pub fn create_vault_ix(
+        &self,
+        transfer_hook_program: Option<Pubkey>,
+        metadata: LstMetadata,
+    ) -> Instruction {
+        self.create_vault_ix_with_base(&self.base, transfer_hook_program, metadata)
+    }
+
+    // vault of `base` over the env's locker
+    fn create_vault_ix_with_base(
+        &self,
+        base: &Pubkey,
+        transfer_hook_program: Option<Pubkey>,
+        metadata: LstMetadata,
+    ) -> Instruction {
+        let (vault, _) =
+            Pubkey::find_program_address(&[Vault::VAULT_SEED, base.as_ref()], &neptune::ID);
+        let (lst_mint, _) =
+            Pubkey::find_program_address(&[Vault::VAULT_LST_MINT, vault.as_ref()], &neptune::ID);
+        let (lst_metadata, metadata_program) = self.metaplex_accounts_of(&lst_mint);
+
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::CreateVault {
+                signer: self.payer,
+                config: config_address(),
+                base: *base,
+                vault,
+                lst_mint,
+                utoken_mint: self.utoken_mint,
+                lst_metadata,
+                locker: self.locker,
+                escrow: lock_voter_mock::escrow_address(&self.locker, &vault).0,
+                vault_list: self.vault_list_address(),
+                vault_owner: self.vault_owner,
+                locked_voter: neptune::lock_voter::ID,
+                lst_token_program: self.lst_token_program,
+                metadata_program,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::CreateVault {
+                fees_bps: FEES_BPS,
+                transfer_hook_program,
+                metadata,
+            }
+            .data(),
+        }
+    }
+
+    pub fn create_vault(
+        &mut self,
+        transfer_hook_program: Option<Pubkey>,
+        metadata: LstMetadata,
+    ) -> ProgramResult {
+        let ix = self.create_vault_ix(transfer_hook_program, metadata);
+        self.svm.process(ix, &[self.payer, self.base])
+    }
+
+    pub fn transfer_ownership(&mut self, new_owner: &Pubkey) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::TransferOwnership {
+                owner: self.vault_owner,
+                vault: self.vault,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::TransferOwnership {
+                new_owner: *new_owner,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn accept_ownership(&mut self, pending_owner: &Pubkey) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::AcceptOwnership {
+                pending_owner: *pending_owner,
+                vault: self.vault,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::AcceptOwnership {}.data(),
+        };
+        self.svm.process(ix, &[*pending_owner])
+    }
+
+    // another vault over the env's locker, owned by the env's vault owner
+    pub fn create_other_vault(&mut self, metadata: LstMetadata) -> Pubkey {
+        let base = Pubkey::new_unique();
+        let ix = self.create_vault_ix_with_base(&base, None, metadata);
+        self.svm.process(ix, &[self.payer, base]).unwrap();
+        Pubkey::find_program_address(&[Vault::VAULT_SEED, base.as_ref()], &neptune::ID).0
+    }
+
+    pub fn config_state(&self) -> Config {
+        self.svm.anchor_account(&config_address())
+    }
+
+    pub fn update_config(&mut self, params: ConfigParams) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::UpdateConfig {
+                admin: self.admin,
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::UpdateConfig { params }.data(),
+        };
+        self.svm.process(ix, &[self.admin])
+    }
+
+    pub fn set_creator(&mut self, creator: &Pubkey, allowed: bool) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::UpdateConfig {
+                admin: self.admin,
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SetCreator {
+                creator: *creator,
+                allowed,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.admin])
+    }
+
+    pub fn vault_list_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[VaultList::VAULT_LIST_SEED, self.locker.as_ref()],
+            &neptune::ID,
+        )
+        .0
+    }
+
+    pub fn vault_list_state(&self) -> VaultList {
+        self.svm.anchor_account(&self.vault_list_address())
+    }
+
+    pub fn register_vault(&mut self, name: &str) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::RegisterVault {
+                owner: self.vault_owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault_list: self.vault_list_address(),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::RegisterVault {
+                name: name.to_string(),
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn get_vaults(&mut self, offset: u32, limit: u8) -> VaultPage {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::GetVaults {
+                vault_list: self.vault_list_address(),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::GetVaults { offset, limit }.data(),
+        };
+        self.svm.process(ix, &[]).unwrap();
+        let (program_id, data) = self.svm.return_data().unwrap();
+        assert_eq!(program_id, neptune::ID);
+        VaultPage::deserialize(&mut &data[..]).unwrap()
+    }
+
+    pub fn update_fees_bps(&mut self, fees_bps: u16) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::UpdateFeesBps {
+                owner: self.vault_owner,
+                vault: self.vault,
+                vault_list: Some(self.vault_list_address()),
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::UpdateFeesBps { fees_bps }.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SetPaused {
+                owner: self.vault_owner,
+                vault: self.vault,
+                vault_list: Some(self.vault_list_address()),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SetPaused { paused }.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    // funded wallet holding `utoken_amt`
+    pub fn new_user(&mut self, utoken_amt: u64) -> Pubkey {
+        let user = Pubkey::new_unique();
+        self.svm.airdrop(&user, 10_000_000_000);
+
+        let create_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &user,
+                &user,
+                &self.utoken_mint,
+                &spl_token::ID,
+            );
+        self.svm.process(create_ata_ix, &[user]).unwrap();
+
+        let mint_to_ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &self.utoken_mint,
+            &self.utoken_ata(&user),
+            &self.utoken_mint_authority,
+            &[],
+            utoken_amt,
+        )
+        .unwrap();
+        self.svm
+            .process(mint_to_ix, &[self.utoken_mint_authority])
+            .unwrap();
+
+        user
+    }
+
+    pub fn utoken_ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.utoken_mint, &spl_token::ID)
+    }
+
+    pub fn lst_ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.lst_mint, &self.lst_token_program)
+    }
+
+    pub fn stake_ix(&self, user: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::Stake {
+                signer: *user,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                lst_mint: self.lst_mint,
+                utoken_mint: self.utoken_mint,
+                utoken_source_ata: self.utoken_ata(user),
+                lst_ata: self.lst_ata(user),
+                utoken_buffer_ata: None,
+                locked_voter: neptune::lock_voter::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: spl_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::Stake {
+                amount,
+                min_lst_out: 0,
+            }
+            .data(),
+        }
+    }
+
+    pub fn stake(&mut self, user: &Pubkey, amount: u64) -> ProgramResult {
+        let ix = self.stake_ix(user, amount);
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn user_state_address(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                UserState::USER_STATE_SEED,
+                self.vault.as_ref(),
+                user.as_ref(),
+            ],
+            &neptune::ID,
+        )
+        .0
+    }
+
+    pub fn user_state(&self, user: &Pubkey) -> Option<UserState> {
+        let user_state = self.user_state_address(user);
+        self.svm
+            .account(&user_state)
+            .map(|_| self.svm.anchor_account(&user_state))
+    }
+
+    pub fn ticket(&self, user: &Pubkey, nonce: u64) -> Ticket {
+        let (unstaking, _) = Pubkey::find_program_address(
+            &[
+                Unstaking::UNSTAKING_SEED,
+                self.vault.as_ref(),
+                user.as_ref(),
+                &nonce.to_le_bytes(),
+            ],
+            &neptune::ID,
+        );
+        let (partial_unstaking, _) = Pubkey::find_program_address(
+            &[Unstaking::PARTIAL_UNSTAKING_SEED, unstaking.as_ref()],
+            &neptune::ID,
+        );
+        let (lst_escrow_ata, _) = Pubkey::find_program_address(
+            &[Unstaking::UNSTAKING_ESCROW_ATA_SEED, unstaking.as_ref()],
+            &neptune::ID,
+        );
+        Ticket {
+            nonce,
+            unstaking,
+            partial_unstaking,
+            lst_escrow_ata,
+        }
+    }
+
+    // the ticket begin_unstaking opens next for `user`
+    pub fn new_ticket(&self, user: &Pubkey) -> Ticket {
+        let nonce = self
+            .user_state(user)
+            .map_or(0, |user_state| user_state.nonce);
+        self.ticket(user, nonce)
+    }
+
+    pub fn begin_unstaking_ix(&self, user: &Pubkey, ticket: &Ticket, amount: u64) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::BeginUnstaking {
+                signer: *user,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                lst_mint: self.lst_mint,
+                user_state: self.user_state_address(user),
+                unstaking: ticket.unstaking,
+                partial_unstaking: ticket.partial_unstaking,
+                lst_source_ata: self.lst_ata(user),
+                lst_escrow_ata: ticket.lst_escrow_ata,
+                locked_voter: neptune::lock_voter::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::BeginUnstaking {
+                amount,
+                min_utoken_out: 0,
+            }
+            .data(),
+        }
+    }
+
+    pub fn begin_unstaking(
+        &mut self,
+        user: &Pubkey,
+        amount: u64,
+    ) -> std::result::Result<Ticket, ProgramError> {
+        let ticket = self.new_ticket(user);
+        let ix = self.begin_unstaking_ix(user, &ticket, amount);
+        self.svm.process(ix, &[*user])?;
+        Ok(ticket)
+    }
+
+    pub fn merge_unstaking_ix(&self, user: &Pubkey, ticket: &Ticket) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::MergeUnstake {
+                signer: *user,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                partial_unstaking: ticket.partial_unstaking,
+                lst_mint: self.lst_mint,
+                lst_ata: self.lst_ata(user),
+                unstaking: ticket.unstaking,
+                lst_escrow_ata: ticket.lst_escrow_ata,
+                locked_voter: neptune::lock_voter::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::MergeUnstaking {}.data(),
+        }
+    }
+
+    pub fn merge_unstaking(&mut self, user: &Pubkey, ticket: &Ticket) -> ProgramResult {
+        let ix = self.merge_unstaking_ix(user, ticket);
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn partial_merge_unstaking(
+        &mut self,
+        user: &Pubkey,
+        ticket: &Ticket,
+        amount: u64,
+    ) -> ProgramResult {
+        let mut ix = self.merge_unstaking_ix(user, ticket);
+        ix.data = neptune::instruction::PartialMergeUnstaking { amount }.data();
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn withdraw_unstake_ix(&self, user: &Pubkey, ticket: &Ticket) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::WithdrawUnstake {
+                signer: *user,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                partial_unstaking: ticket.partial_unstaking,
+                lst_mint: self.lst_mint,
+                unstaking: ticket.unstaking,
+                lst_escrow_ata: ticket.lst_escrow_ata,
+                lst_ata: self.lst_ata(user),
+                utoken_mint: self.utoken_mint,
+                utoken_target_ata: self.utoken_ata(user),
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                locked_voter: neptune::lock_voter::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: spl_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::WithdrawUnstake {}.data(),
+        }
+    }
+
+    pub fn withdraw_unstake(&mut self, user: &Pubkey, ticket: &Ticket) -> ProgramResult {
+        let ix = self.withdraw_unstake_ix(user, ticket);
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn partial_withdraw_unstake(
+        &mut self,
+        user: &Pubkey,
+        ticket: &Ticket,
+        amount: u64,
+    ) -> ProgramResult {
+        let mut ix = self.withdraw_unstake_ix(user, ticket);
+        ix.data = neptune::instruction::PartialWithdrawUnstake { amount }.data();
+        self.svm.process(ix, &[*user])
+    }
+
+    // donation to the vault's reward ata, compounded into the escrow
+    pub fn compound(&mut self, utoken_amt: u64) -> ProgramResult {
+        let utoken_reward_ata = self.utoken_ata(&self.vault);
+        if self.svm.account(&utoken_reward_ata).is_none() {
+            let create_ata_ix =
+                spl_associated_token_account::instruction::create_associated_token_account(
+                    &self.payer,
+                    &self.vault,
+                    &self.utoken_mint,
+                    &spl_token::ID,
+                );
+            self.svm.process(create_ata_ix, &[self.payer])?;
+        }
+        let mint_to_ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &self.utoken_mint,
+            &utoken_reward_ata,
+            &self.utoken_mint_authority,
+            &[],
+            utoken_amt,
+        )?;
+        self.svm
+            .process(mint_to_ix, &[self.utoken_mint_authority])?;
+
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::Compound {
+                signer: self.payer,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                lst_mint: self.lst_mint,
+                config: config_address(),
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                utoken_reward_ata,
+                locked_voter: neptune::lock_voter::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::Compound {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    pub fn sync_vault(&mut self) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SyncVault {
+                signer: self.payer,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                lst_mint: self.lst_mint,
+                config: config_address(),
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SyncVault {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    pub fn resolve_deficit(&mut self) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::ResolveDeficit {
+                owner: self.vault_owner,
+                escrow: self.escrow,
+                vault: self.vault,
+                utoken_escrow_ata: self.utoken_escrow_ata,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::ResolveDeficit {}.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    // plain lst transfer, e.g. a donation to a ticket escrow
+    pub fn transfer_lst(
+        &mut self,
+        user: &Pubkey,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> ProgramResult {
+        let ix = spl_token_2022::instruction::transfer_checked(
+            &self.lst_token_program,
+            &self.lst_ata(user),
+            &self.lst_mint,
+            destination,
+            user,
+            &[],
+            amount,
+            // lst takes the utoken decimals
+            UTOKEN_DECIMALS,
+        )?;
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn sweep_escrow_dust_ix(
+        &self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        source: &Pubkey,
+        destination: Option<Pubkey>,
+        unstaking: Option<Pubkey>,
+    ) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SweepEscrowDust {
+                owner: self.vault_owner,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                mint: *mint,
+                source: *source,
+                destination,
+                unstaking,
+                token_program: *token_program,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SweepEscrowDust {}.data(),
+        }
+    }
+
+    // lst dust of a ticket escrow, back to the ticket owner
+    pub fn sweep_lst_escrow_dust(&mut self, user: &Pubkey, ticket: &Ticket) -> ProgramResult {
+        let ix = self.sweep_escrow_dust_ix(
+            &self.lst_mint,
+            &self.lst_token_program,
+            &ticket.lst_escrow_ata,
+            Some(self.lst_ata(user)),
+            Some(ticket.unstaking),
+        );
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn utoken_buffer_ata(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Vault::VAULT_BUFFER_SEED, self.vault.as_ref()],
+            &neptune::ID,
+        )
+        .0
+    }
+
+    pub fn buffer_refill(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Vault::VAULT_BUFFER_REFILL_SEED, self.vault.as_ref()],
+            &neptune::ID,
+        )
+        .0
+    }
+
+    pub fn stake_with_buffer(&mut self, user: &Pubkey, amount: u64) -> ProgramResult {
+        let mut ix = self.stake_ix(user, amount);
+        ix.accounts[9].pubkey = self.utoken_buffer_ata();
+        ix.accounts[9].is_writable = true;
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn configure_buffer(
+        &mut self,
+        buffer_bps: u16,
+        instant_unstake_fee_bps: u16,
+    ) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::ConfigureBuffer {
+                owner: self.vault_owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::ConfigureBuffer {
+                buffer_bps,
+                instant_unstake_fee_bps,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn instant_unstake(&mut self, user: &Pubkey, amount: u64) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::InstantUnstake {
+                signer: *user,
+                vault: self.vault,
+                config: config_address(),
+                lst_mint: self.lst_mint,
+                lst_source_ata: self.lst_ata(user),
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                utoken_target_ata: self.utoken_ata(user),
+                associated_token_program: spl_associated_token_account::ID,
+                token_program: spl_token::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::InstantUnstake { amount }.data(),
+        };
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn refill_buffer(&mut self) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::RefillBuffer {
+                signer: self.payer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                buffer_refill: self.buffer_refill(),
+                locked_voter: neptune::lock_voter::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::RefillBuffer {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    pub fn withdraw_buffer_refill(&mut self) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::WithdrawBufferRefill {
+                signer: self.payer,
+                locker: self.locker,
+                escrow: self.escrow,
+                vault: self.vault,
+                buffer_refill: self.buffer_refill(),
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: self.utoken_buffer_ata(),
+                utoken_escrow_ata: self.utoken_escrow_ata,
+                locked_voter: neptune::lock_voter::ID,
+                token_program: spl_token::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::WithdrawBufferRefill {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    // `owner` ata of the payout mint, opened if needed
+    pub fn fee_ata(&mut self, owner: &Pubkey, payout: FeePayout) -> Pubkey {
+        let (mint, token_program) = match payout {
+            FeePayout::Lst => (self.lst_mint, self.lst_token_program),
+            FeePayout::Utoken => (self.utoken_mint, spl_token::ID),
+        };
+        let create_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &self.payer,
+                owner,
+                &mint,
+                &token_program,
+            );
+        self.svm.process(create_ata_ix, &[self.payer]).unwrap();
+        get_associated_token_address_with_program_id(owner, &mint, &token_program)
+    }
+
+    pub fn claim_fees_ix(
+        &self,
+        authority: &Pubkey,
+        payout: FeePayout,
+        destination: &Pubkey,
+        protocol: bool,
+    ) -> Instruction {
+        Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::ClaimFees {
+                authority: *authority,
+                config: config_address(),
+                vault: self.vault,
+                lst_mint: self.lst_mint,
+                utoken_mint: self.utoken_mint,
+                utoken_buffer_ata: (payout == FeePayout::Utoken).then(|| self.utoken_buffer_ata()),
+                destination: *destination,
+                token_program: spl_token::ID,
+                lst_token_program: self.lst_token_program,
+            }
+            .to_account_metas(None),
+            data: if protocol {
+                neptune::instruction::ClaimProtocolFees { payout }.data()
+            } else {
+                neptune::instruction::ClaimOwnerFees { payout }.data()
+            },
+        }
+    }
+
+    pub fn claim_owner_fees(&mut self, payout: FeePayout) -> ProgramResult {
+        let destination = self.fee_ata(&self.vault_owner.clone(), payout);
+        let ix = self.claim_fees_ix(&self.vault_owner, payout, &destination, false);
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn claim_protocol_fees(&mut self, payout: FeePayout) -> ProgramResult {
+        let destination = self.fee_ata(&TREASURY, payout);
+        let ix = self.claim_fees_ix(&self.admin, payout, &destination, true);
+        self.svm.process(ix, &[self.admin])
+    }
+
+    pub fn set_lock_policy(&mut self, lock_policy: LockPolicy) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SetLockPolicy {
+                owner: self.vault_owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: neptune::lock_voter::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SetLockPolicy { lock_policy }.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn extend_lock_duration(&mut self, duration: i64) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::ExtendLockDuration {
+                owner: self.vault_owner,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: neptune::lock_voter::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::ExtendLockDuration { duration }.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn enforce_lock_policy(&mut self) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::EnforceLockPolicy {
+                signer: self.payer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                locked_voter: neptune::lock_voter::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::EnforceLockPolicy {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    pub fn set_vote_delegate(&mut self, new_delegate: &Pubkey) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SetVoteDelegate {
+                owner: self.vault_owner,
+                vault: self.vault,
+                escrow: self.escrow,
+                locked_voter: neptune::lock_voter::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SetVoteDelegate {
+                new_delegate: *new_delegate,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    // active proposal of the env's governor
+    pub fn new_proposal(&mut self, max_option: u8) -> Pubkey {
+        let now = self.svm.unix_timestamp();
+        let proposal = Pubkey::new_unique();
+        self.svm.set_anchor_account(
+            proposal,
+            &Proposal {
+                governor: self.governor,
+                index: 0,
+                bump: 255,
+                proposer: Pubkey::new_unique(),
+                quorum_votes: 0,
+                max_option,
+                option_votes: vec![0; max_option as usize],
+                canceled_at: 0,
+                created_at: now,
+                activated_at: now,
+                voting_ends_at: now + VOTING_PERIOD,
+                queued_at: 0,
+                queued_transaction: Pubkey::default(),
+                voting_reward: VotingReward {
+                    reward_mint: Pubkey::default(),
+                    reward_vault: Pubkey::default(),
+                    reward_per_proposal: 0,
+                },
+                total_claimed_reward: 0,
+                // option proposal
+                proposal_type: 1,
+                buffers: [0; 10],
+                instructions: vec![],
+            },
+        );
+        proposal
+    }
+
+    pub fn proposal_state(&self, proposal: &Pubkey) -> Proposal {
+        self.svm.anchor_account(proposal)
+    }
+
+    // the vault's govern vote on `proposal`
+    pub fn vote_state(&self, proposal: &Pubkey) -> Vote {
+        let (vote, _) = govern_mock::vote_address(proposal, &self.vault);
+        self.svm.anchor_account(&vote)
+    }
+
+    pub fn set_voter(&mut self, new_voter: &Pubkey) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::SetVoter {
+                owner: self.vault_owner,
+                vault: self.vault,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::SetVoter {
+                new_voter: *new_voter,
+            }
+            .data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn cast_vote(&mut self, voter: &Pubkey, proposal: &Pubkey, side: u8) -> ProgramResult {
+        let (vote, _) = govern_mock::vote_address(proposal, &self.vault);
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::CastVote {
+                voter: *voter,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                governor: self.governor,
+                proposal: *proposal,
+                vote,
+                vault_proposal: self.vault_proposal_address(proposal),
+                locked_voter: neptune::lock_voter::ID,
+                govern_program: neptune::govern::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::CastVote { side }.data(),
+        };
+        self.svm.process(ix, &[*voter])
+    }
+
+    pub fn vault_proposal_address(&self, proposal: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                VaultProposal::VAULT_PROPOSAL_SEED,
+                self.vault.as_ref(),
+                proposal.as_ref(),
+            ],
+            &neptune::ID,
+        )
+        .0
+    }
+
+    pub fn vault_proposal_state(&self, proposal: &Pubkey) -> VaultProposal {
+        self.svm
+            .anchor_account(&self.vault_proposal_address(proposal))
+    }
+
+    pub fn create_vault_proposal(&mut self, proposal: &Pubkey) -> ProgramResult {
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::CreateVaultProposal {
+                signer: self.payer,
+                vault: self.vault,
+                locker: self.locker,
+                escrow: self.escrow,
+                proposal: *proposal,
+                vault_proposal: self.vault_proposal_address(proposal),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::CreateVaultProposal {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    // lst vote & its escrow token account
+    pub fn lst_vote_address(&self, user: &Pubkey, proposal: &Pubkey) -> (Pubkey, Pubkey) {
+        let vault_proposal = self.vault_proposal_address(proposal);
+        let (lst_vote, _) = Pubkey::find_program_address(
+            &[
+                LstVote::LST_VOTE_SEED,
+                vault_proposal.as_ref(),
+                user.as_ref(),
+            ],
+            &neptune::ID,
+        );
+        let (lst_vote_escrow_ata, _) = Pubkey::find_program_address(
+            &[LstVote::LST_VOTE_ESCROW_ATA_SEED, lst_vote.as_ref()],
+            &neptune::ID,
+        );
+        (lst_vote, lst_vote_escrow_ata)
+    }
+
+    pub fn cast_lst_vote(
+        &mut self,
+        user: &Pubkey,
+        proposal: &Pubkey,
+        side: u8,
+        amount: u64,
+    ) -> ProgramResult {
+        let (lst_vote, lst_vote_escrow_ata) = self.lst_vote_address(user, proposal);
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::CastLstVote {
+                signer: *user,
+                vault: self.vault,
+                vault_proposal: self.vault_proposal_address(proposal),
+                lst_mint: self.lst_mint,
+                lst_source_ata: self.lst_ata(user),
+                lst_vote,
+                lst_vote_escrow_ata,
+                associated_token_program: spl_associated_token_account::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::CastLstVote { side, amount }.data(),
+        };
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn withdraw_lst_vote(&mut self, user: &Pubkey, proposal: &Pubkey) -> ProgramResult {
+        let (lst_vote, lst_vote_escrow_ata) = self.lst_vote_address(user, proposal);
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::WithdrawLstVote {
+                signer: *user,
+                vault: self.vault,
+                vault_proposal: self.vault_proposal_address(proposal),
+                lst_mint: self.lst_mint,
+                lst_vote,
+                lst_vote_escrow_ata,
+                lst_ata: self.lst_ata(user),
+                associated_token_program: spl_associated_token_account::ID,
+                lst_token_program: self.lst_token_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::WithdrawLstVote {}.data(),
+        };
+        self.svm.process(ix, &[*user])
+    }
+
+    pub fn cast_vault_vote(&mut self, proposal: &Pubkey) -> ProgramResult {
+        let (vote, _) = govern_mock::vote_address(proposal, &self.vault);
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::CastVaultVote {
+                signer: self.payer,
+                vault: self.vault,
+                vault_proposal: self.vault_proposal_address(proposal),
+                locker: self.locker,
+                escrow: self.escrow,
+                governor: self.governor,
+                proposal: *proposal,
+                vote,
+                locked_voter: neptune::lock_voter::ID,
+                govern_program: neptune::govern::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::CastVaultVote {}.data(),
+        };
+        self.svm.process(ix, &[self.payer])
+    }
+
+    pub fn update_lst_metadata(&mut self, metadata: LstMetadata) -> ProgramResult {
+        let (lst_metadata, metadata_program) = self.metaplex_accounts();
+        let ix = Instruction {
+            program_id: neptune::ID,
+            accounts: neptune::accounts::UpdateLstMetadata {
+                owner: self.vault_owner,
+                vault: self.vault,
+                vault_list: Some(self.vault_list_address()),
+                lst_mint: self.lst_mint,
+                lst_metadata,
+                lst_token_program: self.lst_token_program,
+                metadata_program,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: neptune::instruction::UpdateLstMetadata { metadata }.data(),
+        };
+        self.svm.process(ix, &[self.vault_owner])
+    }
+
+    pub fn warp_past_unstaking(&mut self) {
+        let now = self.svm.unix_timestamp();
+        self.svm.warp_to(now + UNSTAKE_DURATION as i64 + 1);
+    }
+
+    pub fn vault_state(&self) -> Vault {
+        self.svm.anchor_account(&self.vault)
+    }
+
+    pub fn escrow_state(&self) -> Escrow {
+        self.svm.anchor_account(&self.escrow)
+    }
+
+    pub fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        self.svm.account(token_account).map_or(0, |account| {
+            StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        })
+    }
+
+    // stands in for tokens lost or sent outside the program
+    pub fn set_token_balance(&mut self, token_account: &Pubkey, amount: u64) {
+        let mut account = self.svm.account(token_account).unwrap().clone();
+        let mut state = StateWithExtensionsMut::<TokenAccount>::unpack(&mut account.data).unwrap();
+        state.base.amount = amount;
+        state.pack_base();
+        self.svm.set_account(*token_account, account);
+    }
+
+    // spl lst mints only
+    pub fn lst_metaplex_metadata(&self) -> mpl_token_metadata::accounts::Metadata {
+        let (lst_metadata, _) = self.metaplex_accounts();
+        let account = self.svm.account(&lst_metadata.unwrap()).unwrap();
+        metadata_mock::load(&account.data)
+    }
+
+    pub fn lst_supply(&self) -> u64 {
+        let account = self.svm.account(&self.lst_mint).unwrap();
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+            .supply
+    }
+}
@@ -0,0 +1,451 @@
+// in-process bank: serializes accounts the way the runtime does,
+// runs programs natively and routes cpis through syscall stubs.
+// litesvm and a solana 2.x solana-program-test are not in the registry this
+// builds against, and there is no sbf toolchain to build the stand-ins with
+use std::{cell::RefCell, collections::HashMap, mem::size_of, sync::Once};
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader,
+        entrypoint::{self, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE},
+        instruction::Instruction,
+        program_stubs::{self, SyscallStubs},
+        system_program,
+    },
+};
+use anchor_spl::{
+    associated_token::spl_associated_token_account, metadata::mpl_token_metadata, token::spl_token,
+    token_2022::spl_token_2022,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::{govern_mock, lock_voter_mock, metadata_mock};
+
+type Processor = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+const SUCCESS: u64 = 0;
+const NON_DUP_MARKER: u8 = u8::MAX;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+thread_local! {
+    static PROGRAM_STACK: RefCell<Vec<Pubkey>> = const { RefCell::new(Vec::new()) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::default());
+    static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn processor_of(program_id: &Pubkey) -> Option<Processor> {
+    match *program_id {
+        id if id == system_program::ID => Some(system_processor),
+        id if id == spl_token::ID => Some(spl_token::processor::Processor::process),
+        id if id == spl_token_2022::ID => Some(spl_token_2022::processor::Processor::process),
+        id if id == spl_associated_token_account::ID => {
+            Some(spl_associated_token_account::processor::process_instruction)
+        }
+        id if id == neptune::lock_voter::ID => Some(lock_voter_mock::process),
+        id if id == neptune::govern::ID => Some(govern_mock::process),
+        id if id == mpl_token_metadata::ID => Some(metadata_mock::process),
+        _ => None,
+    }
+}
+
+// neptune is not in processor_of, anchor's entry ties the slice and account
+// lifetimes together, only the top level call has both. nothing cpis into it
+fn process_neptune<'a>(accounts: &'a [AccountInfo<'a>], data: &[u8]) -> ProgramResult {
+    run_as(&neptune::ID, || {
+        neptune::entry(&neptune::ID, accounts, data)
+    })
+}
+
+fn process_with(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let processor = processor_of(program_id).ok_or(ProgramError::IncorrectProgramId)?;
+    run_as(program_id, || processor(program_id, accounts, data))
+}
+
+fn run_as(program_id: &Pubkey, process: impl FnOnce() -> ProgramResult) -> ProgramResult {
+    PROGRAM_STACK.with(|stack| stack.borrow_mut().push(*program_id));
+    let result = process();
+    PROGRAM_STACK.with(|stack| stack.borrow_mut().pop());
+    result
+}
+
+fn current_program() -> Pubkey {
+    PROGRAM_STACK.with(|stack| *stack.borrow().last().expect("no program running"))
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+    }
+
+    // as the runtime logs emit!, one base64 field per slice
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.sol_log(&format!("Program data: {}", fields.join(" ")));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = current_program();
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut callee_infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in &instruction.accounts {
+            let info = account_infos
+                .iter()
+                .find(|info| info.key == &meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            // privileges only pass through, or come from the caller's pdas
+            if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::Immutable);
+            }
+            let mut info = info.clone();
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            callee_infos.push(info);
+        }
+
+        process_with(&instruction.program_id, &callee_infos, &instruction.data)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CLOCK.with(|clock| clock.borrow().clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut EpochSchedule) = EpochSchedule::default() };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|data| data.borrow().clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = current_program();
+        RETURN_DATA.with(|return_data| {
+            *return_data.borrow_mut() = (!data.is_empty()).then(|| (program_id, data.to_vec()))
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        PROGRAM_STACK.with(|stack| stack.borrow().len() as u64)
+    }
+}
+
+// runtime system program subset used by anchor, token & ata programs
+fn system_processor(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let read_u64 = |at: usize| -> std::result::Result<u64, ProgramError> {
+        data.get(at..at + 8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidInstructionData)
+    };
+    let read_pubkey = |at: usize| -> std::result::Result<Pubkey, ProgramError> {
+        data.get(at..at + 32)
+            .map(|b| Pubkey::new_from_array(b.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidInstructionData)
+    };
+    let signer = |index: usize| -> std::result::Result<&AccountInfo, ProgramError> {
+        let info = accounts
+            .get(index)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if !info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(info)
+    };
+    let transfer = |from: &AccountInfo, to: &AccountInfo, lamports: u64| -> ProgramResult {
+        if !from.data_is_empty() || from.owner != &system_program::ID {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let from_lamports = from
+            .lamports()
+            .checked_sub(lamports)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **from.try_borrow_mut_lamports()? = from_lamports;
+        **to.try_borrow_mut_lamports()? += lamports;
+        Ok(())
+    };
+    let allocate = |info: &AccountInfo, space: u64| -> ProgramResult {
+        if !info.data_is_empty() || info.owner != &system_program::ID {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        #[allow(deprecated)]
+        info.realloc(space as usize, true)
+    };
+
+    let tag = data
+        .get(..4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    match tag {
+        // create_account
+        0 => {
+            let (from, to) = (signer(0)?, signer(1)?);
+            if to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            transfer(from, to, read_u64(4)?)?;
+            allocate(to, read_u64(12)?)?;
+            to.assign(&read_pubkey(20)?);
+            Ok(())
+        }
+        // assign
+        1 => {
+            signer(0)?.assign(&read_pubkey(4)?);
+            Ok(())
+        }
+        // transfer
+        2 => {
+            let from = signer(0)?;
+            let to = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+            transfer(from, to, read_u64(4)?)
+        }
+        // allocate
+        8 => allocate(signer(0)?, read_u64(4)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub struct TestSvm {
+    accounts: HashMap<Pubkey, TestAccount>,
+}
+
+impl TestSvm {
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            program_stubs::set_syscall_stubs(Box::new(Stubs));
+        });
+        CLOCK.with(|clock| *clock.borrow_mut() = Clock::default());
+
+        let mut svm = Self {
+            accounts: HashMap::new(),
+        };
+        for program_id in [
+            system_program::ID,
+            spl_token::ID,
+            spl_token_2022::ID,
+            spl_associated_token_account::ID,
+            neptune::lock_voter::ID,
+            neptune::govern::ID,
+            mpl_token_metadata::ID,
+            neptune::ID,
+        ] {
+            svm.set_account(
+                program_id,
+                TestAccount {
+                    lamports: 1,
+                    data: vec![],
+                    owner: bpf_loader::ID,
+                    executable: true,
+                },
+            );
+        }
+        svm
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<&TestAccount> {
+        self.accounts.get(key)
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: TestAccount) {
+        self.accounts.insert(key, account);
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        self.accounts.entry(*key).or_default().lamports += lamports;
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        let account = self.account(key).expect("account not found");
+        T::try_deserialize(&mut account.data.as_slice()).expect("invalid account data")
+    }
+
+    pub fn set_anchor_account<T: AccountSerialize + Owner>(&mut self, key: Pubkey, value: &T) {
+        let mut data = vec![];
+        value.try_serialize(&mut data).unwrap();
+        self.set_account(
+            key,
+            TestAccount {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: T::owner(),
+                executable: false,
+            },
+        );
+    }
+
+    pub fn unix_timestamp(&self) -> i64 {
+        CLOCK.with(|clock| clock.borrow().unix_timestamp)
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        CLOCK.with(|clock| clock.borrow_mut().unix_timestamp = unix_timestamp);
+    }
+
+    // set by the last processed instruction
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|data| data.borrow().clone())
+    }
+
+    // logged by the last processed instruction, cpis included
+    pub fn logs(&self) -> Vec<String> {
+        LOGS.with(|logs| logs.borrow().clone())
+    }
+
+    // anchor events of type T in the last instruction's logs,
+    // decoded the way indexers read them
+    pub fn events<T: AnchorDeserialize + Discriminator>(&self) -> Vec<T> {
+        self.logs()
+            .iter()
+            .filter_map(|log| {
+                let data = STANDARD.decode(log.strip_prefix("Program data: ")?).ok()?;
+                T::try_from_slice(data.strip_prefix(T::DISCRIMINATOR)?).ok()
+            })
+            .collect()
+    }
+
+    pub fn event<T: AnchorDeserialize + Discriminator>(&self) -> T {
+        let mut events = self.events::<T>();
+        assert_eq!(events.len(), 1, "expected a single event");
+        events.remove(0)
+    }
+
+    // atomic like a single instruction transaction,
+    // only accounts in `signers` sign whatever the metas say
+    pub fn process(&mut self, instruction: Instruction, signers: &[Pubkey]) -> ProgramResult {
+        LOGS.with(|logs| logs.borrow_mut().clear());
+        RETURN_DATA.with(|data| *data.borrow_mut() = None);
+
+        let mut metas = instruction.accounts.clone();
+        for meta in &mut metas {
+            meta.is_signer = signers.contains(&meta.pubkey);
+        }
+
+        let mut input = self.serialize(&metas, &instruction);
+        let (program_id, infos, data) =
+            unsafe { entrypoint::deserialize(input.as_mut_ptr() as *mut u8) };
+        let result = if program_id == &neptune::ID {
+            process_neptune(&infos, data)
+        } else {
+            process_with(program_id, &infos, data)
+        };
+
+        if result.is_ok() {
+            for info in infos.iter() {
+                let account = TestAccount {
+                    lamports: info.lamports(),
+                    data: info.data.borrow().to_vec(),
+                    owner: *info.owner,
+                    executable: info.executable,
+                };
+                // like the runtime, drained accounts are gone
+                if account.lamports == 0 {
+                    self.accounts.remove(info.key);
+                } else {
+                    self.accounts.insert(*info.key, account);
+                }
+            }
+        }
+        result
+    }
+
+    fn serialize(&self, metas: &[AccountMeta], instruction: &Instruction) -> Vec<u64> {
+        let align = |offset: usize| offset.div_ceil(BPF_ALIGN_OF_U128) * BPF_ALIGN_OF_U128;
+        let empty = TestAccount::default();
+
+        let mut len = size_of::<u64>();
+        let mut seen = vec![];
+        for meta in metas {
+            if seen.contains(&meta.pubkey) {
+                len += size_of::<u64>();
+                continue;
+            }
+            seen.push(meta.pubkey);
+            let data_len = self.account(&meta.pubkey).unwrap_or(&empty).data.len();
+            len = align(len + 4 + 4 + 32 + 32 + 8 + 8 + data_len + MAX_PERMITTED_DATA_INCREASE) + 8;
+        }
+        len += 8 + instruction.data.len() + 32;
+
+        let mut buffer = vec![0u64; len.div_ceil(8)];
+        let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, len) };
+        let mut offset = 0;
+        let mut write = |src: &[u8], offset: &mut usize| {
+            bytes[*offset..*offset + src.len()].copy_from_slice(src);
+            *offset += src.len();
+        };
+
+        write(&(metas.len() as u64).to_le_bytes(), &mut offset);
+        let mut seen = vec![];
+        for meta in metas {
+            if let Some(index) = seen.iter().position(|key| key == &meta.pubkey) {
+                write(&[index as u8, 0, 0, 0, 0, 0, 0, 0], &mut offset);
+                continue;
+            }
+            seen.push(meta.pubkey);
+            let account = self.account(&meta.pubkey).unwrap_or(&empty);
+            // writable if any meta of the key is
+            let is_writable = metas
+                .iter()
+                .any(|other| other.pubkey == meta.pubkey && other.is_writable);
+            let is_signer = metas
+                .iter()
+                .any(|other| other.pubkey == meta.pubkey && other.is_signer);
+            write(
+                &[
+                    NON_DUP_MARKER,
+                    is_signer as u8,
+                    is_writable as u8,
+                    account.executable as u8,
+                ],
+                &mut offset,
+            );
+            write(&[0; 4], &mut offset);
+            write(meta.pubkey.as_ref(), &mut offset);
+            write(account.owner.as_ref(), &mut offset);
+            write(&account.lamports.to_le_bytes(), &mut offset);
+            write(&(account.data.len() as u64).to_le_bytes(), &mut offset);
+            write(&account.data, &mut offset);
+            offset = align(offset + MAX_PERMITTED_DATA_INCREASE);
+            write(&u64::MAX.to_le_bytes(), &mut offset);
+        }
+        write(&(instruction.data.len() as u64).to_le_bytes(), &mut offset);
+        write(&instruction.data, &mut offset);
+        write(instruction.program_id.as_ref(), &mut offset);
+
+        buffer
+    }
+}
@@ -0,0 +1,2124 @@
+mod common;
+
+use anchor_lang::{
+    error::ErrorCode, prelude::*, solana_program::instruction::Instruction, system_program,
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint,
+    },
+    token_interface::spl_token_metadata_interface::state::TokenMetadata,
+};
+use common::{
+    anchor_err, config_address, config_params, govern_mock, init_config_ix, lock_voter_mock,
+    lst_metadata, neptune_err, program_data_address, set_spl_mint, svm, Env, Ticket, FEES_BPS,
+    MAX_FEES_BPS, TREASURY, UNSTAKE_DURATION, UTOKEN_DECIMALS,
+};
+use neptune::{
+    events::{
+        BufferConfigured, BufferRefillBegun, DeficitResolved, FeesBpsUpdated, LockPolicySet,
+        OwnershipAccepted, OwnershipTransferStarted, Staked, UnstakeWithdrawn, UnstakingBegun,
+        UnstakingMerged, VaultCreated, VaultListed, VaultPauseSet, VoteDelegateSet,
+    },
+    lock_voter::accounts::{Escrow, PartialUnstaking},
+    state::{
+        ConfigParams, FeePayout, LockPolicy, LstMetadata, Unstaking, Vault, VaultEntry, VaultList,
+        VaultStatus,
+    },
+    NeptuneError,
+};
+
+const LST_TOKEN_PROGRAMS: [Pubkey; 2] = [spl_token::ID, spl_token_2022::ID];
+
+#[test]
+fn test_create_vault() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let env = Env::with_vault(lst_token_program);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.owner, env.vault_owner);
+        assert_eq!(vault.base, env.base);
+        assert_eq!(vault.escrow, env.escrow);
+        assert_eq!(vault.lst_mint, env.lst_mint);
+        assert_eq!(vault.fees_bps, FEES_BPS);
+        assert_eq!(vault.lock_policy, LockPolicy::MaxLock);
+        assert_eq!(vault.total_lst_minted, 0);
+
+        let escrow = env.escrow_state();
+        assert_eq!(escrow.owner, env.vault);
+        assert!(escrow.is_max_lock);
+
+        let lst_mint = env.svm.account(&env.lst_mint).unwrap();
+        assert_eq!(lst_mint.owner, lst_token_program);
+        let lst_mint = StateWithExtensions::<Mint>::unpack(&lst_mint.data).unwrap();
+        assert_eq!(lst_mint.base.decimals, UTOKEN_DECIMALS);
+        assert_eq!(lst_mint.base.mint_authority, Some(env.vault).into());
+        assert_eq!(lst_mint.base.supply, 0);
+
+        if env.is_token_2022() {
+            let metadata_pointer = lst_mint.get_extension::<MetadataPointer>().unwrap();
+            assert_eq!(
+                Option::<Pubkey>::from(metadata_pointer.metadata_address),
+                Some(env.lst_mint)
+            );
+            let metadata = lst_mint
+                .get_variable_len_extension::<TokenMetadata>()
+                .unwrap();
+            assert_eq!(metadata.name, lst_metadata().name);
+            assert_eq!(metadata.symbol, lst_metadata().symbol);
+            assert_eq!(metadata.uri, lst_metadata().uri);
+        } else {
+            let metadata = env.lst_metaplex_metadata();
+            assert_eq!(metadata.update_authority, env.vault);
+            assert_eq!(metadata.mint, env.lst_mint);
+            assert_eq!(metadata.name, lst_metadata().name);
+            assert_eq!(metadata.symbol, lst_metadata().symbol);
+            assert_eq!(metadata.uri, lst_metadata().uri);
+        }
+    }
+}
+
+#[test]
+fn test_create_vault_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        // metadata out of bounds
+        let mut env = Env::new(lst_token_program);
+        let metadata = LstMetadata {
+            symbol: String::new(),
+            ..lst_metadata()
+        };
+        assert_eq!(
+            env.create_vault(None, metadata),
+            Err(neptune_err(NeptuneError::InvalidLstMetadata))
+        );
+
+        // base must sign
+        let ix = env.create_vault_ix(None, lst_metadata());
+        assert_eq!(
+            env.svm.process(ix, &[env.payer]),
+            Err(anchor_err(ErrorCode::AccountNotSigner))
+        );
+
+        // one vault per base
+        env.create_vault(None, lst_metadata()).unwrap();
+        assert!(env.create_vault(None, lst_metadata()).is_err());
+    }
+
+    // transfer hooks are token-2022 only
+    let mut env = Env::new(spl_token::ID);
+    assert_eq!(
+        env.create_vault(Some(Pubkey::new_unique()), lst_metadata()),
+        Err(neptune_err(NeptuneError::InvalidTokenProgram))
+    );
+
+    // spl lst mints need the metaplex accounts
+    let mut ix = env.create_vault_ix(None, lst_metadata());
+    // anchor reads the program id as a missing optional account
+    ix.accounts[6] = AccountMeta::new_readonly(neptune::ID, false);
+    ix.accounts[13] = AccountMeta::new_readonly(neptune::ID, false);
+    assert_eq!(
+        env.svm.process(ix, &[env.payer, env.base]),
+        Err(neptune_err(NeptuneError::MissingMetadataAccounts))
+    );
+}
+
+#[test]
+fn test_stake() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+
+        env.stake(&user, 400_000).unwrap();
+        // empty vault stakes 1:1
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 400_000);
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 600_000);
+        assert_eq!(env.token_balance(&env.utoken_escrow_ata), 400_000);
+        assert_eq!(env.escrow_state().amount, 400_000);
+
+        env.stake(&user, 600_000).unwrap();
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 1_000_000);
+        assert_eq!(vault.total_lst_minted, 1_000_000);
+        assert_eq!(env.lst_supply(), 1_000_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_000_000);
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 0);
+    }
+}
+
+#[test]
+fn test_stake_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+
+        assert_eq!(
+            env.stake(&user, 0),
+            Err(neptune_err(NeptuneError::AmtMustGreaterThanZero))
+        );
+        // more than the user holds, nothing minted
+        assert!(env.stake(&user, 1_001).is_err());
+        assert_eq!(env.vault_state().total_lst_minted, 0);
+        assert_eq!(env.lst_supply(), 0);
+
+        // lst program must own the lst mint
+        let mut ix = env.stake_ix(&user, 1_000);
+        ix.accounts[13].pubkey = LST_TOKEN_PROGRAMS
+            .into_iter()
+            .find(|program| program != &lst_token_program)
+            .unwrap();
+        assert!(env.svm.process(ix, &[user]).is_err());
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 1_000);
+    }
+}
+
+#[test]
+fn test_stake_slippage() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+
+        // first stake seeds the rate
+        assert_eq!(
+            env.stake(&user, Vault::MIN_FIRST_STAKE_AMT - 1),
+            Err(neptune_err(NeptuneError::FirstStakeTooSmall))
+        );
+        env.stake(&user, 500_000).unwrap();
+        env.compound(50_000).unwrap();
+
+        // rate went up, 1:1 is no longer on offer
+        let mut ix = env.stake_ix(&user, 100_000);
+        ix.data = neptune::instruction::Stake {
+            amount: 100_000,
+            min_lst_out: 100_000,
+        }
+        .data();
+        assert_eq!(
+            env.svm.process(ix, &[user]),
+            Err(neptune_err(NeptuneError::SlippageExceeded))
+        );
+
+        let lst_amt = env.vault_state().get_lst_amt(100_000).unwrap();
+        let mut ix = env.stake_ix(&user, 100_000);
+        ix.data = neptune::instruction::Stake {
+            amount: 100_000,
+            min_lst_out: lst_amt,
+        }
+        .data();
+        env.svm.process(ix, &[user]).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 500_000 + lst_amt);
+
+        // the ticket is quoted at the current rate too
+        let utoken_amt = env.vault_state().get_utoken_amt(100_000).unwrap();
+        let ticket = env.new_ticket(&user);
+        let mut ix = env.begin_unstaking_ix(&user, &ticket, 100_000);
+        ix.data = neptune::instruction::BeginUnstaking {
+            amount: 100_000,
+            min_utoken_out: utoken_amt + 1,
+        }
+        .data();
+        assert_eq!(
+            env.svm.process(ix, &[user]),
+            Err(neptune_err(NeptuneError::SlippageExceeded))
+        );
+
+        let mut ix = env.begin_unstaking_ix(&user, &ticket, 100_000);
+        ix.data = neptune::instruction::BeginUnstaking {
+            amount: 100_000,
+            min_utoken_out: utoken_amt,
+        }
+        .data();
+        env.svm.process(ix, &[user]).unwrap();
+        let unstaking: Unstaking = env.svm.anchor_account(&ticket.unstaking);
+        assert_eq!(unstaking.utoken_amt, utoken_amt);
+    }
+}
+
+#[test]
+fn test_begin_unstaking() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 750_000);
+        assert_eq!(env.token_balance(&ticket.lst_escrow_ata), 250_000);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 250_000);
+
+        let unstaking: Unstaking = env.svm.anchor_account(&ticket.unstaking);
+        assert_eq!(unstaking.owner, user);
+        assert_eq!(unstaking.vault, env.vault);
+        assert_eq!(unstaking.partial_unstaking, ticket.partial_unstaking);
+        assert_eq!(unstaking.lst_amt, 250_000);
+        assert_eq!(unstaking.utoken_amt, 250_000);
+    }
+}
+
+#[test]
+fn test_begin_unstaking_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+
+        assert_eq!(
+            env.begin_unstaking(&user, 0).err(),
+            Some(neptune_err(NeptuneError::AmtMustGreaterThanZero))
+        );
+        // more lst than the user holds
+        assert!(env.begin_unstaking(&user, 1_001).is_err());
+
+        // tickets are opened in nonce order
+        let ticket = env.ticket(&user, 1);
+        let ix = env.begin_unstaking_ix(&user, &ticket, 500);
+        assert_eq!(
+            env.svm.process(ix, &[user]),
+            Err(anchor_err(ErrorCode::ConstraintSeeds))
+        );
+
+        // partial unstaking is the ticket's pda
+        let mut ticket = env.new_ticket(&user);
+        ticket.partial_unstaking = env.ticket(&user, 1).partial_unstaking;
+        let ix = env.begin_unstaking_ix(&user, &ticket, 500);
+        assert_eq!(
+            env.svm.process(ix, &[user]),
+            Err(anchor_err(ErrorCode::ConstraintSeeds))
+        );
+
+        // only the lst owner can unstake it
+        let other = env.new_user(0);
+        let ticket = env.new_ticket(&other);
+        let mut ix = env.begin_unstaking_ix(&other, &ticket, 500);
+        ix.accounts[8].pubkey = env.lst_ata(&user);
+        assert!(env.svm.process(ix, &[other]).is_err());
+
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_000);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 0);
+    }
+}
+
+#[test]
+fn test_concurrent_unstakings() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        let other = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+        env.stake(&other, 1_000).unwrap();
+
+        let tickets: Vec<Ticket> = [100, 200, 300]
+            .into_iter()
+            .map(|amount| env.begin_unstaking(&user, amount).unwrap())
+            .collect();
+        // each user numbers their own tickets
+        let other_ticket = env.begin_unstaking(&other, 50).unwrap();
+        assert_eq!(other_ticket.nonce, 0);
+
+        let user_state = env.user_state(&user).unwrap();
+        assert_eq!(user_state.owner, user);
+        assert_eq!(user_state.vault, env.vault);
+        assert_eq!(user_state.nonce, 3);
+        for (nonce, ticket) in tickets.iter().enumerate() {
+            assert_eq!(ticket.unstaking, env.ticket(&user, nonce as u64).unstaking);
+            let unstaking: Unstaking = env.svm.anchor_account(&ticket.unstaking);
+            assert_eq!(unstaking.partial_unstaking, ticket.partial_unstaking);
+        }
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 650);
+
+        // nonces are never reused
+        env.merge_unstaking(&user, &tickets[1]).unwrap();
+        let ticket = env.begin_unstaking(&user, 200).unwrap();
+        assert_eq!(ticket.nonce, 3);
+
+        env.warp_past_unstaking();
+        for ticket in [&tickets[0], &tickets[2], &ticket] {
+            env.withdraw_unstake(&user, ticket).unwrap();
+        }
+        env.withdraw_unstake(&other, &other_ticket).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 600);
+        assert_eq!(env.token_balance(&env.utoken_ata(&other)), 50);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 0);
+        assert_eq!(env.user_state(&user).unwrap().nonce, 4);
+    }
+}
+
+#[test]
+fn test_merge_unstaking() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+
+        env.merge_unstaking(&user, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_000_000);
+        assert_eq!(env.token_balance(&ticket.lst_escrow_ata), 0);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 0);
+        assert!(env.svm.account(&ticket.unstaking).is_none());
+        assert!(env.svm.account(&ticket.partial_unstaking).is_none());
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 1_000_000);
+        assert_eq!(vault.total_lst_minted, 1_000_000);
+        assert_eq!(env.lst_supply(), 1_000_000);
+    }
+}
+
+#[test]
+fn test_merge_unstaking_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 500).unwrap();
+
+        // someone else's ticket
+        let other = env.new_user(0);
+        assert_eq!(
+            env.merge_unstaking(&other, &ticket),
+            Err(anchor_err(ErrorCode::ConstraintRaw))
+        );
+
+        // ticket of another partial unstaking
+        let other_ticket = env.begin_unstaking(&user, 100).unwrap();
+        let mut ix = env.merge_unstaking_ix(&user, &ticket);
+        ix.accounts[4].pubkey = other_ticket.partial_unstaking;
+        assert_eq!(
+            env.svm.process(ix, &[user]),
+            Err(anchor_err(ErrorCode::ConstraintHasOne))
+        );
+
+        // merged tickets are gone
+        env.merge_unstaking(&user, &ticket).unwrap();
+        assert!(env.merge_unstaking(&user, &ticket).is_err());
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 100);
+    }
+}
+
+#[test]
+fn test_withdraw_unstake() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+
+        env.warp_past_unstaking();
+        env.withdraw_unstake(&user, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 250_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 750_000);
+        assert_eq!(env.token_balance(&env.utoken_escrow_ata), 750_000);
+        assert!(env.svm.account(&ticket.unstaking).is_none());
+        assert!(env.svm.account(&ticket.partial_unstaking).is_none());
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+
+        let escrow = env.escrow_state();
+        assert_eq!(escrow.amount, 750_000);
+        assert_eq!(escrow.partial_unstaking_amount, 0);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 750_000);
+        assert_eq!(vault.total_lst_minted, 750_000);
+        assert_eq!(env.lst_supply(), 750_000);
+    }
+}
+
+#[test]
+fn test_withdraw_unstake_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 500).unwrap();
+
+        // WAGMI lock still running
+        assert_eq!(
+            env.withdraw_unstake(&user, &ticket),
+            Err(ProgramError::Custom(
+                lock_voter_mock::ERR_UNSTAKING_NOT_EXPIRED
+            ))
+        );
+
+        env.warp_past_unstaking();
+        // someone else's ticket
+        let other = env.new_user(0);
+        assert_eq!(
+            env.withdraw_unstake(&other, &ticket),
+            Err(anchor_err(ErrorCode::ConstraintRaw))
+        );
+
+        // withdrawn tickets are gone
+        env.withdraw_unstake(&user, &ticket).unwrap();
+        assert!(env.withdraw_unstake(&user, &ticket).is_err());
+        assert!(env.merge_unstaking(&user, &ticket).is_err());
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 500);
+        assert_eq!(env.vault_state().total_lst_minted, 500);
+    }
+}
+
+#[test]
+fn test_withdraw_unstake_with_escrow_donation() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        let donor = env.new_user(10_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.stake(&donor, 10_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+
+        // anyone can send lst to the escrow, it must not lock the ticket
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 1_000)
+            .unwrap();
+        env.warp_past_unstaking();
+        env.withdraw_unstake(&user, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 250_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 751_000);
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 760_000);
+        assert_eq!(vault.total_lst_minted, 760_000);
+        assert_eq!(env.lst_supply(), 760_000);
+    }
+}
+
+#[test]
+fn test_unstaking_lst_token_program() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let other_program = LST_TOKEN_PROGRAMS
+            .into_iter()
+            .find(|program| program != &lst_token_program)
+            .unwrap();
+        let with_other_program = |mut ix: Instruction| {
+            for meta in ix.accounts.iter_mut() {
+                if meta.pubkey == lst_token_program {
+                    meta.pubkey = other_program;
+                }
+            }
+            ix
+        };
+
+        // lst accounts are tied to the lst mint's program on every ticket step
+        let ticket = env.new_ticket(&user);
+        let ix = with_other_program(env.begin_unstaking_ix(&user, &ticket, 500_000));
+        assert!(env.svm.process(ix, &[user]).is_err());
+        let ticket = env.begin_unstaking(&user, 500_000).unwrap();
+        let ix = with_other_program(env.merge_unstaking_ix(&user, &ticket));
+        assert!(env.svm.process(ix, &[user]).is_err());
+        env.warp_past_unstaking();
+        let ix = with_other_program(env.withdraw_unstake_ix(&user, &ticket));
+        assert!(env.svm.process(ix, &[user]).is_err());
+
+        env.withdraw_unstake(&user, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 500_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 500_000);
+    }
+}
+
+#[test]
+fn test_partial_merge_unstaking() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 400_000).unwrap();
+        let begun_at = env.svm.unix_timestamp();
+
+        // halfway through the cooldown
+        env.svm.warp_to(begun_at + UNSTAKE_DURATION as i64 / 2);
+        env.partial_merge_unstaking(&user, &ticket, 100_000)
+            .unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 700_000);
+        assert_eq!(env.token_balance(&ticket.lst_escrow_ata), 300_000);
+
+        let unstaking: Unstaking = env.svm.anchor_account(&ticket.unstaking);
+        assert_eq!(unstaking.lst_amt, 300_000);
+        assert_eq!(unstaking.utoken_amt, 300_000);
+        assert_eq!(unstaking.partial_unstaking, ticket.partial_unstaking);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 300_000);
+
+        // WAGMI cannot split a partial unstaking, the rest starts over
+        let partial_unstaking: PartialUnstaking = env.svm.anchor_account(&ticket.partial_unstaking);
+        assert_eq!(partial_unstaking.amount, 300_000);
+        assert_eq!(
+            partial_unstaking.expiration,
+            env.svm.unix_timestamp() + UNSTAKE_DURATION as i64
+        );
+        env.svm.warp_to(begun_at + UNSTAKE_DURATION as i64 + 1);
+        assert_eq!(
+            env.withdraw_unstake(&user, &ticket),
+            Err(ProgramError::Custom(
+                lock_voter_mock::ERR_UNSTAKING_NOT_EXPIRED
+            ))
+        );
+
+        // merging the rest closes the ticket
+        env.partial_merge_unstaking(&user, &ticket, 300_000)
+            .unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_000_000);
+        assert!(env.svm.account(&ticket.unstaking).is_none());
+        assert!(env.svm.account(&ticket.partial_unstaking).is_none());
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 0);
+        assert_eq!(env.escrow_state().amount, 1_000_000);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 1_000_000);
+        assert_eq!(vault.total_lst_minted, 1_000_000);
+        assert_eq!(env.lst_supply(), 1_000_000);
+    }
+}
+
+#[test]
+fn test_partial_merge_unstaking_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 500).unwrap();
+
+        assert_eq!(
+            env.partial_merge_unstaking(&user, &ticket, 0),
+            Err(neptune_err(NeptuneError::InvalidUnstakeAmt))
+        );
+        assert_eq!(
+            env.partial_merge_unstaking(&user, &ticket, 501),
+            Err(neptune_err(NeptuneError::InvalidUnstakeAmt))
+        );
+        let other = env.new_user(0);
+        assert_eq!(
+            env.partial_merge_unstaking(&other, &ticket, 100),
+            Err(anchor_err(ErrorCode::ConstraintRaw))
+        );
+
+        let unstaking: Unstaking = env.svm.anchor_account(&ticket.unstaking);
+        assert_eq!(unstaking.lst_amt, 500);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 500);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 500);
+    }
+}
+
+#[test]
+fn test_partial_withdraw_unstake() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 400_000).unwrap();
+
+        env.warp_past_unstaking();
+        env.partial_withdraw_unstake(&user, &ticket, 100_000)
+            .unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 100_000);
+        // the rest is staked again
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 900_000);
+        assert!(env.svm.account(&ticket.unstaking).is_none());
+        assert!(env.svm.account(&ticket.partial_unstaking).is_none());
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+
+        let escrow = env.escrow_state();
+        assert_eq!(escrow.amount, 900_000);
+        assert_eq!(escrow.partial_unstaking_amount, 0);
+        assert_eq!(env.token_balance(&env.utoken_escrow_ata), 900_000);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 900_000);
+        assert_eq!(vault.total_lst_minted, 900_000);
+        assert_eq!(env.lst_supply(), 900_000);
+    }
+}
+
+#[test]
+fn test_partial_withdraw_unstake_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000);
+        env.stake(&user, 1_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 500).unwrap();
+
+        assert_eq!(
+            env.partial_withdraw_unstake(&user, &ticket, 100),
+            Err(ProgramError::Custom(
+                lock_voter_mock::ERR_UNSTAKING_NOT_EXPIRED
+            ))
+        );
+        env.warp_past_unstaking();
+        assert_eq!(
+            env.partial_withdraw_unstake(&user, &ticket, 0),
+            Err(neptune_err(NeptuneError::InvalidUnstakeAmt))
+        );
+        assert_eq!(
+            env.partial_withdraw_unstake(&user, &ticket, 501),
+            Err(neptune_err(NeptuneError::InvalidUnstakeAmt))
+        );
+
+        // nothing can be staked back into a paused vault
+        let mut vault = env.vault_state();
+        vault.paused = true;
+        env.svm.set_anchor_account(env.vault, &vault);
+        assert_eq!(
+            env.partial_withdraw_unstake(&user, &ticket, 100),
+            Err(neptune_err(NeptuneError::VaultPaused))
+        );
+        // all of it still can be withdrawn
+        env.partial_withdraw_unstake(&user, &ticket, 500).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 500);
+    }
+}
+
+#[test]
+fn test_unstaking_cooldown_rewards() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let staker = env.new_user(1_000_000);
+        let unstaker = env.new_user(1_000_000);
+        env.stake(&staker, 1_000_000).unwrap();
+        env.stake(&unstaker, 1_000_000).unwrap();
+
+        // the ticket leaves the totals right away
+        let ticket = env.begin_unstaking(&unstaker, 1_000_000).unwrap();
+        let vault = env.vault_state();
+        assert_eq!(vault.total_lst_minted, 1_000_000);
+        assert_eq!(vault.total_utoken_staked, 1_000_000);
+        assert_eq!(vault.pending_unstake_lst_amt, 1_000_000);
+        assert_eq!(vault.pending_unstake_utoken_amt, 1_000_000);
+
+        // reward during cooldown, 1% fee, the rest to the staker alone
+        env.compound(100_000).unwrap();
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 1_100_000);
+        assert_eq!(vault.accrued_fees_lst, 910);
+        assert_eq!(vault.get_utoken_amt(1_000_000).unwrap(), 1_098_901);
+
+        // the escrow still holds the ticket, it is no reward
+        env.sync_vault().unwrap();
+        let synced_vault = env.vault_state();
+        assert_eq!(synced_vault.total_utoken_staked, 1_100_000);
+        assert_eq!(synced_vault.accrued_fees_lst, 910);
+        assert!(!synced_vault.in_deficit);
+
+        // the unstaker is paid what they were quoted
+        env.warp_past_unstaking();
+        env.withdraw_unstake(&unstaker, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&unstaker)), 1_000_000);
+        assert_eq!(env.escrow_state().amount, 1_100_000);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 1_100_000);
+        assert_eq!(vault.total_lst_minted, 1_000_910);
+        assert_eq!(vault.pending_unstake_lst_amt, 0);
+        assert_eq!(vault.pending_unstake_utoken_amt, 0);
+        assert_eq!(env.lst_supply(), 1_000_000);
+        env.sync_vault().unwrap();
+        assert_eq!(env.vault_state().total_utoken_staked, 1_100_000);
+    }
+}
+
+#[test]
+fn test_sync_vault_escrow_balance() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.begin_unstaking(&user, 200_000).unwrap();
+        let utoken_escrow_ata = env.utoken_escrow_ata;
+
+        // utoken outside the escrow's bookkeeping is no reward
+        env.set_token_balance(&utoken_escrow_ata, 1_100_000);
+        env.sync_vault().unwrap();
+        let vault = env.vault_state();
+        assert!(!vault.in_deficit);
+        assert_eq!(vault.total_utoken_staked, 800_000);
+
+        // the escrow's token account lost utoken, tickets are paid first
+        env.set_token_balance(&utoken_escrow_ata, 900_000);
+        env.sync_vault().unwrap();
+        let vault = env.vault_state();
+        assert!(vault.in_deficit);
+        assert_eq!(vault.total_utoken_staked, 700_000);
+
+        // no rewards on top of the loss until it is resolved
+        assert_eq!(
+            env.compound(10_000),
+            Err(neptune_err(NeptuneError::VaultInDeficit))
+        );
+
+        // resolving needs the escrow to cover tickets and totals again
+        env.set_token_balance(&utoken_escrow_ata, 850_000);
+        assert_eq!(
+            env.resolve_deficit(),
+            Err(neptune_err(NeptuneError::DeficitNotCovered))
+        );
+        env.set_token_balance(&utoken_escrow_ata, 900_000);
+        env.resolve_deficit().unwrap();
+        assert!(!env.vault_state().in_deficit);
+        assert_eq!(
+            env.resolve_deficit(),
+            Err(neptune_err(NeptuneError::VaultNotInDeficit))
+        );
+        env.compound(10_000).unwrap();
+    }
+}
+
+#[test]
+fn test_merge_unstaking_after_rewards() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let staker = env.new_user(1_000_000);
+        let unstaker = env.new_user(1_000_000);
+        env.stake(&staker, 1_000_000).unwrap();
+        env.stake(&unstaker, 1_000_000).unwrap();
+        let ticket = env.begin_unstaking(&unstaker, 1_000_000).unwrap();
+        env.compound(100_000).unwrap();
+
+        // cancelling restakes the ticket at the new rate,
+        // lst it can no longer cover is burnt
+        env.merge_unstaking(&unstaker, &ticket).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&unstaker)), 910_000);
+        assert_eq!(env.lst_supply(), 1_910_000);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 2_100_000);
+        assert_eq!(vault.total_lst_minted, 1_910_910);
+        assert_eq!(vault.pending_unstake_lst_amt, 0);
+        assert_eq!(vault.pending_unstake_utoken_amt, 0);
+        // neither side takes from the other
+        assert_eq!(vault.get_utoken_amt(1_000_000).unwrap(), 1_098_901);
+        assert_eq!(vault.get_utoken_amt(910_000).unwrap(), 1_000_000);
+    }
+}
+
+#[test]
+fn test_sweep_escrow_dust() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        let donor = env.new_user(10_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.stake(&donor, 10_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+
+        // only what is above the ticket, back to its owner
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 1_000)
+            .unwrap();
+        env.sweep_lst_escrow_dust(&user, &ticket).unwrap();
+        assert_eq!(env.token_balance(&ticket.lst_escrow_ata), 250_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 751_000);
+
+        // a merged ticket closes its escrow, dust goes back with it
+        let lst_escrow_account = env.svm.account(&ticket.lst_escrow_ata).unwrap().clone();
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 500)
+            .unwrap();
+        env.merge_unstaking(&user, &ticket).unwrap();
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_001_500);
+
+        // escrow of a ticket closed before that, nobody to return it to,
+        // it is burnt for the stakers
+        env.svm
+            .set_account(ticket.lst_escrow_ata, lst_escrow_account);
+        env.set_token_balance(&ticket.lst_escrow_ata, 0);
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 500)
+            .unwrap();
+        let ix = env.sweep_escrow_dust_ix(
+            &env.lst_mint,
+            &lst_token_program,
+            &ticket.lst_escrow_ata,
+            None,
+            Some(ticket.unstaking),
+        );
+        env.svm.process(ix, &[env.vault_owner]).unwrap();
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+        assert_eq!(env.token_balance(&env.lst_ata(&donor)), 8_000);
+        let vault = env.vault_state();
+        assert_eq!(vault.total_lst_minted, 1_009_500);
+        assert_eq!(vault.total_utoken_staked, 1_010_000);
+        assert_eq!(env.lst_supply(), 1_009_500);
+
+        // utoken donated to the buffer goes to the reward ata for compound,
+        // the buffer keeps what the vault counts in it
+        env.configure_buffer(1_000, 50).unwrap();
+        let staker = env.new_user(100_000);
+        env.stake_with_buffer(&staker, 100_000).unwrap();
+        let buffer_utoken_amt = env.vault_state().buffer_utoken_amt;
+        assert_eq!(buffer_utoken_amt, 10_000);
+        let mint_to_ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &env.utoken_mint,
+            &env.utoken_buffer_ata(),
+            &env.utoken_mint_authority,
+            &[],
+            3_000,
+        )
+        .unwrap();
+        env.svm
+            .process(mint_to_ix, &[env.utoken_mint_authority])
+            .unwrap();
+        // nothing to compound until it is swept
+        assert_eq!(
+            env.compound(0),
+            Err(neptune_err(NeptuneError::AmtMustGreaterThanZero))
+        );
+        let ix = env.sweep_escrow_dust_ix(
+            &env.utoken_mint,
+            &spl_token::ID,
+            &env.utoken_buffer_ata(),
+            Some(env.utoken_ata(&env.vault)),
+            None,
+        );
+        env.svm.process(ix, &[env.vault_owner]).unwrap();
+        assert_eq!(
+            env.token_balance(&env.utoken_buffer_ata()),
+            buffer_utoken_amt
+        );
+        env.compound(0).unwrap();
+        assert_eq!(env.vault_state().total_utoken_staked, 1_113_000);
+
+        // stray tokens of any other mint go where the owner says
+        let stray_mint = Pubkey::new_unique();
+        let stray_mint_authority = Pubkey::new_unique();
+        set_spl_mint(&mut env.svm, stray_mint, stray_mint_authority);
+        let stray_ata =
+            get_associated_token_address_with_program_id(&env.vault, &stray_mint, &spl_token::ID);
+        let owner_stray_ata = get_associated_token_address_with_program_id(
+            &env.vault_owner,
+            &stray_mint,
+            &spl_token::ID,
+        );
+        for owner in [env.vault, env.vault_owner] {
+            let create_ata_ix =
+                create_associated_token_account(&env.payer, &owner, &stray_mint, &spl_token::ID);
+            env.svm.process(create_ata_ix, &[env.payer]).unwrap();
+        }
+        let mint_to_ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &stray_mint,
+            &stray_ata,
+            &stray_mint_authority,
+            &[],
+            7_000,
+        )
+        .unwrap();
+        env.svm
+            .process(mint_to_ix, &[stray_mint_authority])
+            .unwrap();
+
+        let ix = env.sweep_escrow_dust_ix(
+            &stray_mint,
+            &spl_token::ID,
+            &stray_ata,
+            Some(owner_stray_ata),
+            None,
+        );
+        env.svm.process(ix, &[env.vault_owner]).unwrap();
+        assert_eq!(env.token_balance(&stray_ata), 0);
+        assert_eq!(env.token_balance(&owner_stray_ata), 7_000);
+    }
+}
+
+#[test]
+fn test_sweep_escrow_dust_fails() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        let donor = env.new_user(10_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.stake(&donor, 10_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 250_000).unwrap();
+
+        // the ticket itself is not dust
+        assert_eq!(
+            env.sweep_lst_escrow_dust(&user, &ticket),
+            Err(neptune_err(NeptuneError::NothingToSweep))
+        );
+
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 1_000)
+            .unwrap();
+        // owner only
+        let mut ix = env.sweep_escrow_dust_ix(
+            &env.lst_mint,
+            &lst_token_program,
+            &ticket.lst_escrow_ata,
+            Some(env.lst_ata(&user)),
+            Some(ticket.unstaking),
+        );
+        ix.accounts[0].pubkey = donor;
+        assert_eq!(
+            env.svm.process(ix, &[donor]),
+            Err(neptune_err(NeptuneError::Unauthorized))
+        );
+
+        // live ticket dust only goes back to its owner
+        let ix = env.sweep_escrow_dust_ix(
+            &env.lst_mint,
+            &lst_token_program,
+            &ticket.lst_escrow_ata,
+            Some(env.lst_ata(&donor)),
+            Some(ticket.unstaking),
+        );
+        assert_eq!(
+            env.svm.process(ix, &[env.vault_owner]),
+            Err(neptune_err(NeptuneError::InvalidSweepSource))
+        );
+
+        // lst sources need their ticket
+        let other_ticket = env.begin_unstaking(&user, 250_000).unwrap();
+        for unstaking in [None, Some(other_ticket.unstaking)] {
+            let ix = env.sweep_escrow_dust_ix(
+                &env.lst_mint,
+                &lst_token_program,
+                &ticket.lst_escrow_ata,
+                Some(env.lst_ata(&user)),
+                unstaking,
+            );
+            assert_eq!(
+                env.svm.process(ix, &[env.vault_owner]),
+                Err(neptune_err(NeptuneError::InvalidSweepSource))
+            );
+        }
+
+        // utoken only goes to the reward ata, counted buffer is not dust
+        env.configure_buffer(1_000, 50).unwrap();
+        let staker = env.new_user(100_000);
+        env.stake_with_buffer(&staker, 100_000).unwrap();
+        env.compound(1_000).unwrap();
+        for (source, destination) in [
+            (env.utoken_ata(&env.vault), env.utoken_ata(&user)),
+            (env.utoken_buffer_ata(), env.utoken_ata(&user)),
+        ] {
+            let ix = env.sweep_escrow_dust_ix(
+                &env.utoken_mint,
+                &spl_token::ID,
+                &source,
+                Some(destination),
+                None,
+            );
+            assert_eq!(
+                env.svm.process(ix, &[env.vault_owner]),
+                Err(neptune_err(NeptuneError::InvalidSweepSource))
+            );
+        }
+        let ix = env.sweep_escrow_dust_ix(
+            &env.utoken_mint,
+            &spl_token::ID,
+            &env.utoken_buffer_ata(),
+            Some(env.utoken_ata(&env.vault)),
+            None,
+        );
+        assert_eq!(
+            env.svm.process(ix, &[env.vault_owner]),
+            Err(neptune_err(NeptuneError::NothingToSweep))
+        );
+        assert_eq!(env.token_balance(&ticket.lst_escrow_ata), 251_000);
+    }
+}
+
+#[test]
+fn test_migrate_vault() {
+    let mut env = Env::new(spl_token::ID);
+    let owner = env.vault_owner;
+
+    // pre ownership-transfer layout, derived from a key it did not record
+    let legacy_base = Pubkey::new_unique();
+    let (vault, vault_bump) =
+        Pubkey::find_program_address(&[Vault::VAULT_SEED, legacy_base.as_ref()], &neptune::ID);
+    let (escrow, escrow_bump) = lock_voter_mock::escrow_address(&env.locker, &vault);
+    let mut data = Vault::DISCRIMINATOR.to_vec();
+    (
+        owner,
+        escrow,
+        env.utoken_mint,
+        1_000_000u64,
+        1_000_000u64,
+        FEES_BPS,
+        vault_bump,
+    )
+        .serialize(&mut data)
+        .unwrap();
+    env.svm.set_account(
+        vault,
+        svm::TestAccount {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: neptune::ID,
+            executable: false,
+        },
+    );
+    env.svm.set_anchor_account(
+        escrow,
+        &Escrow {
+            locker: env.locker,
+            owner: vault,
+            bump: escrow_bump,
+            tokens: env.utoken_ata(&escrow),
+            amount: 1_000_000,
+            escrow_started_at: 0,
+            escrow_ends_at: 0,
+            vote_delegate: vault,
+            is_max_lock: true,
+            partial_unstaking_amount: 0,
+            padding: 0,
+            buffers: [0; 9],
+        },
+    );
+    let migrate_vault_ix = |owner: Pubkey, base: Pubkey| Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::MigrateVault {
+            owner,
+            vault,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::MigrateVault { base }.data(),
+    };
+
+    let other = Pubkey::new_unique();
+    env.svm.airdrop(&other, 1_000_000_000);
+    assert_eq!(
+        env.svm
+            .process(migrate_vault_ix(other, legacy_base), &[other]),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    assert_eq!(
+        env.svm
+            .process(migrate_vault_ix(owner, Pubkey::new_unique()), &[owner]),
+        Err(neptune_err(NeptuneError::InvalidVaultBase))
+    );
+    env.svm
+        .process(migrate_vault_ix(owner, legacy_base), &[owner])
+        .unwrap();
+
+    let migrated: Vault = env.svm.anchor_account(&vault);
+    assert_eq!(migrated.base, legacy_base);
+    assert_eq!(migrated.bump, vault_bump);
+    assert_eq!(migrated.voter, owner);
+    assert_eq!(migrated.vote_delegate, vault);
+    assert_eq!(
+        (migrated.lst_mint, migrated.lst_mint_bump),
+        Pubkey::find_program_address(&[Vault::VAULT_LST_MINT, vault.as_ref()], &neptune::ID)
+    );
+    assert_eq!(migrated.total_utoken_staked, 1_000_000);
+    assert_eq!(
+        env.svm.account(&vault).unwrap().data.len(),
+        Vault::DISCRIMINATOR.len() + Vault::INIT_SPACE
+    );
+
+    // the migrated vault signs with its recorded seeds
+    let new_delegate = Pubkey::new_unique();
+    let set_vote_delegate_ix = Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::SetVoteDelegate {
+            owner,
+            vault,
+            escrow,
+            locked_voter: neptune::lock_voter::ID,
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::SetVoteDelegate { new_delegate }.data(),
+    };
+    env.svm.process(set_vote_delegate_ix, &[owner]).unwrap();
+    let escrow: Escrow = env.svm.anchor_account(&escrow);
+    assert_eq!(escrow.vote_delegate, new_delegate);
+}
+
+// every cpi the vault signs for, over the mock locker and token programs
+
+#[test]
+fn test_vault_signs_buffer_cpis() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let staker = env.new_user(1_000_000);
+        let user = env.new_user(1_000_000);
+        env.configure_buffer(1_000, 50).unwrap();
+        env.stake(&staker, 1_000_000).unwrap();
+        env.stake_with_buffer(&user, 1_000_000).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_buffer_ata()), 100_000);
+
+        // buffer pays out
+        env.instant_unstake(&user, 10_000).unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 9_950);
+        assert_eq!(env.token_balance(&env.utoken_buffer_ata()), 90_050);
+
+        // vault & buffer_refill both sign the partial unstaking
+        env.refill_buffer().unwrap();
+        let refill_amt = env.vault_state().buffer_refill_amt;
+        assert!(refill_amt > 0);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, refill_amt);
+
+        env.warp_past_unstaking();
+        env.withdraw_buffer_refill().unwrap();
+        let vault = env.vault_state();
+        assert_eq!(vault.buffer_refill_amt, 0);
+        assert_eq!(vault.buffer_utoken_amt, 90_050 + refill_amt);
+        assert_eq!(
+            env.token_balance(&env.utoken_buffer_ata()),
+            vault.buffer_utoken_amt
+        );
+    }
+}
+
+#[test]
+fn test_vault_signs_escrow_cpis() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+
+        // leaving max lock restarts the escrow at the locker's max duration
+        let now = env.svm.unix_timestamp();
+        env.set_lock_policy(LockPolicy::Duration(86_400)).unwrap();
+        assert!(!env.escrow_state().is_max_lock);
+        assert_eq!(
+            env.escrow_state().escrow_ends_at,
+            now + UNSTAKE_DURATION as i64
+        );
+
+        env.extend_lock_duration(86_400).unwrap();
+        assert_eq!(env.vault_state().lock_policy, LockPolicy::Duration(172_800));
+        assert_eq!(
+            env.escrow_state().escrow_ends_at,
+            now + UNSTAKE_DURATION as i64
+        );
+
+        // escrow drifted from the policy, anyone brings it back
+        let mut escrow = env.escrow_state();
+        escrow.is_max_lock = true;
+        env.svm.set_anchor_account(env.escrow, &escrow);
+        env.enforce_lock_policy().unwrap();
+        assert!(!env.escrow_state().is_max_lock);
+
+        let new_delegate = Pubkey::new_unique();
+        env.set_vote_delegate(&new_delegate).unwrap();
+        assert_eq!(env.escrow_state().vote_delegate, new_delegate);
+        assert_eq!(env.vault_state().vote_delegate, new_delegate);
+    }
+}
+
+#[test]
+fn test_enforce_lock_duration() {
+    let mut env = Env::with_vault(spl_token::ID);
+    let user = env.new_user(1_000_000);
+    env.stake(&user, 1_000_000).unwrap();
+
+    // outside the locker's stake duration bounds
+    assert_eq!(
+        env.set_lock_policy(LockPolicy::Duration(0)),
+        Err(neptune_err(NeptuneError::InvalidLockPolicy))
+    );
+    assert_eq!(
+        env.set_lock_policy(LockPolicy::Duration(UNSTAKE_DURATION as i64 + 1)),
+        Err(neptune_err(NeptuneError::InvalidLockPolicy))
+    );
+    // max lock has no duration to extend
+    assert_eq!(
+        env.extend_lock_duration(86_400),
+        Err(neptune_err(NeptuneError::InvalidLockPolicy))
+    );
+
+    // escrow already ends past the target
+    let escrow_ends_at = env.escrow_state().escrow_ends_at;
+    env.set_lock_policy(LockPolicy::Duration(86_400)).unwrap();
+    assert_eq!(env.escrow_state().escrow_ends_at, escrow_ends_at);
+    assert_eq!(
+        env.enforce_lock_policy(),
+        Err(neptune_err(NeptuneError::LockPolicyInEffect))
+    );
+    assert_eq!(
+        env.extend_lock_duration(UNSTAKE_DURATION as i64),
+        Err(neptune_err(NeptuneError::InvalidLockPolicy))
+    );
+
+    // lock ran down, anyone tops it back up to the target
+    env.warp_past_unstaking();
+    let now = env.svm.unix_timestamp();
+    env.enforce_lock_policy().unwrap();
+    assert_eq!(env.escrow_state().escrow_ends_at, now + 86_400);
+    assert_eq!(
+        env.enforce_lock_policy(),
+        Err(neptune_err(NeptuneError::LockPolicyInEffect))
+    );
+
+    env.svm.warp_to(now + 3_600);
+    env.enforce_lock_policy().unwrap();
+    assert_eq!(env.escrow_state().escrow_ends_at, now + 3_600 + 86_400);
+}
+
+#[test]
+fn test_max_lock_to_duration_after_lock_ran_down() {
+    let mut env = Env::with_vault(spl_token::ID);
+    let user = env.new_user(1_000_000);
+    env.stake(&user, 1_000_000).unwrap();
+
+    // the max lock escrow_ends_at is stale by the time the policy switches,
+    // the extension is measured from the end the toggle leaves behind
+    env.warp_past_unstaking();
+    let now = env.svm.unix_timestamp();
+    assert!(env.escrow_state().escrow_ends_at < now);
+    env.set_lock_policy(LockPolicy::Duration(86_400)).unwrap();
+    let escrow = env.escrow_state();
+    assert!(!escrow.is_max_lock);
+    assert_eq!(escrow.escrow_ends_at, now + UNSTAKE_DURATION as i64);
+    assert_eq!(
+        env.enforce_lock_policy(),
+        Err(neptune_err(NeptuneError::LockPolicyInEffect))
+    );
+
+    // same again after a round trip through max lock
+    env.set_lock_policy(LockPolicy::MaxLock).unwrap();
+    assert!(env.escrow_state().is_max_lock);
+    env.warp_past_unstaking();
+    let now = env.svm.unix_timestamp();
+    env.set_lock_policy(LockPolicy::Duration(UNSTAKE_DURATION as i64))
+        .unwrap();
+    assert_eq!(
+        env.escrow_state().escrow_ends_at,
+        now + UNSTAKE_DURATION as i64
+    );
+}
+
+#[test]
+fn test_vault_signs_ticket_cpis() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        let donor = env.new_user(10_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.stake(&donor, 10_000).unwrap();
+        let ticket = env.begin_unstaking(&user, 400_000).unwrap();
+
+        // vault returns the lst & reopens the rest under the ticket's partial pda
+        env.partial_merge_unstaking(&user, &ticket, 100_000)
+            .unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 700_000);
+        assert_eq!(env.escrow_state().partial_unstaking_amount, 300_000);
+
+        // vault withdraws, relocks, returns, refunds & burns
+        env.transfer_lst(&donor, &ticket.lst_escrow_ata, 1_000)
+            .unwrap();
+        env.warp_past_unstaking();
+        env.partial_withdraw_unstake(&user, &ticket, 100_000)
+            .unwrap();
+        assert_eq!(env.token_balance(&env.utoken_ata(&user)), 100_000);
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 901_000);
+        assert!(env.svm.account(&ticket.lst_escrow_ata).is_none());
+        assert_eq!(env.escrow_state().amount, 910_000);
+
+        let vault = env.vault_state();
+        assert_eq!(vault.total_utoken_staked, 910_000);
+        assert_eq!(vault.total_lst_minted, 910_000);
+        assert_eq!(env.lst_supply(), 910_000);
+    }
+}
+
+#[test]
+fn test_vault_signs_vote_cpis() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+
+        // owner's voter votes with the whole escrow
+        let voter = Pubkey::new_unique();
+        env.svm.airdrop(&voter, 1_000_000_000);
+        env.set_voter(&voter).unwrap();
+        let proposal = env.new_proposal(3);
+        env.cast_vote(&voter, &proposal, 2).unwrap();
+        let vote = env.vote_state(&proposal);
+        assert_eq!(vote.voter, env.vault);
+        assert_eq!((vote.side, vote.voting_power), (2, 1_000_000));
+        assert_eq!(
+            env.proposal_state(&proposal).option_votes,
+            [0, 0, 1_000_000]
+        );
+
+        // it can change sides while voting
+        env.cast_vote(&voter, &proposal, 1).unwrap();
+        assert_eq!(
+            env.proposal_state(&proposal).option_votes,
+            [0, 1_000_000, 0]
+        );
+        assert_eq!(
+            env.cast_vote(&user, &proposal, 1),
+            Err(neptune_err(NeptuneError::Unauthorized))
+        );
+        assert_eq!(
+            env.cast_vote(&voter, &proposal, 3),
+            Err(ProgramError::Custom(govern_mock::ERR_INVALID_VOTE_SIDE))
+        );
+
+        // lst holders vote, anyone cranks the plurality
+        let proposal = env.new_proposal(3);
+        env.create_vault_proposal(&proposal).unwrap();
+        env.cast_lst_vote(&user, &proposal, 1, 600_000).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 400_000);
+        env.cast_vault_vote(&proposal).unwrap();
+        let vote = env.vote_state(&proposal);
+        assert_eq!((vote.side, vote.voting_power), (1, 1_000_000));
+
+        // vault returns the lst & closes the vote escrow
+        let (_, lst_vote_escrow_ata) = env.lst_vote_address(&user, &proposal);
+        env.withdraw_lst_vote(&user, &proposal).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&user)), 1_000_000);
+        assert!(env.svm.account(&lst_vote_escrow_ata).is_none());
+        assert_eq!(env.vault_proposal_state(&proposal).total_weight, 0);
+        assert_eq!(
+            env.cast_vault_vote(&proposal),
+            Err(neptune_err(NeptuneError::NoLstVotes))
+        );
+
+        // govern rejects votes once voting ended
+        env.cast_lst_vote(&user, &proposal, 0, 1_000).unwrap();
+        env.svm
+            .warp_to(env.proposal_state(&proposal).voting_ends_at);
+        assert_eq!(
+            env.cast_vault_vote(&proposal),
+            Err(ProgramError::Custom(govern_mock::ERR_PROPOSAL_NOT_ACTIVE))
+        );
+    }
+}
+
+#[test]
+fn test_cast_vote_on_vault_proposal() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let voter = Pubkey::new_unique();
+        env.svm.airdrop(&voter, 1_000_000_000);
+        env.set_voter(&voter).unwrap();
+
+        // once mirrored, only lst holders decide the vault's vote
+        let proposal = env.new_proposal(3);
+        env.create_vault_proposal(&proposal).unwrap();
+        assert_eq!(
+            env.cast_vote(&voter, &proposal, 2),
+            Err(neptune_err(NeptuneError::VaultProposalExists))
+        );
+        env.cast_lst_vote(&user, &proposal, 1, 1_000).unwrap();
+
+        // a tied tally casts nothing until it is broken
+        let other = env.new_user(1_000);
+        env.stake(&other, 1_000).unwrap();
+        env.cast_lst_vote(&other, &proposal, 2, 1_000).unwrap();
+        assert_eq!(
+            env.cast_vault_vote(&proposal),
+            Err(neptune_err(NeptuneError::LstVoteTied))
+        );
+        env.withdraw_lst_vote(&other, &proposal).unwrap();
+
+        env.cast_vault_vote(&proposal).unwrap();
+        assert_eq!(env.vote_state(&proposal).side, 1);
+        assert_eq!(
+            env.cast_vote(&voter, &proposal, 2),
+            Err(neptune_err(NeptuneError::VaultProposalExists))
+        );
+        assert_eq!(env.vote_state(&proposal).side, 1);
+    }
+}
+
+#[test]
+fn test_cast_vote_while_delegated() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let voter = Pubkey::new_unique();
+        env.svm.airdrop(&voter, 1_000_000_000);
+        env.set_voter(&voter).unwrap();
+        let proposal = env.new_proposal(3);
+        let vault_proposal = env.new_proposal(3);
+        env.create_vault_proposal(&vault_proposal).unwrap();
+        env.cast_lst_vote(&user, &vault_proposal, 1, 1_000).unwrap();
+
+        // the delegate votes for the escrow, the vault cannot
+        env.set_vote_delegate(&Pubkey::new_unique()).unwrap();
+        assert_eq!(
+            env.cast_vote(&voter, &proposal, 2),
+            Err(neptune_err(NeptuneError::VoteDelegated))
+        );
+        assert_eq!(
+            env.cast_vault_vote(&vault_proposal),
+            Err(neptune_err(NeptuneError::VoteDelegated))
+        );
+
+        // delegating back to the vault undoes it
+        let vault = env.vault;
+        env.set_vote_delegate(&vault).unwrap();
+        env.cast_vote(&voter, &proposal, 2).unwrap();
+        env.cast_vault_vote(&vault_proposal).unwrap();
+        assert_eq!(env.vote_state(&proposal).side, 2);
+        assert_eq!(env.vote_state(&vault_proposal).side, 1);
+    }
+}
+
+#[test]
+fn test_vault_signs_lst_mint_cpis() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        env.compound(100_000).unwrap();
+
+        let fee_lst_amt = env.vault_state().accrued_fees_lst;
+        assert!(fee_lst_amt > 0);
+        env.claim_owner_fees(FeePayout::Lst).unwrap();
+        assert_eq!(
+            env.token_balance(&env.lst_ata(&env.vault_owner)),
+            fee_lst_amt
+        );
+        assert_eq!(env.vault_state().accrued_fees_lst, 0);
+
+        let metadata = LstMetadata {
+            name: "Neptune JUP v2".to_string(),
+            ..lst_metadata()
+        };
+        env.update_lst_metadata(metadata.clone()).unwrap();
+        if env.is_token_2022() {
+            let lst_mint = env.svm.account(&env.lst_mint).unwrap();
+            let lst_mint = StateWithExtensions::<Mint>::unpack(&lst_mint.data).unwrap();
+            let token_metadata = lst_mint
+                .get_variable_len_extension::<TokenMetadata>()
+                .unwrap();
+            assert_eq!(token_metadata.name, metadata.name);
+        } else {
+            assert_eq!(env.lst_metaplex_metadata().name, metadata.name);
+        }
+    }
+}
+
+#[test]
+fn test_vault_list() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+
+        let vault_list = env.vault_list_state();
+        assert_eq!(vault_list.locker, env.locker);
+        assert_eq!(
+            vault_list.vaults,
+            vec![VaultEntry {
+                vault: env.vault,
+                lst_mint: env.lst_mint,
+                name: lst_metadata().name,
+                fees_bps: FEES_BPS,
+                status: VaultStatus::Active,
+            }]
+        );
+
+        // later vaults over the same locker append
+        let other_vaults: Vec<_> = (0..9)
+            .map(|i| {
+                env.create_other_vault(LstMetadata {
+                    name: format!("Neptune JUP {i}"),
+                    ..lst_metadata()
+                })
+            })
+            .collect();
+        let vault_list = env.vault_list_state();
+        assert_eq!(vault_list.vaults.len(), 10);
+        assert_eq!(vault_list.vaults[9].vault, other_vaults[8]);
+        assert_eq!(vault_list.vaults[9].name, "Neptune JUP 8");
+        assert_eq!(
+            env.svm
+                .account(&env.vault_list_address())
+                .unwrap()
+                .data
+                .len(),
+            VaultList::space(10)
+        );
+
+        // owner instructions keep the entry in sync
+        env.update_fees_bps(500).unwrap();
+        env.set_paused(true).unwrap();
+        let metadata = LstMetadata {
+            name: "Neptune JUP v2".to_string(),
+            ..lst_metadata()
+        };
+        env.update_lst_metadata(metadata).unwrap();
+        let entry = &env.vault_list_state().vaults[0];
+        assert_eq!(entry.fees_bps, 500);
+        assert_eq!(entry.status, VaultStatus::Paused);
+        assert_eq!(entry.name, "Neptune JUP v2");
+        env.set_paused(false).unwrap();
+        assert_eq!(env.vault_list_state().vaults[0].status, VaultStatus::Active);
+
+        // paged, at most MAX_PAGE_LEN per page
+        let vaults = env.vault_list_state().vaults;
+        let page = env.get_vaults(0, u8::MAX);
+        assert_eq!(page.total, 10);
+        assert_eq!(page.vaults, vaults[..VaultList::MAX_PAGE_LEN as usize]);
+        let page = env.get_vaults(8, 5);
+        assert_eq!(page.vaults, vaults[8..]);
+        assert!(env.get_vaults(10, 5).vaults.is_empty());
+    }
+}
+
+#[test]
+fn test_register_vault() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        env.create_other_vault(lst_metadata());
+
+        // vault from before vault lists
+        let mut vault_list = env.vault_list_state();
+        vault_list.vaults.retain(|entry| entry.vault != env.vault);
+        let vault_list_address = env.vault_list_address();
+        env.svm.set_anchor_account(vault_list_address, &vault_list);
+        // owner instructions skip a vault missing from the list
+        env.update_fees_bps(500).unwrap();
+        env.set_paused(true).unwrap();
+        assert_eq!(env.vault_state().fees_bps, 500);
+        assert_eq!(env.vault_list_state().vaults, vault_list.vaults);
+
+        env.register_vault("Neptune JUP").unwrap();
+        let vault_list = env.vault_list_state();
+        assert_eq!(vault_list.vaults.len(), 2);
+        assert_eq!(vault_list.vaults[1].vault, env.vault);
+        assert_eq!(vault_list.vaults[1].lst_mint, env.lst_mint);
+        assert_eq!(vault_list.vaults[1].name, "Neptune JUP");
+        assert_eq!(vault_list.vaults[1].fees_bps, 500);
+        assert_eq!(vault_list.vaults[1].status, VaultStatus::Paused);
+        env.update_fees_bps(600).unwrap();
+        assert_eq!(env.vault_list_state().vaults[1].fees_bps, 600);
+    }
+
+    // a locker's first vault may be registered, opening its list
+    let mut env = Env::with_vault(spl_token::ID);
+    let vault_list_address = env.vault_list_address();
+    env.svm.set_account(vault_list_address, Default::default());
+    env.register_vault("Neptune JUP").unwrap();
+    let vault_list = env.vault_list_state();
+    assert_eq!(vault_list.locker, env.locker);
+    assert_eq!(vault_list.vaults.len(), 1);
+}
+
+#[test]
+fn test_vault_list_full() {
+    let mut env = Env::with_vault(spl_token::ID);
+    let mut vault_list = env.vault_list_state();
+    vault_list.vaults.clear();
+    while vault_list.vaults.len() < VaultList::MAX_VAULTS {
+        vault_list.vaults.push(VaultEntry {
+            vault: Pubkey::new_unique(),
+            ..Default::default()
+        });
+    }
+    let vault_list_address = env.vault_list_address();
+    env.svm.set_anchor_account(vault_list_address, &vault_list);
+
+    // vaults past a full list are created unlisted
+    let other_vault = env.create_other_vault(lst_metadata());
+    assert!(env.svm.account(&other_vault).is_some());
+    assert_eq!(env.vault_list_state().vaults, vault_list.vaults);
+    assert_eq!(
+        env.register_vault("Neptune JUP"),
+        Err(neptune_err(NeptuneError::VaultListFull))
+    );
+
+    // owner instructions go through with or without the list
+    env.update_fees_bps(500).unwrap();
+    env.set_paused(true).unwrap();
+    let ix = Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::SetPaused {
+            owner: env.vault_owner,
+            vault: env.vault,
+            vault_list: None,
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::SetPaused { paused: false }.data(),
+    };
+    env.svm.process(ix, &[env.vault_owner]).unwrap();
+    let vault = env.vault_state();
+    assert_eq!(vault.fees_bps, 500);
+    assert!(!vault.paused);
+    assert_eq!(env.vault_list_state().vaults, vault_list.vaults);
+}
+
+#[test]
+fn test_register_vault_fails() {
+    let mut env = Env::with_vault(spl_token::ID);
+
+    // listed once
+    assert_eq!(
+        env.register_vault("Neptune JUP"),
+        Err(neptune_err(NeptuneError::VaultAlreadyListed))
+    );
+
+    let mut vault_list = env.vault_list_state();
+    vault_list.vaults.clear();
+    let vault_list_address = env.vault_list_address();
+    env.svm.set_anchor_account(vault_list_address, &vault_list);
+
+    assert_eq!(
+        env.register_vault(&"n".repeat(33)),
+        Err(neptune_err(NeptuneError::InvalidLstMetadata))
+    );
+
+    // owner only
+    env.vault_owner = env.payer;
+    assert_eq!(
+        env.register_vault("Neptune JUP"),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_init_config() {
+    let env = Env::new(spl_token::ID);
+    let config = env.config_state();
+    assert_eq!(config.admin, env.admin);
+    assert_eq!(config.pending_admin, Pubkey::default());
+    assert!(config.permissionless);
+    assert_eq!(config.max_fees_bps, MAX_FEES_BPS);
+    assert_eq!(config.protocol_fee_bps, 0);
+    assert!(config.creators.is_empty());
+}
+
+#[test]
+fn test_init_config_fails() {
+    let mut env = Env::new(spl_token::ID);
+
+    // once
+    let ix = init_config_ix(&env.admin, &env.admin, config_params());
+    assert!(env.svm.process(ix, &[env.admin]).is_err());
+
+    env.svm.set_account(config_address(), Default::default());
+
+    // upgrade authority only
+    let ix = init_config_ix(&env.payer, &env.payer, config_params());
+    assert_eq!(
+        env.svm.process(ix, &[env.payer]),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+
+    // program data of the neptune program only
+    let mut ix = init_config_ix(&env.admin, &env.admin, config_params());
+    let program_data = env.svm.account(&program_data_address()).unwrap().clone();
+    let other_program_data = Pubkey::new_unique();
+    env.svm.set_account(other_program_data, program_data);
+    ix.accounts[3].pubkey = other_program_data;
+    assert_eq!(
+        env.svm.process(ix, &[env.admin]),
+        Err(anchor_err(ErrorCode::ConstraintRaw))
+    );
+
+    // limits checked
+    let params = ConfigParams {
+        max_fees_bps: 10_000,
+        ..config_params()
+    };
+    let ix = init_config_ix(&env.admin, &env.admin, params);
+    assert_eq!(
+        env.svm.process(ix, &[env.admin]),
+        Err(neptune_err(NeptuneError::InvalidBPS))
+    );
+}
+
+#[test]
+fn test_create_vault_allow_list() {
+    let mut env = Env::new(spl_token::ID);
+    env.update_config(ConfigParams {
+        permissionless: false,
+        ..config_params()
+    })
+    .unwrap();
+
+    assert_eq!(
+        env.create_vault(None, lst_metadata()),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+
+    let payer = env.payer;
+    env.set_creator(&payer, true).unwrap();
+    assert_eq!(env.config_state().creators, vec![payer]);
+    env.create_vault(None, lst_metadata()).unwrap();
+
+    env.set_creator(&payer, false).unwrap();
+    assert!(env.config_state().creators.is_empty());
+}
+
+#[test]
+fn test_config_max_fees_bps() {
+    let mut env = Env::new(spl_token::ID);
+    env.update_config(ConfigParams {
+        max_fees_bps: FEES_BPS - 1,
+        ..config_params()
+    })
+    .unwrap();
+    assert_eq!(
+        env.create_vault(None, lst_metadata()),
+        Err(neptune_err(NeptuneError::FeesAboveProtocolMax))
+    );
+
+    env.update_config(config_params()).unwrap();
+    env.create_vault(None, lst_metadata()).unwrap();
+    env.update_fees_bps(MAX_FEES_BPS).unwrap();
+    assert_eq!(
+        env.update_fees_bps(MAX_FEES_BPS + 1),
+        Err(neptune_err(NeptuneError::FeesAboveProtocolMax))
+    );
+
+    // lowering the max keeps existing fees
+    env.update_config(ConfigParams {
+        max_fees_bps: FEES_BPS,
+        ..config_params()
+    })
+    .unwrap();
+    assert_eq!(env.vault_state().fees_bps, MAX_FEES_BPS);
+    env.update_fees_bps(FEES_BPS).unwrap();
+}
+
+#[test]
+fn test_config_admin() {
+    let mut env = Env::new(spl_token::ID);
+
+    // admin only
+    let admin = env.admin;
+    env.admin = env.payer;
+    assert_eq!(
+        env.update_config(config_params()),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    assert_eq!(
+        env.set_creator(&Pubkey::new_unique(), true),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    env.admin = admin;
+
+    let new_admin = Pubkey::new_unique();
+    let transfer_ix = Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::UpdateConfig {
+            admin,
+            config: config_address(),
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::TransferConfigAdmin { new_admin }.data(),
+    };
+    env.svm.process(transfer_ix, &[admin]).unwrap();
+    assert_eq!(env.config_state().pending_admin, new_admin);
+
+    let accept_ix = |pending_admin| Instruction {
+        program_id: neptune::ID,
+        accounts: neptune::accounts::AcceptConfigAdmin {
+            pending_admin,
+            config: config_address(),
+        }
+        .to_account_metas(None),
+        data: neptune::instruction::AcceptConfigAdmin {}.data(),
+    };
+    assert_eq!(
+        env.svm.process(accept_ix(admin), &[admin]),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    env.svm.process(accept_ix(new_admin), &[new_admin]).unwrap();
+    let config = env.config_state();
+    assert_eq!(config.admin, new_admin);
+    assert_eq!(config.pending_admin, Pubkey::default());
+
+    // old admin is out
+    assert_eq!(
+        env.update_config(config_params()),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    env.admin = new_admin;
+    env.update_config(config_params()).unwrap();
+}
+
+#[test]
+fn test_protocol_fee_split() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::with_vault(lst_token_program);
+        env.update_config(ConfigParams {
+            protocol_fee_bps: 2_500,
+            ..config_params()
+        })
+        .unwrap();
+        let staker = env.new_user(1_000_000);
+        let user = env.new_user(1_000_000);
+        env.configure_buffer(1_000, 50).unwrap();
+        env.stake(&staker, 1_000_000).unwrap();
+        env.stake_with_buffer(&user, 1_000_000).unwrap();
+
+        // a quarter of the 1% fee goes to the protocol
+        env.compound(100_000).unwrap();
+        let vault = env.vault_state();
+        let fee_lst_amt = vault.accrued_fees_lst + vault.accrued_protocol_fees_lst;
+        assert_eq!(fee_lst_amt, 952);
+        assert_eq!(vault.accrued_protocol_fees_lst, 238);
+        assert_eq!(vault.accrued_fees_lst, 714);
+
+        // protocol takes utoken out of the buffer at the current rate
+        let lst_supply = env.lst_supply();
+        let utoken_amt = vault.clone().redeem_fees(238).unwrap();
+        assert!(utoken_amt > 238);
+        env.claim_protocol_fees(FeePayout::Utoken).unwrap();
+        let treasury_ata = env.utoken_ata(&TREASURY);
+        assert_eq!(env.token_balance(&treasury_ata), utoken_amt);
+        let vault = env.vault_state();
+        assert_eq!(vault.accrued_protocol_fees_lst, 0);
+        assert_eq!(vault.buffer_utoken_amt, 100_000 - utoken_amt);
+        assert_eq!(
+            env.token_balance(&env.utoken_buffer_ata()),
+            vault.buffer_utoken_amt
+        );
+        assert_eq!(env.lst_supply(), lst_supply);
+
+        // owner is minted lst, the rate is unchanged by either claim
+        let exchange_rate = vault.get_exchange_rate().unwrap();
+        env.claim_owner_fees(FeePayout::Lst).unwrap();
+        assert_eq!(env.token_balance(&env.lst_ata(&env.vault_owner)), 714);
+        let vault = env.vault_state();
+        assert_eq!(vault.accrued_fees_lst, 0);
+        assert_eq!(vault.get_exchange_rate().unwrap(), exchange_rate);
+
+        // nothing left to claim
+        assert_eq!(
+            env.claim_protocol_fees(FeePayout::Lst),
+            Err(neptune_err(NeptuneError::NoFeesToClaim))
+        );
+    }
+}
+
+#[test]
+fn test_claim_fees_fails() {
+    let mut env = Env::with_vault(spl_token::ID);
+    env.update_config(ConfigParams {
+        protocol_fee_bps: 5_000,
+        ..config_params()
+    })
+    .unwrap();
+    env.configure_buffer(1_000, 50).unwrap();
+    let user = env.new_user(1_000_000);
+    env.stake(&user, 1_000_000).unwrap();
+    env.compound(100_000).unwrap();
+
+    // owner fees to the owner only
+    let payer = env.payer;
+    let owner_lst_ata = env.fee_ata(&env.vault_owner.clone(), FeePayout::Lst);
+    let ix = env.claim_fees_ix(&payer, FeePayout::Lst, &owner_lst_ata, false);
+    assert_eq!(
+        env.svm.process(ix, &[payer]),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+
+    // protocol fees by the admin only, to the treasury only
+    let treasury_lst_ata = env.fee_ata(&TREASURY, FeePayout::Lst);
+    let vault_owner = env.vault_owner;
+    let ix = env.claim_fees_ix(&vault_owner, FeePayout::Lst, &treasury_lst_ata, true);
+    assert_eq!(
+        env.svm.process(ix, &[vault_owner]),
+        Err(neptune_err(NeptuneError::Unauthorized))
+    );
+    let admin = env.admin;
+    let ix = env.claim_fees_ix(&admin, FeePayout::Lst, &owner_lst_ata, true);
+    assert_eq!(
+        env.svm.process(ix, &[admin]),
+        Err(neptune_err(NeptuneError::InvalidFeeDestination))
+    );
+
+    // destination mint follows the payout
+    let ix = env.claim_fees_ix(&admin, FeePayout::Utoken, &treasury_lst_ata, true);
+    assert_eq!(
+        env.svm.process(ix, &[admin]),
+        Err(neptune_err(NeptuneError::InvalidFeeDestination))
+    );
+    let owner_utoken_ata = env.fee_ata(&vault_owner, FeePayout::Utoken);
+    let ix = env.claim_fees_ix(&vault_owner, FeePayout::Lst, &owner_utoken_ata, false);
+    assert_eq!(
+        env.svm.process(ix, &[vault_owner]),
+        Err(neptune_err(NeptuneError::InvalidFeeDestination))
+    );
+
+    // utoken needs the buffer, and enough in it
+    let mut ix = env.claim_fees_ix(&vault_owner, FeePayout::Utoken, &owner_utoken_ata, false);
+    ix.accounts[5].pubkey = neptune::ID;
+    ix.accounts[5].is_writable = false;
+    assert_eq!(
+        env.svm.process(ix, &[vault_owner]),
+        Err(neptune_err(NeptuneError::InsufficientBuffer))
+    );
+    assert_eq!(
+        env.claim_owner_fees(FeePayout::Utoken),
+        Err(neptune_err(NeptuneError::InsufficientBuffer))
+    );
+
+    env.claim_owner_fees(FeePayout::Lst).unwrap();
+    env.claim_protocol_fees(FeePayout::Lst).unwrap();
+    assert_eq!(env.token_balance(&owner_lst_ata), 455);
+    assert_eq!(env.token_balance(&treasury_lst_ata), 455);
+}
+
+// events as indexers decode them from the tx logs
+
+#[test]
+fn test_vault_flow_events() {
+    for lst_token_program in LST_TOKEN_PROGRAMS {
+        let mut env = Env::new(lst_token_program);
+        env.create_vault(None, lst_metadata()).unwrap();
+        let created: VaultCreated = env.svm.event();
+        assert_eq!(created.vault, env.vault);
+        assert_eq!(created.owner, env.vault_owner);
+        assert_eq!(created.escrow, env.escrow);
+        assert_eq!(created.lst_mint, env.lst_mint);
+        assert_eq!(created.fees_bps, FEES_BPS);
+        let listed: VaultListed = env.svm.event();
+        assert_eq!(listed.vault, env.vault);
+        assert_eq!(listed.locker, env.locker);
+        assert_eq!(listed.vault_list, env.vault_list_address());
+        env.create_utoken_escrow_ata();
+
+        let user = env.new_user(1_000_000);
+        env.stake(&user, 1_000_000).unwrap();
+        let staked: Staked = env.svm.event();
+        assert_eq!(staked.vault, env.vault);
+        assert_eq!(staked.user, user);
+        assert_eq!((staked.utoken_amt, staked.lst_amt), (1_000_000, 1_000_000));
+        assert_eq!(staked.total_lst_minted, 1_000_000);
+        assert_eq!(staked.total_utoken_staked, 1_000_000);
+        let exchange_rate = env.vault_state().get_exchange_rate().unwrap();
+        assert_eq!(staked.exchange_rate, exchange_rate);
+
+        let ticket = env.begin_unstaking(&user, 400_000).unwrap();
+        let begun: UnstakingBegun = env.svm.event();
+        assert_eq!(begun.vault, env.vault);
+        assert_eq!(begun.user, user);
+        assert_eq!(begun.unstaking, ticket.unstaking);
+        assert_eq!(begun.nonce, ticket.nonce);
+        assert_eq!((begun.utoken_amt, begun.lst_amt), (400_000, 400_000));
+        assert_eq!(begun.total_lst_minted, 600_000);
+        assert_eq!(begun.total_utoken_staked, 600_000);
+        assert_eq!(begun.exchange_rate, exchange_rate);
+
+        env.partial_merge_unstaking(&user, &ticket, 100_000)
+            .unwrap();
+        let merged: UnstakingMerged = env.svm.event();
+        assert_eq!(merged.unstaking, ticket.unstaking);
+        assert_eq!((merged.utoken_amt, merged.lst_amt), (100_000, 100_000));
+        assert_eq!(merged.returned_lst_amt, 100_000);
+        assert_eq!(merged.remaining_lst_amt, 300_000);
+        assert_eq!(merged.total_lst_minted, 700_000);
+        assert_eq!(merged.total_utoken_staked, 700_000);
+        assert_eq!(merged.exchange_rate, exchange_rate);
+
+        env.warp_past_unstaking();
+        env.withdraw_unstake(&user, &ticket).unwrap();
+        let withdrawn: UnstakeWithdrawn = env.svm.event();
+        assert_eq!(withdrawn.vault, env.vault);
+        assert_eq!(withdrawn.user, user);
+        assert_eq!(withdrawn.unstaking, ticket.unstaking);
+        assert_eq!(
+            (withdrawn.utoken_amt, withdrawn.lst_amt),
+            (300_000, 300_000)
+        );
+        assert_eq!(withdrawn.restaked_lst_amt, 0);
+        assert_eq!(withdrawn.total_lst_minted, 700_000);
+        assert_eq!(withdrawn.total_utoken_staked, 700_000);
+        assert_eq!(withdrawn.exchange_rate, exchange_rate);
+
+        // failed instructions emit nothing
+        assert!(env.stake(&user, 1_000_000_000).is_err());
+        assert!(env.svm.events::<Staked>().is_empty());
+    }
+}
+
+#[test]
+fn test_admin_events() {
+    let mut env = Env::with_vault(spl_token::ID);
+    let vault = env.vault;
+
+    env.update_fees_bps(200).unwrap();
+    let fees_bps_updated: FeesBpsUpdated = env.svm.event();
+    assert_eq!(fees_bps_updated.vault, vault);
+    assert_eq!(fees_bps_updated.fees_bps, 200);
+
+    env.set_paused(true).unwrap();
+    let pause_set: VaultPauseSet = env.svm.event();
+    assert_eq!((pause_set.vault, pause_set.paused), (vault, true));
+    env.set_paused(false).unwrap();
+
+    env.set_lock_policy(LockPolicy::Duration(86_400)).unwrap();
+    let lock_policy_set: LockPolicySet = env.svm.event();
+    assert_eq!(lock_policy_set.vault, vault);
+    assert_eq!(lock_policy_set.lock_policy, LockPolicy::Duration(86_400));
+    env.extend_lock_duration(86_400).unwrap();
+    let lock_policy_set: LockPolicySet = env.svm.event();
+    assert_eq!(lock_policy_set.lock_policy, LockPolicy::Duration(172_800));
+
+    let new_delegate = Pubkey::new_unique();
+    env.set_vote_delegate(&new_delegate).unwrap();
+    let vote_delegate_set: VoteDelegateSet = env.svm.event();
+    assert_eq!(vote_delegate_set.vault, vault);
+    assert_eq!(vote_delegate_set.vote_delegate, new_delegate);
+
+    env.configure_buffer(1_000, 50).unwrap();
+    let buffer_configured: BufferConfigured = env.svm.event();
+    assert_eq!(buffer_configured.vault, vault);
+    assert_eq!(buffer_configured.buffer_bps, 1_000);
+    assert_eq!(buffer_configured.instant_unstake_fee_bps, 50);
+
+    let staker = env.new_user(1_000_000);
+    let user = env.new_user(1_000_000);
+    env.stake(&staker, 1_000_000).unwrap();
+    env.stake_with_buffer(&user, 1_000_000).unwrap();
+    env.instant_unstake(&user, 10_000).unwrap();
+    env.refill_buffer().unwrap();
+    let refill_begun: BufferRefillBegun = env.svm.event();
+    let vault_state = env.vault_state();
+    assert_eq!(refill_begun.vault, vault);
+    assert_eq!(refill_begun.buffer_refill, env.buffer_refill());
+    assert_eq!(refill_begun.utoken_amt, vault_state.buffer_refill_amt);
+    assert_eq!(refill_begun.buffer_utoken_amt, 90_050);
+
+    // lose utoken from the escrow, then bring it back
+    let utoken_escrow_ata = env.utoken_escrow_ata;
+    let escrow_utoken_amt = env.token_balance(&utoken_escrow_ata);
+    env.set_token_balance(&utoken_escrow_ata, escrow_utoken_amt - 1_000);
+    env.sync_vault().unwrap();
+    env.set_token_balance(&utoken_escrow_ata, escrow_utoken_amt);
+    env.resolve_deficit().unwrap();
+    let deficit_resolved: DeficitResolved = env.svm.event();
+    let vault_state = env.vault_state();
+    assert_eq!(deficit_resolved.vault, vault);
+    assert_eq!(
+        deficit_resolved.escrow_utoken_amt,
+        env.escrow_state().amount.min(escrow_utoken_amt)
+    );
+    assert_eq!(
+        deficit_resolved.total_utoken_staked,
+        vault_state.total_utoken_staked
+    );
+    assert_eq!(
+        deficit_resolved.total_lst_minted,
+        vault_state.total_lst_minted
+    );
+    assert_eq!(
+        deficit_resolved.exchange_rate,
+        vault_state.get_exchange_rate().unwrap()
+    );
+
+    let new_owner = Pubkey::new_unique();
+    env.transfer_ownership(&new_owner).unwrap();
+    let transfer_started: OwnershipTransferStarted = env.svm.event();
+    assert_eq!(transfer_started.vault, vault);
+    assert_eq!(transfer_started.owner, env.vault_owner);
+    assert_eq!(transfer_started.pending_owner, new_owner);
+
+    env.svm.airdrop(&new_owner, 1_000_000_000);
+    env.accept_ownership(&new_owner).unwrap();
+    let accepted: OwnershipAccepted = env.svm.event();
+    assert_eq!(accepted.vault, vault);
+    assert_eq!(accepted.previous_owner, env.vault_owner);
+    assert_eq!(accepted.owner, new_owner);
+    assert_eq!(env.vault_state().owner, new_owner);
+}
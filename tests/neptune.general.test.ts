@@ -33,7 +33,11 @@ describe("neptune", () => {
       // systemProgram: SystemProgram.programId,
     };
     const tx = await program.methods
-      .createVault(100)
+      .createVault(100, null, {
+        name: "Neptune JUP",
+        symbol: "nJUP",
+        uri: "",
+      })
       .accounts(accounts)
       .signers([player])
       .rpc({ skipPreflight: true });